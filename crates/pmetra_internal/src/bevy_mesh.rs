@@ -1,8 +1,13 @@
+use std::hash::{Hash, Hasher};
+
 use bevy::{
-    asset::RenderAssetUsages, mesh::Indices, prelude::*, render::render_resource::PrimitiveTopology,
+    asset::RenderAssetUsages, mesh::Indices, platform::collections::HashMap, prelude::*,
+    render::render_resource::PrimitiveTopology,
 };
 use truck_meshalgo::rexport_polymesh::PolygonMesh;
 
+use crate::constants::MESH_WELD_TOLERANCE;
+
 /// Indices type used for Bevy [`Mesh`].
 pub type BevyIndices = Vec<u32>;
 
@@ -28,6 +33,121 @@ impl BevyMeshBuilder {
     pub fn build(self) -> Mesh {
         Mesh::from(self)
     }
+
+    /// Recompute the normals as per the [`CadNormalMode`].
+    ///
+    /// Vertices with the same position, normal and uv are welded, and split where these differ
+    /// (eg: at creases). Order of the triangles is kept.
+    pub fn with_normal_mode(self, normal_mode: CadNormalMode) -> Self {
+        let cos_crease_angle = match normal_mode {
+            CadNormalMode::Tessellated => return self,
+            CadNormalMode::Flat => None,
+            CadNormalMode::Smooth { crease_angle } => Some(crease_angle.cos()),
+        };
+        let weld_key = |value: [f32; 3]| value.map(|v| (v / MESH_WELD_TOLERANCE).round() as i64);
+        // Area weighted normals of the triangles...
+        let triangle_normals = self
+            .indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(self.vertices[triangle[i] as usize]));
+                (b - a).cross(c - a)
+            })
+            .collect::<Vec<_>>();
+        // Triangles around each position (across the faces of the shell)...
+        let mut triangles_by_position: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+        for (corner, index) in self.indices.iter().enumerate() {
+            triangles_by_position
+                .entry(weld_key(self.vertices[*index as usize]))
+                .or_default()
+                .push(corner / 3);
+        }
+
+        let mut builder = BevyMeshBuilder {
+            vertices: vec![],
+            indices: vec![],
+            normals: vec![],
+            uvs: vec![],
+        };
+        let mut welded_indices: HashMap<([i64; 3], [i64; 3], [u32; 2]), u32> = HashMap::new();
+        for (corner, index) in self.indices.iter().enumerate() {
+            let index = *index as usize;
+            let position = self.vertices[index];
+            let position_key = weld_key(position);
+            let triangle_normal = triangle_normals[corner / 3].normalize_or_zero();
+            let normal = match cos_crease_angle {
+                None => triangle_normal,
+                // Average the normals of the adjacent triangles within the crease angle...
+                Some(cos_crease_angle) => triangles_by_position[&position_key]
+                    .iter()
+                    .map(|triangle| triangle_normals[*triangle])
+                    .filter(|normal| {
+                        normal.normalize_or_zero().dot(triangle_normal) >= cos_crease_angle
+                    })
+                    .sum::<Vec3>()
+                    .normalize_or_zero(),
+            };
+            // Keep the tessellated normal for degenerate triangles...
+            let normal = if normal == Vec3::ZERO {
+                self.normals[index]
+            } else {
+                normal.to_array()
+            };
+            let uv = self.uvs.get(index).copied().unwrap_or_default();
+            let welded_index = *welded_indices
+                .entry((position_key, weld_key(normal), uv.map(f32::to_bits)))
+                .or_insert_with(|| {
+                    builder.vertices.push(position);
+                    builder.normals.push(normal);
+                    builder.uvs.push(uv);
+                    builder.vertices.len() as u32 - 1
+                });
+            builder.indices.push(welded_index);
+        }
+
+        builder
+    }
+}
+
+/// Mode of computing the normals of a mesh. Refer [`BevyMeshBuilder::with_normal_mode`].
+///
+/// Compared and hashed by the bits of the `crease_angle` (eg: for caching the meshes).
+#[derive(Debug, Clone, Copy, Default)]
+pub enum CadNormalMode {
+    /// Normals as tessellated from the surfaces, ie: smooth within a face and split between faces.
+    #[default]
+    Tessellated,
+    /// Flat shading with the normals of the triangles. Suited for mechanical parts.
+    Flat,
+    /// Smooth shading across the triangles (and faces) meeting at an angle below the `crease_angle` (in radians).
+    ///
+    /// Suited for organic shapes, eg: tangent NURBS surfaces.
+    Smooth { crease_angle: f32 },
+}
+
+impl PartialEq for CadNormalMode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Smooth { crease_angle },
+                Self::Smooth {
+                    crease_angle: other_crease_angle,
+                },
+            ) => crease_angle.to_bits() == other_crease_angle.to_bits(),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for CadNormalMode {}
+
+impl Hash for CadNormalMode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let Self::Smooth { crease_angle } = self {
+            crease_angle.to_bits().hash(state);
+        }
+    }
 }
 
 impl From<BevyMeshBuilder> for Mesh {
@@ -78,3 +198,77 @@ impl From<&PolygonMesh> for BevyMeshBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn build_folded_quad() -> BevyMeshBuilder {
+        // Two triangles (of separate faces) folded at 90 degrees along the shared edge...
+        BevyMeshBuilder {
+            vertices: vec![
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [0., 0., -1.],
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [0., 1., 0.],
+            ],
+            indices: vec![0, 1, 2, 3, 4, 5],
+            normals: vec![[0., 1., 0.]; 3]
+                .into_iter()
+                .chain(vec![[0., 0., 1.]; 3])
+                .collect(),
+            uvs: vec![[0., 0.]; 6],
+        }
+    }
+
+    #[test]
+    pub fn test_normal_modes() {
+        let flat = build_folded_quad().with_normal_mode(CadNormalMode::Flat);
+        assert_eq!(flat.vertices.len(), 6);
+        assert_eq!(flat.normals[0], [0., 1., 0.]);
+        assert_eq!(flat.normals[3], [0., 0., 1.]);
+
+        // Creases sharper than the crease angle are kept...
+        let creased = build_folded_quad().with_normal_mode(CadNormalMode::Smooth {
+            crease_angle: 45_f32.to_radians(),
+        });
+        assert_eq!(creased.vertices.len(), 6);
+
+        // Shared edge is welded and smoothed otherwise...
+        let smooth = build_folded_quad().with_normal_mode(CadNormalMode::Smooth {
+            crease_angle: 100_f32.to_radians(),
+        });
+        assert_eq!(smooth.vertices.len(), 4);
+        assert_eq!(smooth.indices.len(), 6);
+        assert!(Vec3::from(smooth.normals[smooth.indices[0] as usize])
+            .abs_diff_eq(Vec3::new(0., 1., 1.).normalize(), 1e-6));
+    }
+
+    #[test]
+    pub fn test_normal_mode_eq_hash() {
+        let hash = |normal_mode: CadNormalMode| {
+            let mut hasher = std::hash::DefaultHasher::new();
+            normal_mode.hash(&mut hasher);
+            hasher.finish()
+        };
+        let smooth = |crease_angle: f32| CadNormalMode::Smooth { crease_angle };
+        for (a, b, is_eq) in [
+            (smooth(0.5), smooth(0.5), true),
+            (smooth(0.5), smooth(0.6), false),
+            (smooth(0.), smooth(-0.), false),
+            (smooth(f32::NAN), smooth(f32::NAN), true),
+            (CadNormalMode::Flat, CadNormalMode::Flat, true),
+            (CadNormalMode::Flat, CadNormalMode::Tessellated, false),
+        ] {
+            assert_eq!(a == b, is_eq, "{:?} == {:?}", a, b);
+            // Equal modes have equal hashes...
+            if is_eq {
+                assert_eq!(hash(a), hash(b));
+            }
+        }
+    }
+}
@@ -2,9 +2,21 @@
 pub const CUSTOM_TRUCK_TOLERANCE_1: f64 = 1e-3;
 /// Tolerance const value for fast triangulation.
 pub const FAST_TRIANGULATION_TOL_1: f64 = 1e-2;
+/// Max difference of the positions (and normals) of welded mesh vertices.
+pub const MESH_WELD_TOLERANCE: f32 = 1e-5;
 /// Tolerance used for boolean ops.
 pub const TRUCK_BOOLEAN_TOLERANCE_1: f64 = 0.05;
+/// Looser tolerance used for retrying failed boolean ops.
+pub const TRUCK_BOOLEAN_RETRY_TOLERANCE_1: f64 = 0.1;
+/// Max trials for searching the parameters of points on surfaces.
+pub const SURFACE_SEARCH_TRIALS: usize = 100;
+/// Max residual of solved sketch constraints.
+pub const SKETCH_SOLVER_TOLERANCE: f64 = 1e-9;
+/// Max iterations of the sketch constraints solver.
+pub const SKETCH_SOLVER_MAX_ITERATIONS: usize = 100;
 /// Space between interactive face and real face.
 pub const INTERACTIVE_FACE_PADDING: f32 = 0.001;
+/// Bisection iterations for clamping an invalid slider edit to the last valid value along the drag.
+pub const SLIDER_CLAMP_ITERATIONS: usize = 8;
 /// Params UI bottom shift px.
 pub const PARAMS_UI_BOTTOM_SHIFT_PX: f32 = 20.;
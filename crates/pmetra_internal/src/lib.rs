@@ -12,23 +12,47 @@ pub mod math;
 pub mod pmetra_core;
 /// Plugin(s) for integrating with [`bevy`].
 pub mod pmetra_plugins;
+/// Fixtures shared by the tests.
+#[cfg(test)]
+mod test_utils;
 
 /// Commonly imported/prelude modules.
 pub mod prelude {
     use super::*;
 
     pub use {
-        bevy_mesh::BevyMeshBuilder,
+        bevy_mesh::{BevyMeshBuilder, CadNormalMode},
         constants::*,
-        pmetra_core::{builders::*, centroid::CadCentroid, dimensions::*, meshing::*},
+        pmetra_core::{builders::*, centroid::CadCentroid, dimensions::*, meshing::*, sketch::*},
         pmetra_plugins::{
             components::cad::*, components::camera::*, components::wire_frame::*, events::cad::*,
             plugins::*, resources::PmetraGlobalSettings,
         },
     };
+
+    #[cfg(feature = "serde")]
+    pub use pmetra_plugins::document::{CadDocument, CadDocumentEntry, CadDocumentFormat};
+
+    #[cfg(feature = "gltf")]
+    pub use pmetra_plugins::gltf_export::CadGltfBuilder;
+
+    /// Derive macros for the Pmetra traits.
+    ///
+    /// The generated code refers to `::bevy_pmetra`. Add `#[pmetra(crate = ::pmetra_internal)]`
+    /// when depending on this crate directly.
+    pub use pmetra_macros::{PmetraCad, PmetraInteractions, PmetraModelling};
 }
 
 /// Re-exported library modules. (incl truck modules).
 pub mod re_exports {
-    pub use {anyhow, truck_meshalgo, truck_modeling, truck_shapeops, truck_topology};
+    pub use {anyhow, bevy, truck_meshalgo, truck_modeling, truck_shapeops, truck_topology};
+
+    #[cfg(feature = "serde")]
+    pub use {ron, serde, serde_json};
+
+    #[cfg(feature = "step")]
+    pub use truck_stepio;
+
+    #[cfg(feature = "gltf")]
+    pub use gltf;
 }
@@ -0,0 +1,333 @@
+use anyhow::{anyhow, Result};
+use bevy::math::DVec3;
+use truck_meshalgo::prelude::TOLERANCE;
+use truck_modeling::{Curve, Face};
+
+use crate::pmetra_core::{
+    dimensions::AsBevyDVec3,
+    meshing::BuildPolygon,
+    sketch::{Sketch, SketchPlane},
+};
+
+use super::{CadElement, CadElementTag, CadShell, CadSolid};
+
+/// Blend (rounding/beveling) of an edge of [`CadSolid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CadEdgeBlend {
+    /// Round the edge with constant radius.
+    Fillet(f64),
+    /// Bevel the edge with constant distance (along both faces).
+    Chamfer(f64),
+}
+
+impl CadEdgeBlend {
+    /// Suffix of the tag of the blend face.
+    fn tag_suffix(&self) -> &'static str {
+        match self {
+            CadEdgeBlend::Fillet(_) => "Fillet",
+            CadEdgeBlend::Chamfer(_) => "Chamfer",
+        }
+    }
+}
+
+impl CadSolid {
+    /// Fillet the tagged edges with `radius`. Refer [`Self::blend_edges`].
+    pub fn fillet_edges(&self, edge_tags: &[CadElementTag], radius: f64) -> Result<Self> {
+        self.blend_edges(edge_tags, CadEdgeBlend::Fillet(radius))
+    }
+
+    /// Chamfer the tagged edges with `distance`. Refer [`Self::blend_edges`].
+    pub fn chamfer_edges(&self, edge_tags: &[CadElementTag], distance: f64) -> Result<Self> {
+        self.blend_edges(edge_tags, CadEdgeBlend::Chamfer(distance))
+    }
+
+    /// Blend the tagged edges (one by one), by cutting away the material at the edge (boolean difference).
+    ///
+    /// Supports straight edges between 2 planar faces at convex corners, ending at the solid boundary,
+    /// eg: side edges of an extruded profile (tagged by [`SketchProfile::extrude`](crate::pmetra_core::sketch::SketchProfile::extrude)).
+    /// Edges should not share vertices, as blending one changes the other.
+    ///
+    /// The new blend faces are tagged as `{edge_tag}Fillet`/`{edge_tag}Chamfer`.
+    pub fn blend_edges(&self, edge_tags: &[CadElementTag], blend: CadEdgeBlend) -> Result<Self> {
+        let mut cad_solid = self.clone();
+        for edge_tag in edge_tags {
+            cad_solid = cad_solid.blend_edge(edge_tag, blend)?;
+        }
+        Ok(cad_solid)
+    }
+
+    fn blend_edge(&self, edge_tag: &CadElementTag, blend: CadEdgeBlend) -> Result<Self> {
+        let (CadEdgeBlend::Fillet(size) | CadEdgeBlend::Chamfer(size)) = blend;
+        if !size.is_finite() || size < TOLERANCE {
+            return Err(anyhow!("Invalid blend {:?} for edge {:?}", blend, edge_tag));
+        }
+        let Some(CadElement::Edge(edge)) = self.get_element_by_tag(edge_tag.clone()) else {
+            return Err(anyhow!("Could not find edge with tag: {:?}", edge_tag));
+        };
+        if !matches!(edge.curve(), Curve::Line(_)) {
+            return Err(anyhow!("Edge {:?} is not straight!", edge_tag));
+        }
+        let adjacent_faces = self
+            .solid
+            .face_iter()
+            .filter(|face| {
+                face.edge_iter()
+                    .any(|face_edge| face_edge.id() == edge.id())
+            })
+            .collect::<Vec<_>>();
+        let [face_a, face_b] = adjacent_faces.as_slice() else {
+            return Err(anyhow!(
+                "Edge {:?} should have 2 adjacent faces, found: {}",
+                edge_tag,
+                adjacent_faces.len()
+            ));
+        };
+        let (start, end) = (
+            edge.front().point().as_bevy_dvec3(),
+            edge.back().point().as_bevy_dvec3(),
+        );
+        let length = start.distance(end);
+        let edge_dir = (end - start)
+            .try_normalize()
+            .ok_or_else(|| anyhow!("Edge {:?} has zero length!", edge_tag))?;
+        let (normal_a, inward_a) = face_frame(face_a, start, end)?;
+        let (normal_b, inward_b) = face_frame(face_b, start, end)?;
+        if normal_a.dot(inward_b) > -TOLERANCE {
+            return Err(anyhow!("Edge {:?} is not at a convex corner!", edge_tag));
+        }
+
+        // Cutter profile in the plane perpendicular to the edge (moved back from its start by a margin),
+        // extending out of the faces by the margin to avoid coplanar faces in the boolean op...
+        let angle = inward_a.angle_between(inward_b);
+        let trim = match blend {
+            CadEdgeBlend::Fillet(radius) => radius / (angle / 2.).tan(),
+            CadEdgeBlend::Chamfer(distance) => distance,
+        };
+        if trim < TOLERANCE {
+            return Err(anyhow!("Invalid blend {:?} for edge {:?}", blend, edge_tag));
+        }
+        let margin = trim;
+        let corner = start - edge_dir * margin;
+        let plane = SketchPlane::new(corner, inward_a, edge_dir.cross(inward_a))?;
+        let (a, b) = (corner + inward_a * trim, corner + inward_b * trim);
+        let mut sketch = Sketch::new(&format!("{}Cutter", edge_tag.0), plane)
+            .move_to(plane.to_sketch(a + normal_a * margin))?
+            .line_to(plane.to_sketch(a))?;
+        match blend {
+            CadEdgeBlend::Fillet(radius) => {
+                let bisector = (inward_a + inward_b).normalize();
+                let center = corner + bisector * radius / (angle / 2.).sin();
+                sketch.arc_to(
+                    plane.to_sketch(center - bisector * radius),
+                    plane.to_sketch(b),
+                )?;
+            }
+            CadEdgeBlend::Chamfer(_) => {
+                sketch.line_to(plane.to_sketch(b))?;
+            }
+        }
+        let blend_edge_tag = CadElementTag::new("BlendEdge");
+        let cutter_shell = sketch
+            .tag_last(blend_edge_tag.clone())?
+            .line_to(plane.to_sketch(b + normal_b * margin))?
+            .line_to(plane.to_sketch(corner + (normal_a + normal_b) * margin))?
+            .close()?
+            .build()?
+            .extrude(length + 2. * margin)?;
+
+        // Tag the face swept from the blend edge (ie: not the cap faces)...
+        let Some(CadElement::Edge(cutter_blend_edge)) =
+            cutter_shell.get_element_by_tag(blend_edge_tag)
+        else {
+            return Err(anyhow!("Could not find blend edge of cutter!"));
+        };
+        let blend_face = cutter_shell
+            .shell
+            .face_iter()
+            .filter(|face| {
+                face.edge_iter()
+                    .any(|face_edge| face_edge.id() == cutter_blend_edge.id())
+            })
+            .find(|face| {
+                face_normal(face)
+                    .map(|normal| normal.dot(edge_dir).abs() < 0.5)
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| anyhow!("Could not find blend face of cutter!"))?;
+        let blend_face_tag = CadElementTag(format!("{}{}", edge_tag.0, blend.tag_suffix()));
+        let cutter = CadSolid::try_from(CadShell {
+            shell: cutter_shell.shell.clone(),
+            tagged_elements: Default::default(),
+        })?
+        .set_tag(blend_face_tag, CadElement::Face(blend_face))?;
+
+        self.difference(&cutter)
+    }
+}
+
+/// Get the normal of the (planar) face from its first triangle.
+fn face_normal(face: &Face) -> Result<DVec3> {
+    let polygon = face.build_polygon()?;
+    let tri_face = polygon
+        .tri_faces()
+        .first()
+        .ok_or_else(|| anyhow!("Face has no triangles!"))?;
+    let [p0, p1, p2] = tri_face.map(|v| polygon.positions()[v.pos].as_bevy_dvec3());
+    (p1 - p0)
+        .cross(p2 - p0)
+        .try_normalize()
+        .ok_or_else(|| anyhow!("Face has degenerate triangles!"))
+}
+
+/// Get the (outward) normal of the planar face and the direction (perpendicular to the edge)
+/// pointing into the face from the edge.
+fn face_frame(face: &Face, edge_start: DVec3, edge_end: DVec3) -> Result<(DVec3, DVec3)> {
+    let normal = face_normal(face)?;
+    let inward = (edge_end - edge_start).cross(normal).normalize();
+    // Triangle closest to the edge is on the inner side of the face...
+    let polygon = face.build_polygon()?;
+    let edge_mid = (edge_start + edge_end) / 2.;
+    let closest_centroid = polygon
+        .tri_faces()
+        .iter()
+        .map(|tri_face| {
+            tri_face
+                .iter()
+                .map(|v| polygon.positions()[v.pos].as_bevy_dvec3())
+                .sum::<DVec3>()
+                / 3.
+        })
+        .min_by(|a, b| a.distance(edge_mid).total_cmp(&b.distance(edge_mid)))
+        .ok_or_else(|| anyhow!("Face has no triangles!"))?;
+    let inward = if inward.dot(closest_centroid - edge_start) < 0. {
+        -inward
+    } else {
+        inward
+    };
+    Ok((normal, inward))
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::test_utils::face_count;
+    #[allow(unused_imports)]
+    use bevy::math::DVec2;
+
+    #[allow(dead_code)]
+    fn box_solid() -> CadSolid {
+        let cad_shell = Sketch::new("Box", SketchPlane::xy())
+            .add_rect(DVec2::ZERO, DVec2::new(1., 0.6))
+            .unwrap()
+            .build()
+            .unwrap()
+            .extrude(0.4)
+            .unwrap();
+        CadSolid::try_from(cad_shell).unwrap()
+    }
+
+    #[test]
+    pub fn test_fillet_and_chamfer_box_side_edges() {
+        let cad_solid = box_solid();
+        assert_eq!(face_count(&cad_solid), 6);
+
+        // Each blend adds a face...
+        let cad_solid = cad_solid
+            .fillet_edges(
+                &[
+                    CadElementTag::new("BoxSideEdge0"),
+                    CadElementTag::new("BoxSideEdge2"),
+                ],
+                0.1,
+            )
+            .unwrap();
+        assert_eq!(face_count(&cad_solid), 8);
+        let cad_solid = cad_solid
+            .chamfer_edges(
+                &[
+                    CadElementTag::new("BoxSideEdge1"),
+                    CadElementTag::new("BoxSideEdge3"),
+                ],
+                0.05,
+            )
+            .unwrap();
+        assert_eq!(face_count(&cad_solid), 10);
+
+        for tag in [
+            "BoxSideEdge0Fillet",
+            "BoxSideEdge2Fillet",
+            "BoxSideEdge1Chamfer",
+            "BoxSideEdge3Chamfer",
+        ] {
+            assert!(
+                matches!(
+                    cad_solid.get_element_by_tag(CadElementTag::new(tag)),
+                    Some(CadElement::Face(_))
+                ),
+                "Missing blend face: {}",
+                tag
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_blend_edges_errors() {
+        // Non straight edge...
+        let cad_shell = Sketch::new("Cylinder", SketchPlane::xy())
+            .add_circle(DVec2::ZERO, 0.5)
+            .unwrap()
+            .build()
+            .unwrap()
+            .extrude(1.)
+            .unwrap();
+        let error = CadSolid::try_from(cad_shell)
+            .unwrap()
+            .fillet_edges(&[CadElementTag::new("CylinderEdge0")], 0.1)
+            .unwrap_err();
+        assert!(error.to_string().contains("not straight"), "{}", error);
+
+        // Concave edge (at the inner corner of an L profile)...
+        let cad_shell = Sketch::new("L", SketchPlane::xy())
+            .add_polygon(&[
+                DVec2::new(0., 0.),
+                DVec2::new(2., 0.),
+                DVec2::new(2., 1.),
+                DVec2::new(1., 1.),
+                DVec2::new(1., 2.),
+                DVec2::new(0., 2.),
+            ])
+            .unwrap()
+            .build()
+            .unwrap()
+            .extrude(1.)
+            .unwrap();
+        let error = CadSolid::try_from(cad_shell)
+            .unwrap()
+            .fillet_edges(&[CadElementTag::new("LSideEdge3")], 0.1)
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("not at a convex corner"),
+            "{}",
+            error
+        );
+
+        // Zero, negative or non-finite radius/distance...
+        for size in [0., -0.1, f64::NAN, f64::INFINITY] {
+            let error = box_solid()
+                .fillet_edges(&[CadElementTag::new("BoxSideEdge0")], size)
+                .unwrap_err();
+            assert!(error.to_string().contains("Invalid blend"), "{}", error);
+            let error = box_solid()
+                .chamfer_edges(&[CadElementTag::new("BoxSideEdge0")], size)
+                .unwrap_err();
+            assert!(error.to_string().contains("Invalid blend"), "{}", error);
+        }
+
+        // Unknown edge...
+        assert!(box_solid()
+            .fillet_edges(&[CadElementTag::new("Unknown")], 0.1)
+            .is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use std::ops::Range;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use truck_modeling::Surface;
+use truck_topology::FaceID;
+
+use super::{CadElement, CadElementTag, CadShell};
+
+/// Maps the triangles of a built [`Mesh`] back to the B-rep faces of the [`CadShell`].
+#[derive(Debug, Clone, Default)]
+pub struct CadMeshFaces {
+    /// Triangle index ranges (of the mesh) per face, in order.
+    pub triangle_ranges: Vec<(Range<usize>, FaceID<Surface>)>,
+    /// Tags of the tagged faces of the shell.
+    pub tags_by_face_id: HashMap<FaceID<Surface>, CadElementTag>,
+}
+
+impl CadMeshFaces {
+    /// Create new [`CadMeshFaces`] with the face tags of the [`CadShell`].
+    pub fn new(cad_shell: &CadShell) -> Self {
+        let tags_by_face_id = cad_shell
+            .tagged_elements
+            .iter()
+            .filter_map(|(tag, element)| match element {
+                CadElement::Face(face) => Some((face.id(), tag.clone())),
+                _ => None,
+            })
+            .collect();
+        Self {
+            triangle_ranges: vec![],
+            tags_by_face_id,
+        }
+    }
+
+    /// Get the face the triangle (at index) belongs to.
+    pub fn face_at_triangle(&self, triangle_index: usize) -> Option<FaceID<Surface>> {
+        let idx = self
+            .triangle_ranges
+            .partition_point(|(range, _)| range.end <= triangle_index);
+        let (range, face_id) = self.triangle_ranges.get(idx)?;
+        range.contains(&triangle_index).then_some(*face_id)
+    }
+
+    /// Get the tag of the face (if tagged).
+    pub fn tag(&self, face_id: FaceID<Surface>) -> Option<&CadElementTag> {
+        self.tags_by_face_id.get(&face_id)
+    }
+
+    /// Get the id of the face with tag.
+    pub fn face_id_by_tag(&self, tag: &CadElementTag) -> Option<FaceID<Surface>> {
+        self.tags_by_face_id
+            .iter()
+            .find_map(|(face_id, cur_tag)| (cur_tag == tag).then_some(*face_id))
+    }
+}
+
+/// Hit on a face of a [`CadGeneratedMesh`].
+///
+/// Passed to [`PmetraInteractions::on_face_click`] and [`PmetraInteractions::on_face_hover`].
+#[derive(Debug, Clone)]
+pub struct CadFaceHit {
+    /// Id of the B-rep face in the shell the mesh was built from.
+    ///
+    /// Prefer the [`Self::tag`] for identifying faces, as ids change when the shell is rebuilt.
+    pub face_id: FaceID<Surface>,
+    /// Tag of the face (if tagged).
+    pub tag: Option<CadElementTag>,
+    /// Hit point in world space.
+    pub position: Vec3,
+    /// Normal at the hit point in world space.
+    pub normal: Vec3,
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::pmetra_core::builders::CadTaggedElements;
+    #[allow(unused_imports)]
+    use truck_modeling::{builder, Point3, Vector3};
+
+    #[test]
+    pub fn test_face_at_triangle() {
+        let v = builder::vertex(Point3::new(0., 0., 0.));
+        let e = builder::tsweep(&v, Vector3::unit_x());
+        let f = builder::tsweep(&e, Vector3::unit_y());
+        let solid = builder::tsweep(&f, Vector3::unit_z());
+        let shell = solid.into_boundaries().pop().unwrap();
+        let faces = shell.face_iter().cloned().collect::<Vec<_>>();
+        let mut tagged_elements = CadTaggedElements::default();
+        tagged_elements.insert(
+            CadElementTag::new("Last"),
+            CadElement::Face(faces[2].clone()),
+        );
+        let cad_shell = CadShell {
+            shell,
+            tagged_elements,
+        };
+
+        let mut mesh_faces = CadMeshFaces::new(&cad_shell);
+        mesh_faces.triangle_ranges = vec![
+            (0..2, faces[0].id()),
+            (2..5, faces[1].id()),
+            (5..6, faces[2].id()),
+        ];
+        // First and last triangles of each range...
+        for (range, face_id) in mesh_faces.triangle_ranges.clone() {
+            assert_eq!(mesh_faces.face_at_triangle(range.start), Some(face_id));
+            assert_eq!(mesh_faces.face_at_triangle(range.end - 1), Some(face_id));
+        }
+        assert_eq!(mesh_faces.face_at_triangle(3), Some(faces[1].id()));
+        // Out of range...
+        assert_eq!(mesh_faces.face_at_triangle(6), None);
+        assert_eq!(mesh_faces.face_at_triangle(100), None);
+        assert_eq!(CadMeshFaces::default().face_at_triangle(0), None);
+        // Tags...
+        assert_eq!(
+            mesh_faces.tag(faces[2].id()),
+            Some(&CadElementTag::new("Last"))
+        );
+        assert_eq!(mesh_faces.tag(faces[0].id()), None);
+        assert_eq!(
+            mesh_faces.face_id_by_tag(&CadElementTag::new("Last")),
+            Some(faces[2].id())
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use anyhow::{anyhow, Context, Result};
+use bevy::{
+    math::{DMat3, DVec3},
+    platform::collections::{HashMap, HashSet},
+};
+use truck_meshalgo::prelude::TOLERANCE;
+use truck_modeling::{builder, Curve, Edge, Point3, Shell, Solid, Surface, Wire};
+
+use crate::pmetra_core::dimensions::{AsBevyDVec3, FromBevyDVec3};
+
+use super::{CadElement, CadElementTag, CadSolid, CadTaggedElements};
+
+impl CadSolid {
+    /// Hollow the solid into walls of `thickness` (offset inwards).
+    ///
+    /// Faces tagged with `open_face_tags` are removed to make the solid open there (replaced by rims).
+    ///
+    /// Supports solids with planar faces and straight edges, with their (offset) faces meeting
+    /// at unique points, eg: extruded polygon profiles. For curved profiles,
+    /// use [`Sketch::thicken`](crate::pmetra_core::sketch::Sketch::thicken) before extruding.
+    ///
+    /// Tags of the kept elements are carried forward. The inner faces of tagged faces are tagged as
+    /// `{tag}Inner` and the rims of open faces as `{tag}Rim`.
+    pub fn hollow(&self, thickness: f64, open_face_tags: &[CadElementTag]) -> Result<Self> {
+        if !thickness.is_finite() || thickness < TOLERANCE {
+            return Err(anyhow!("Invalid hollow thickness: {}", thickness));
+        }
+        let [outer_shell] = self.solid.boundaries().as_slice() else {
+            return Err(anyhow!(
+                "Hollow needs a solid with a single boundary shell!"
+            ));
+        };
+        let open_face_ids = open_face_tags
+            .iter()
+            .map(|tag| match self.get_element_by_tag(tag.clone()) {
+                Some(CadElement::Face(face)) => Ok(face.id()),
+                _ => Err(anyhow!("Could not find face with tag: {:?}", tag)),
+            })
+            .collect::<Result<HashSet<_>>>()?;
+
+        // Offset planes (normal, constant) of the faces, ie: `normal.dot(point) = constant`...
+        let mut offset_planes = HashMap::new();
+        for face in outer_shell.face_iter() {
+            let Surface::Plane(plane) = face.oriented_surface() else {
+                return Err(anyhow!("Hollow only supports planar faces!"));
+            };
+            let normal = plane.normal().as_bevy_dvec3().normalize();
+            let offset = if open_face_ids.contains(&face.id()) {
+                0.
+            } else {
+                thickness
+            };
+            offset_planes.insert(
+                face.id(),
+                (normal, normal.dot(plane.origin().as_bevy_dvec3()) - offset),
+            );
+        }
+
+        // Inner vertices at the intersections of the offset planes of their faces...
+        let mut inner_vertices = HashMap::new();
+        for vertex in outer_shell.vertex_iter() {
+            if inner_vertices.contains_key(&vertex.id()) {
+                continue;
+            }
+            let planes = outer_shell
+                .face_iter()
+                .filter(|face| face.vertex_iter().any(|v| v.id() == vertex.id()))
+                .map(|face| offset_planes[&face.id()])
+                .collect::<Vec<_>>();
+            let point = planes_intersection(&planes)
+                .with_context(|| format!("Could not offset vertex at: {:?}", vertex.point()))?;
+            inner_vertices.insert(vertex.id(), builder::vertex(Point3::from_bevy_dvec3(point)));
+        }
+
+        // Inner edges between the inner vertices...
+        let mut inner_edges = HashMap::new();
+        for edge in outer_shell.edge_iter() {
+            if !matches!(edge.curve(), Curve::Line(_)) {
+                return Err(anyhow!("Hollow only supports straight edges!"));
+            }
+            inner_edges.entry(edge.id()).or_insert_with(|| {
+                builder::line(
+                    &inner_vertices[&edge.absolute_front().id()],
+                    &inner_vertices[&edge.absolute_back().id()],
+                )
+            });
+        }
+        let inner_wire = |wire: &Wire| -> Wire {
+            wire.edge_iter()
+                .map(|edge| {
+                    let inner_edge: &Edge = &inner_edges[&edge.id()];
+                    if edge.orientation() {
+                        inner_edge.clone()
+                    } else {
+                        inner_edge.inverse()
+                    }
+                })
+                .collect()
+        };
+
+        let tags_by_face_id = self
+            .tagged_elements
+            .iter()
+            .filter_map(|(tag, element)| match element {
+                CadElement::Face(face) => Some((face.id(), tag.clone())),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+        let mut tagged_elements = CadTaggedElements::default();
+        for (tag, element) in self.tagged_elements.iter() {
+            if matches!(element, CadElement::Face(face) if open_face_ids.contains(&face.id())) {
+                continue;
+            }
+            tagged_elements.insert(tag.clone(), element.clone());
+        }
+
+        let mut shell = Shell::new();
+        let mut inner_shell = Shell::new();
+        for face in outer_shell.face_iter() {
+            let boundaries = face.boundaries();
+            if open_face_ids.contains(&face.id()) {
+                // Rim between the outer and inner boundary of the open face...
+                let [boundary] = boundaries.as_slice() else {
+                    return Err(anyhow!("Hollow open faces should not have holes!"));
+                };
+                let rim_face =
+                    builder::try_attach_plane(&[boundary.clone(), inner_wire(boundary).inverse()])
+                        .with_context(|| "Could not attach plane to rim!")?;
+                if let Some(tag) = tags_by_face_id.get(&face.id()) {
+                    tagged_elements.insert(
+                        CadElementTag(format!("{}Rim", tag.0)),
+                        CadElement::Face(rim_face.clone()),
+                    );
+                }
+                shell.push(rim_face);
+                continue;
+            }
+            shell.push(face.clone());
+            let inner_boundaries = boundaries.iter().map(inner_wire).collect::<Vec<_>>();
+            let mut inner_face = builder::try_attach_plane(&inner_boundaries)
+                .with_context(|| "Could not attach plane to inner face!")?;
+            // Inner faces point into the hollow...
+            inner_face.invert();
+            if let Some(tag) = tags_by_face_id.get(&face.id()) {
+                tagged_elements.insert(
+                    CadElementTag(format!("{}Inner", tag.0)),
+                    CadElement::Face(inner_face.clone()),
+                );
+            }
+            inner_shell.push(inner_face);
+        }
+        let shells = if open_face_ids.is_empty() {
+            vec![shell, inner_shell]
+        } else {
+            shell.extend(inner_shell);
+            vec![shell]
+        };
+        let solid = Solid::try_new(shells)
+            .map_err(|e| anyhow!("Could not create hollow solid: {:?}", e))?;
+
+        Ok(Self {
+            solid,
+            tagged_elements,
+        })
+    }
+}
+
+/// Get the (least squares) intersection point of the planes (normal, constant).
+///
+/// Fails if the planes do not meet at a unique point.
+fn planes_intersection(planes: &[(DVec3, f64)]) -> Result<DVec3> {
+    let (lhs, rhs) = planes.iter().fold(
+        (DMat3::ZERO, DVec3::ZERO),
+        |(lhs, rhs), (normal, constant)| {
+            (
+                lhs + DMat3::from_cols(*normal * normal.x, *normal * normal.y, *normal * normal.z),
+                rhs + *normal * *constant,
+            )
+        },
+    );
+    if lhs.determinant().abs() < TOLERANCE {
+        return Err(anyhow!("Planes do not meet at a point!"));
+    }
+    let point = lhs.inverse() * rhs;
+    if planes
+        .iter()
+        .any(|(normal, constant)| (normal.dot(point) - constant).abs() > TOLERANCE * 10.)
+    {
+        return Err(anyhow!("Planes do not meet at a unique point!"));
+    }
+
+    Ok(point)
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::pmetra_core::sketch::{Sketch, SketchPlane};
+    use crate::test_utils::face_count;
+    #[allow(unused_imports)]
+    use bevy::math::DVec2;
+
+    /// Unit cube with its top face tagged as `Top`.
+    fn cube_with_top_tag() -> CadSolid {
+        let cad_shell = Sketch::new("Cube", SketchPlane::xy())
+            .add_rect(DVec2::ZERO, DVec2::ONE)
+            .unwrap()
+            .build()
+            .unwrap()
+            .extrude(1.)
+            .unwrap();
+        let mut cad_solid = CadSolid::try_from(cad_shell).unwrap();
+        let top_face = cad_solid
+            .solid
+            .face_iter()
+            .find(|face| {
+                face.vertex_iter()
+                    .all(|v| (v.point().z - 1.).abs() < TOLERANCE)
+            })
+            .unwrap()
+            .clone();
+        cad_solid
+            .set_tag(CadElementTag::new("Top"), CadElement::Face(top_face))
+            .unwrap()
+    }
+
+    #[test]
+    pub fn test_hollow_closed_cube() {
+        let cad_solid = cube_with_top_tag().hollow(0.1, &[]).unwrap();
+        assert_eq!(cad_solid.solid.boundaries().len(), 2);
+        assert_eq!(face_count(&cad_solid), 12);
+        assert!(matches!(
+            cad_solid.get_element_by_tag(CadElementTag::new("Top")),
+            Some(CadElement::Face(_))
+        ));
+        let Some(CadElement::Face(top_inner)) =
+            cad_solid.get_element_by_tag(CadElementTag::new("TopInner"))
+        else {
+            panic!("Missing inner face of the top face!");
+        };
+        assert!(top_inner
+            .vertex_iter()
+            .all(|v| (v.point().z - 0.9).abs() < TOLERANCE));
+    }
+
+    #[test]
+    pub fn test_hollow_open_top_cube() {
+        let cad_solid = cube_with_top_tag()
+            .hollow(0.1, &[CadElementTag::new("Top")])
+            .unwrap();
+        assert_eq!(cad_solid.solid.boundaries().len(), 1);
+        // 5 outer + 5 inner faces + the rim...
+        assert_eq!(face_count(&cad_solid), 11);
+        assert!(cad_solid
+            .get_element_by_tag(CadElementTag::new("Top"))
+            .is_none());
+        assert!(cad_solid
+            .get_element_by_tag(CadElementTag::new("TopInner"))
+            .is_none());
+        let Some(CadElement::Face(top_rim)) =
+            cad_solid.get_element_by_tag(CadElementTag::new("TopRim"))
+        else {
+            panic!("Missing rim face of the top face!");
+        };
+        assert_eq!(top_rim.boundaries().len(), 2);
+    }
+
+    #[test]
+    pub fn test_hollow_errors() {
+        for thickness in [0., -0.1, f64::NAN, f64::INFINITY] {
+            let error = cube_with_top_tag().hollow(thickness, &[]).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("Invalid hollow thickness: {}", thickness)
+            );
+        }
+        assert!(cube_with_top_tag()
+            .hollow(0.1, &[CadElementTag::new("Unknown")])
+            .is_err());
+        // Already hollow (2 boundary shells)...
+        assert!(cube_with_top_tag()
+            .hollow(0.1, &[])
+            .unwrap()
+            .hollow(0.05, &[])
+            .is_err());
+    }
+}
@@ -1,17 +1,27 @@
 use anyhow::{anyhow, Result};
-use bevy::{platform::collections::HashMap, prelude::*};
-use truck_meshalgo::tessellation::{MeshableShape, MeshedShape};
-use truck_modeling::{builder, Shell};
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use truck_meshalgo::{
+    filters::OptimizingFilter,
+    rexport_polymesh::PolygonMesh,
+    tessellation::{MeshableShape, MeshedShape},
+};
+use truck_modeling::{builder, Shell, Surface};
+use truck_topology::FaceID;
 
 use crate::{
+    bevy_mesh::{BevyMeshBuilder, CadNormalMode},
     constants::CUSTOM_TRUCK_TOLERANCE_1,
     pmetra_core::{
         dimensions::AsBevyVec3,
-        meshing::{BuildBevyMesh, BuildPolygon},
+        meshing::BuildCadMeshedShell,
+        tessellation::{CadMeshedShell, MeshedFace, MeshedShell},
     },
 };
 
-use super::{CadShellName, CadShellsByName};
+use super::{CadElementTag, CadMeshFaces, CadMeshPattern, CadShellName, CadShellsByName};
 
 #[derive(Debug, Clone, Default)]
 pub struct CadMeshesBuildersByCadShell<P: Default + Clone> {
@@ -55,6 +65,59 @@ impl<P: Default + Clone> CadMeshesBuildersByCadShell<P> {
         self.add_mesh_builder_internal(shell_name, mesh_name, mesh_builder, false)
     }
 
+    /// Add [`CadMeshBuilder`]s for all the instances of the [`CadMeshPattern`].
+    ///
+    /// Instances are named `{mesh_name}{index}` and placed by the pattern transforms (on top of the builder's transform).
+    /// All instances share the same mesh (and materials) as the shell is tessellated once.
+    /// Builds outlines for the meshes.
+    pub fn add_mesh_builder_pattern_with_outlines(
+        &mut self,
+        shell_name: CadShellName,
+        mesh_name: String,
+        mesh_builder: CadMeshBuilder<P>,
+        pattern: &CadMeshPattern,
+    ) -> Result<Self> {
+        self.add_mesh_builder_pattern_internal(shell_name, mesh_name, mesh_builder, pattern, true)
+    }
+
+    /// Add [`CadMeshBuilder`]s for all the instances of the [`CadMeshPattern`].
+    ///
+    /// Does not build outlines for the meshes.
+    /// Refer to [`add_mesh_builder_pattern_with_outlines`](Self::add_mesh_builder_pattern_with_outlines).
+    pub fn add_mesh_builder_pattern(
+        &mut self,
+        shell_name: CadShellName,
+        mesh_name: String,
+        mesh_builder: CadMeshBuilder<P>,
+        pattern: &CadMeshPattern,
+    ) -> Result<Self> {
+        self.add_mesh_builder_pattern_internal(shell_name, mesh_name, mesh_builder, pattern, false)
+    }
+
+    /// Internal function to add [`CadMeshBuilder`]s for the [`CadMeshPattern`].
+    fn add_mesh_builder_pattern_internal(
+        &mut self,
+        shell_name: CadShellName,
+        mesh_name: String,
+        mesh_builder: CadMeshBuilder<P>,
+        pattern: &CadMeshPattern,
+        build_outlines: bool,
+    ) -> Result<Self> {
+        let mut mesh_builder = mesh_builder;
+        mesh_builder.pattern_name = Some(mesh_name.clone().into());
+        for (idx, pattern_transform) in pattern.transforms().into_iter().enumerate() {
+            let mut instance = mesh_builder.clone();
+            instance.transform = pattern_transform * mesh_builder.transform;
+            self.add_mesh_builder_internal(
+                shell_name.clone(),
+                format!("{}{}", mesh_name, idx),
+                instance,
+                build_outlines,
+            )?;
+        }
+        Ok(self.clone())
+    }
+
     /// Internal function to add a new [`CadMeshBuilder`] to the builders.
     fn add_mesh_builder_internal(
         &mut self,
@@ -63,15 +126,12 @@ impl<P: Default + Clone> CadMeshesBuildersByCadShell<P> {
         mesh_builder: CadMeshBuilder<P>,
         build_outlines: bool,
     ) -> Result<Self> {
-        // Get the shell and set outlines on mesh builder...
-        let shell = self
-            .shells_by_name
-            .get(&shell_name)
-            .ok_or_else(|| anyhow!("Could not find shell with name: {:?}", shell_name))?;
-        let mut mesh_builder = mesh_builder;
-        if build_outlines {
-            mesh_builder.set_outlines(shell.shell.build_outlines())?;
+        if !self.shells_by_name.contains_key(&shell_name) {
+            return Err(anyhow!("Could not find shell with name: {:?}", shell_name));
         }
+        let mut mesh_builder = mesh_builder;
+        // Outlines are built along with the mesh (off the main thread)...
+        mesh_builder.build_outlines = build_outlines;
         if let Some(meshes_builder) = self.meshes_builders.get_mut(&shell_name) {
             // Add the mesh builder to the existing meshes builder...
             meshes_builder.add_mesh_builder(mesh_name, mesh_builder)?;
@@ -97,6 +157,14 @@ pub struct CadMeshesBuilder<P: Default + Clone> {
     /// Name of the singular shell used to build the meshes.
     pub shell_name: CadShellName,
     pub mesh_builders: HashMap<CadMeshName, CadMeshBuilder<P>>,
+    /// Tolerance used for tessellating the shell.
+    ///
+    /// Uses the finest tolerance of the [`CadMeshBuilder`]s (or [`CUSTOM_TRUCK_TOLERANCE_1`]) if `None`.
+    pub tolerance: Option<f64>,
+    /// Mode of computing the normals of the meshes.
+    ///
+    /// Uses the normal mode of the [`CadMeshBuilder`]s if `None`.
+    pub normal_mode: Option<CadNormalMode>,
 }
 
 impl<P: Default + Clone> CadMeshesBuilder<P> {
@@ -114,13 +182,199 @@ impl<P: Default + Clone> CadMeshesBuilder<P> {
         Ok(builder)
     }
 
-    pub fn build_bevy_mesh(&self) -> Result<Mesh> {
+    /// Set the tolerance used for tessellating the shell.
+    ///
+    /// Overrides the tolerances of the [`CadMeshBuilder`]s. Fails if `tolerance` is not finite and positive.
+    pub fn set_tolerance(&mut self, tolerance: f64) -> Result<Self> {
+        if !(tolerance.is_finite() && tolerance > 0.) {
+            return Err(anyhow!(
+                "Tolerance must be finite and positive: {}",
+                tolerance
+            ));
+        }
+        self.tolerance = Some(tolerance);
+        Ok(self.clone())
+    }
+
+    /// Get the tolerance used for tessellating the shell.
+    ///
+    /// The shell is tessellated once for all its [`CadMeshBuilder`]s, so the finest of their tolerances is used.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance.unwrap_or_else(|| {
+            self.mesh_builders
+                .values()
+                .filter_map(|mesh_builder| mesh_builder.tolerance)
+                .reduce(f64::min)
+                .unwrap_or(CUSTOM_TRUCK_TOLERANCE_1)
+        })
+    }
+
+    /// Set the mode of computing the normals of the meshes.
+    pub fn set_normal_mode(&mut self, normal_mode: CadNormalMode) -> Result<Self> {
+        self.normal_mode = Some(validate_normal_mode(normal_mode)?);
+        Ok(self.clone())
+    }
+
+    /// Get the mode of computing the normals of the meshes.
+    ///
+    /// The meshes are shared by all the [`CadMeshBuilder`]s, so their normal modes must be the same.
+    pub fn normal_mode(&self) -> Result<CadNormalMode> {
+        if let Some(normal_mode) = self.normal_mode {
+            return Ok(normal_mode);
+        }
+        let mut normal_modes = self
+            .mesh_builders
+            .values()
+            .map(|mesh_builder| mesh_builder.normal_mode);
+        let normal_mode = normal_modes.next().unwrap_or_default();
+        if normal_modes.any(|cur_normal_mode| cur_normal_mode != normal_mode) {
+            return Err(anyhow!(
+                "Mesh builders of shell: {:?} have different normal modes!",
+                self.shell_name
+            ));
+        }
+        Ok(normal_mode)
+    }
+
+    /// Whether any of the materials (incl face materials) of the [`CadMeshBuilder`]s use normal maps.
+    ///
+    /// Tangents are generated for the meshes if so.
+    pub fn needs_tangents(&self) -> bool {
+        self.mesh_builders.values().any(|mesh_builder| {
+            mesh_builder.base_material.normal_map_texture.is_some()
+                || mesh_builder
+                    .face_materials
+                    .values()
+                    .any(|material| material.normal_map_texture.is_some())
+        })
+    }
+
+    /// Whether the meshes built by `other` can be reused for this builder (of the same shell),
+    /// ie: the tessellation and the mesh attributes are the same.
+    pub fn has_same_meshing(&self, other: &Self) -> bool {
+        self.face_material_tags() == other.face_material_tags()
+            && self.tolerance() == other.tolerance()
+            && self.lod_tolerances() == other.lod_tolerances()
+            && self.normal_mode().ok() == other.normal_mode().ok()
+            && self.needs_tangents() == other.needs_tangents()
+            && self.needs_outlines() == other.needs_outlines()
+    }
+
+    /// Tolerances of the levels of detail of all the [`CadMeshBuilder`]s (sorted and deduplicated).
+    pub fn lod_tolerances(&self) -> Vec<f64> {
+        let mut lod_tolerances = self
+            .mesh_builders
+            .values()
+            .flat_map(|mesh_builder| mesh_builder.lods.iter().map(|lod| lod.tolerance))
+            .collect::<Vec<_>>();
+        lod_tolerances.sort_by(f64::total_cmp);
+        lod_tolerances.dedup();
+        lod_tolerances
+    }
+
+    /// Tags of the faces with face materials in any of the [`CadMeshBuilder`]s.
+    ///
+    /// These faces are meshed separately from the rest of the shell.
+    pub fn face_material_tags(&self) -> HashSet<CadElementTag> {
+        self.mesh_builders
+            .values()
+            .flat_map(|mesh_builder| mesh_builder.face_materials.keys().cloned())
+            .collect()
+    }
+
+    /// Whether any of the [`CadMeshBuilder`]s need outlines.
+    pub fn needs_outlines(&self) -> bool {
+        self.mesh_builders
+            .values()
+            .any(|mesh_builder| mesh_builder.build_outlines)
+    }
+
+    /// Build the bevy [`Mesh`] for the shell.
+    ///
+    /// Faces tagged with face materials (refer [`CadMeshBuilder::set_face_material`])
+    /// are built as separate meshes.
+    /// Also builds the [`CadMeshOutlines`] if any of the [`CadMeshBuilder`]s need them,
+    /// and a mesh for each of the [`Self::lod_tolerances`].
+    /// Normals are computed as per the [`Self::normal_mode`].
+    pub fn build_bevy_mesh(&self) -> Result<CadMeshesBuildResult> {
+        let normal_mode = self.normal_mode()?;
+        let needs_tangents = self.needs_tangents();
         let cad_shell = self
             .shells_by_name
             .get(&self.shell_name)
             .ok_or_else(|| anyhow!("Could not find shell with name: {:?}", self.shell_name))?;
-        let mesh = cad_shell.build_polygon()?.build_mesh();
-        Ok(mesh)
+        let outlines = if self.needs_outlines() {
+            cad_shell.shell.build_outlines()
+        } else {
+            CadMeshOutlines::default()
+        };
+        let mut faces = CadMeshFaces::new(cad_shell);
+        // Get the ids of the tagged faces with face materials...
+        let face_ids_by_tag = self
+            .face_material_tags()
+            .into_iter()
+            .map(|tag| {
+                let face_id = faces
+                    .face_id_by_tag(&tag)
+                    .ok_or_else(|| anyhow!("Could not find face with tag: {:?}", tag))?;
+                Ok((tag, face_id))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+        let tagged_face_ids = face_ids_by_tag.values().collect::<HashSet<_>>();
+        let cad_meshed_shell = cad_shell.build_cad_meshed_shell_with_tol(self.tolerance())?;
+        // Mesh the rest of the faces together, tracking the triangles of each face...
+        let polygon_mesh =
+            build_untagged_faces_polygon(&cad_meshed_shell, &tagged_face_ids, &mut faces);
+        // Levels of detail only re-mesh the untagged faces (face meshes are shared)...
+        let lod_meshes = self
+            .lod_tolerances()
+            .into_iter()
+            .map(|tolerance| {
+                let cad_meshed_shell = cad_shell.build_cad_meshed_shell_with_tol(tolerance)?;
+                let mut lod_faces = CadMeshFaces::new(cad_shell);
+                let lod_polygon_mesh = build_untagged_faces_polygon(
+                    &cad_meshed_shell,
+                    &tagged_face_ids,
+                    &mut lod_faces,
+                );
+                Ok(CadMeshLodBuildResult {
+                    tolerance,
+                    mesh: build_mesh(&lod_polygon_mesh, normal_mode, needs_tangents)?,
+                    faces: lod_faces,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let face_meshes = face_ids_by_tag
+            .iter()
+            .map(|(tag, face_id)| {
+                let meshed_face = cad_meshed_shell
+                    .meshed_faces_by_brep_face
+                    .get(face_id)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Face with tag: {:?} is not part of shell: {:?}",
+                            tag,
+                            self.shell_name
+                        )
+                    })?;
+                Ok((
+                    tag.clone(),
+                    build_mesh(
+                        &build_meshed_face_polygon(meshed_face),
+                        normal_mode,
+                        needs_tangents,
+                    )?,
+                ))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok(CadMeshesBuildResult {
+            mesh: build_mesh(&polygon_mesh, normal_mode, needs_tangents)?,
+            faces,
+            face_meshes,
+            outlines,
+            lod_meshes,
+        })
     }
 
     pub fn add_mesh_builder(
@@ -133,6 +387,85 @@ impl<P: Default + Clone> CadMeshesBuilder<P> {
     }
 }
 
+/// Build [`PolygonMesh`] from meshed face (cleaning up any degenerate stuff).
+fn build_meshed_face_polygon(meshed_face: &MeshedFace) -> PolygonMesh {
+    let mut polygon_mesh = MeshedShell::from(vec![meshed_face.clone()]).to_polygon();
+    polygon_mesh.remove_degenerate_faces().remove_unused_attrs();
+    polygon_mesh
+}
+
+/// Check the `crease_angle` (if any) of the [`CadNormalMode`] is not NaN or negative (incl `-0.0`).
+fn validate_normal_mode(normal_mode: CadNormalMode) -> Result<CadNormalMode> {
+    if let CadNormalMode::Smooth { crease_angle } = normal_mode {
+        if crease_angle.is_nan() || crease_angle.is_sign_negative() {
+            return Err(anyhow!("Invalid crease angle: {}", crease_angle));
+        }
+    }
+    Ok(normal_mode)
+}
+
+/// Build bevy [`Mesh`] from [`PolygonMesh`] with the normals as per the [`CadNormalMode`],
+/// generating tangents (for normal mapped materials) if needed.
+fn build_mesh(
+    polygon_mesh: &PolygonMesh,
+    normal_mode: CadNormalMode,
+    needs_tangents: bool,
+) -> Result<Mesh> {
+    let mut mesh = BevyMeshBuilder::from(polygon_mesh)
+        .with_normal_mode(normal_mode)
+        .build();
+    if needs_tangents && mesh.count_vertices() > 0 {
+        mesh.generate_tangents()
+            .map_err(|e| anyhow!("Could not generate tangents: {}", e))?;
+    }
+    Ok(mesh)
+}
+
+/// Build [`PolygonMesh`] from all the faces of the meshed shell except the tagged ones,
+/// pushing the triangle range of each face to `faces`.
+fn build_untagged_faces_polygon(
+    cad_meshed_shell: &CadMeshedShell<Surface>,
+    tagged_face_ids: &HashSet<&FaceID<Surface>>,
+    faces: &mut CadMeshFaces,
+) -> PolygonMesh {
+    let mut polygon_mesh = PolygonMesh::default();
+    for (face_id, meshed_face) in cad_meshed_shell.meshed_faces_by_brep_face.iter() {
+        if tagged_face_ids.contains(face_id) {
+            continue;
+        }
+        let tri_faces_start = polygon_mesh.tri_faces().len();
+        polygon_mesh.merge(build_meshed_face_polygon(meshed_face));
+        faces
+            .triangle_ranges
+            .push((tri_faces_start..polygon_mesh.tri_faces().len(), *face_id));
+    }
+    polygon_mesh
+}
+
+/// Result of [`CadMeshesBuilder::build_bevy_mesh`].
+#[derive(Debug, Clone)]
+pub struct CadMeshesBuildResult {
+    /// Mesh of the shell (without the faces with face materials).
+    pub mesh: Mesh,
+    /// Faces of the triangles of the mesh.
+    pub faces: CadMeshFaces,
+    /// Meshes of the faces with face materials by their tags.
+    pub face_meshes: HashMap<CadElementTag, Mesh>,
+    pub outlines: CadMeshOutlines,
+    /// Meshes for the levels of detail, sorted by tolerance. Refer [`CadMeshBuilder::set_lods`].
+    pub lod_meshes: Vec<CadMeshLodBuildResult>,
+}
+
+/// Mesh of the shell built for a level of detail, without the faces with face materials.
+#[derive(Debug, Clone)]
+pub struct CadMeshLodBuildResult {
+    /// Tolerance the shell was tessellated with.
+    pub tolerance: f64,
+    pub mesh: Mesh,
+    /// Faces of the triangles of the mesh.
+    pub faces: CadMeshFaces,
+}
+
 #[derive(Debug, Clone, Deref, DerefMut, Hash, PartialEq, Eq, Component)]
 pub struct CadMeshName(pub String);
 
@@ -150,7 +483,25 @@ pub struct CadMeshBuilder<P: Default + Clone> {
     pub mesh_hdl: Option<Handle<Mesh>>,
     pub base_material: StandardMaterial,
     pub outlines: CadMeshOutlines,
+    /// Set outlines from the shell once the mesh is built.
+    pub build_outlines: bool,
+    /// Faces of the triangles of the mesh. Set once the mesh is built.
+    pub faces: CadMeshFaces,
     pub transform: Transform,
+    /// Materials for tagged faces of the shell. Refer [`Self::set_face_material`].
+    pub face_materials: HashMap<CadElementTag, StandardMaterial>,
+    /// Handles of the meshes of the tagged faces (with face materials).
+    pub face_mesh_hdls: HashMap<CadElementTag, Handle<Mesh>>,
+    /// Name of the [`CadMeshPattern`] this is an instance of (if any).
+    ///
+    /// Instances of the same pattern share materials, allowing them to be batched while rendering.
+    pub pattern_name: Option<CadMeshName>,
+    /// Tolerance used for tessellating the shell. Refer [`Self::set_tolerance`].
+    pub tolerance: Option<f64>,
+    /// Levels of detail, sorted by distance. Refer [`Self::set_lods`].
+    pub lods: Vec<CadMeshLod>,
+    /// Mode of computing the normals of the mesh. Refer [`Self::set_normal_mode`].
+    pub normal_mode: CadNormalMode,
 }
 
 impl<P: Default + Clone> CadMeshBuilder<P> {
@@ -173,6 +524,27 @@ impl<P: Default + Clone> CadMeshBuilder<P> {
         Ok(self.clone())
     }
 
+    /// Set material for the face tagged with [`CadElementTag`] in the shell.
+    ///
+    /// The tagged face is built as a separate mesh (child of the main mesh) with this material.
+    /// Rest of the shell uses the base material.
+    pub fn set_face_material(
+        &mut self,
+        tag: CadElementTag,
+        material: StandardMaterial,
+    ) -> Result<Self> {
+        self.face_materials.insert(tag, material);
+        Ok(self.clone())
+    }
+
+    pub fn set_face_mesh_hdls(
+        &mut self,
+        face_mesh_hdls: HashMap<CadElementTag, Handle<Mesh>>,
+    ) -> Result<Self> {
+        self.face_mesh_hdls = face_mesh_hdls;
+        Ok(self.clone())
+    }
+
     pub fn set_transform(&mut self, transform: Transform) -> Result<Self> {
         self.transform = transform;
         Ok(self.clone())
@@ -183,6 +555,83 @@ impl<P: Default + Clone> CadMeshBuilder<P> {
         Ok(self.clone())
     }
 
+    /// Set the tolerance used for tessellating the shell of this mesh.
+    ///
+    /// Lower tolerance gives denser meshes, eg: use a low tolerance for small bolts and a high one for big hull panels.
+    /// The shell is tessellated once for all the meshes built from it, so the finest tolerance among them is used.
+    /// Refer [`CadMeshesBuilder::tolerance`]. Fails if `tolerance` is not finite and positive.
+    pub fn set_tolerance(&mut self, tolerance: f64) -> Result<Self> {
+        if !(tolerance.is_finite() && tolerance > 0.) {
+            return Err(anyhow!(
+                "Tolerance must be finite and positive: {}",
+                tolerance
+            ));
+        }
+        self.tolerance = Some(tolerance);
+        Ok(self.clone())
+    }
+
+    /// Set the levels of detail of the mesh as `(distance, tolerance)` pairs.
+    ///
+    /// Beyond each `distance` from the [`CadCamera`](crate::pmetra_plugins::components::camera::CadCamera),
+    /// the mesh is swapped with the shell tessellated with the (usually coarser) `tolerance`.
+    /// Meshes of the tagged faces (refer [`Self::set_face_material`]) are not swapped.
+    ///
+    /// Fails if a `distance` is not finite or is negative, or if a `tolerance` is not finite and positive.
+    pub fn set_lods(&mut self, lods: impl IntoIterator<Item = (f32, f64)>) -> Result<Self> {
+        let mut lods = lods
+            .into_iter()
+            .map(|(distance, tolerance)| {
+                if !distance.is_finite() || distance < 0. {
+                    return Err(anyhow!(
+                        "LOD distance must be finite and non-negative: {}",
+                        distance
+                    ));
+                }
+                if !(tolerance.is_finite() && tolerance > 0.) {
+                    return Err(anyhow!(
+                        "LOD tolerance must be finite and positive: {}",
+                        tolerance
+                    ));
+                }
+                Ok(CadMeshLod {
+                    distance,
+                    tolerance,
+                    ..default()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        lods.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        self.lods = lods;
+        Ok(self.clone())
+    }
+
+    /// Set the mode of computing the normals of the mesh, eg: [`CadNormalMode::Flat`] for mechanical parts,
+    /// or [`CadNormalMode::Smooth`] for organic shapes.
+    ///
+    /// All the meshes built from the same shell must use the same normal mode.
+    /// Fails if the `crease_angle` of [`CadNormalMode::Smooth`] is NaN or negative.
+    pub fn set_normal_mode(&mut self, normal_mode: CadNormalMode) -> Result<Self> {
+        self.normal_mode = validate_normal_mode(normal_mode)?;
+        Ok(self.clone())
+    }
+
+    /// Set the mesh handles (and faces) of the levels of detail from the `(tolerance, mesh, faces)` built for them.
+    pub fn set_lod_meshes(
+        &mut self,
+        lod_meshes: &[(f64, Handle<Mesh>, CadMeshFaces)],
+    ) -> Result<Self> {
+        for lod in self.lods.iter_mut() {
+            let (_, mesh_hdl, faces) = lod_meshes
+                .iter()
+                .find(|(tolerance, ..)| *tolerance == lod.tolerance)
+                .ok_or_else(|| anyhow!("No LOD mesh built for tolerance: {}", lod.tolerance))?;
+            lod.mesh_hdl = Some(mesh_hdl.clone());
+            lod.faces = faces.clone();
+        }
+        Ok(self.clone())
+    }
+
     pub fn build(&self) -> Result<CadMesh> {
         Ok(CadMesh {
             mesh_hdl: self
@@ -191,17 +640,62 @@ impl<P: Default + Clone> CadMeshBuilder<P> {
                 .ok_or_else(|| anyhow!("Mesh Handle is None!"))?,
             base_material: self.base_material.clone(),
             transform: self.transform,
+            pattern_name: self.pattern_name.clone(),
             outlines: self.outlines.clone(),
+            faces: self.faces.clone(),
+            face_meshes: self
+                .face_mesh_hdls
+                .iter()
+                .map(|(tag, face_mesh_hdl)| {
+                    let material = self
+                        .face_materials
+                        .get(tag)
+                        .unwrap_or(&self.base_material)
+                        .clone();
+                    (tag.clone(), (face_mesh_hdl.clone(), material))
+                })
+                .collect(),
+            lods: self
+                .lods
+                .iter()
+                .map(|lod| {
+                    let mesh_hdl = lod
+                        .mesh_hdl
+                        .clone()
+                        .ok_or_else(|| anyhow!("LOD Mesh Handle is None!"))?;
+                    Ok((lod.distance, mesh_hdl, lod.faces.clone()))
+                })
+                .collect::<Result<_>>()?,
         })
     }
 }
 
+/// Level of detail of a [`CadMeshBuilder`]. Refer [`CadMeshBuilder::set_lods`].
+#[derive(Debug, Clone, Default)]
+pub struct CadMeshLod {
+    /// Min distance from the camera to use this level of detail.
+    pub distance: f32,
+    /// Tolerance used for tessellating the shell.
+    pub tolerance: f64,
+    /// Handle of the mesh. Set once the mesh is built.
+    pub mesh_hdl: Option<Handle<Mesh>>,
+    /// Faces of the triangles of the mesh. Set once the mesh is built.
+    pub faces: CadMeshFaces,
+}
+
 #[derive(Debug, Clone)]
 pub struct CadMesh {
     pub mesh_hdl: Handle<Mesh>,
     pub base_material: StandardMaterial,
     pub transform: Transform,
+    /// Name of the [`CadMeshPattern`] this is an instance of (if any).
+    pub pattern_name: Option<CadMeshName>,
     pub outlines: CadMeshOutlines,
+    pub faces: CadMeshFaces,
+    /// Meshes (with materials) of the tagged faces.
+    pub face_meshes: HashMap<CadElementTag, (Handle<Mesh>, StandardMaterial)>,
+    /// Levels of detail as `(distance, mesh, faces)`, sorted by distance.
+    pub lods: Vec<(f32, Handle<Mesh>, CadMeshFaces)>,
 }
 
 /// Outlines for [`InteractiveCadMesh`]
@@ -244,3 +738,92 @@ impl BuildCadMeshOutlines for Shell {
         CadMeshOutlines(outlines)
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    pub fn test_set_normal_mode() {
+        let mut mesh_builder = CadMeshBuilder::new((), CadShellName("Cube".into())).unwrap();
+        let mut meshes_builder =
+            CadMeshesBuilder::new((), CadShellsByName::default(), CadShellName("Cube".into()))
+                .unwrap();
+        let smooth = CadNormalMode::Smooth { crease_angle: 0.5 };
+        assert_eq!(
+            mesh_builder.set_normal_mode(smooth).unwrap().normal_mode,
+            smooth
+        );
+        assert_eq!(
+            meshes_builder.set_normal_mode(smooth).unwrap().normal_mode,
+            Some(smooth)
+        );
+        for crease_angle in [f32::NAN, -0.5, -0.] {
+            let normal_mode = CadNormalMode::Smooth { crease_angle };
+            let error = mesh_builder.set_normal_mode(normal_mode).unwrap_err();
+            assert!(error.to_string().starts_with("Invalid crease angle:"));
+            assert!(meshes_builder.set_normal_mode(normal_mode).is_err());
+        }
+        // Previous (valid) modes are kept...
+        assert_eq!(mesh_builder.normal_mode, smooth);
+        assert_eq!(meshes_builder.normal_mode, Some(smooth));
+    }
+
+    #[test]
+    pub fn test_set_lods() {
+        let mut mesh_builder = CadMeshBuilder::new((), CadShellName("Cube".into())).unwrap();
+        let lods = mesh_builder
+            .set_lods([(50., 2e-1), (10., 5e-2)])
+            .unwrap()
+            .lods;
+        assert_eq!(
+            lods.iter()
+                .map(|lod| (lod.distance, lod.tolerance))
+                .collect::<Vec<_>>(),
+            vec![(10., 5e-2), (50., 2e-1)]
+        );
+        for distance in [f32::NAN, f32::INFINITY, -10.] {
+            let error = mesh_builder.set_lods([(distance, 2e-1)]).unwrap_err();
+            assert!(error
+                .to_string()
+                .starts_with("LOD distance must be finite and non-negative:"));
+        }
+        for tolerance in [0., -1e-2, f64::NAN, f64::INFINITY] {
+            let error = mesh_builder.set_lods([(10., tolerance)]).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("LOD tolerance must be finite and positive: {}", tolerance)
+            );
+        }
+        // Previous (valid) levels of detail are kept...
+        assert_eq!(mesh_builder.lods.len(), 2);
+    }
+
+    #[test]
+    pub fn test_set_tolerance() {
+        let mut mesh_builder = CadMeshBuilder::new((), CadShellName("Cube".into())).unwrap();
+        let mut meshes_builder =
+            CadMeshesBuilder::new((), CadShellsByName::default(), CadShellName("Cube".into()))
+                .unwrap();
+        assert_eq!(
+            mesh_builder.set_tolerance(1e-2).unwrap().tolerance,
+            Some(1e-2)
+        );
+        assert_eq!(
+            meshes_builder.set_tolerance(1e-2).unwrap().tolerance(),
+            1e-2
+        );
+        for tolerance in [0., -1e-2, f64::NAN, f64::INFINITY] {
+            let message = format!("Tolerance must be finite and positive: {}", tolerance);
+            let error = mesh_builder.set_tolerance(tolerance).unwrap_err();
+            assert_eq!(error.to_string(), message);
+            let error = meshes_builder.set_tolerance(tolerance).unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+        // Previous (valid) tolerances are kept...
+        assert_eq!(mesh_builder.tolerance, Some(1e-2));
+        assert_eq!(meshes_builder.tolerance(), 1e-2);
+    }
+}
@@ -1,17 +1,46 @@
 use anyhow::Result;
 use bevy::prelude::*;
 
+pub mod blends;
+pub mod faces;
+pub mod hollow;
 pub mod meshes;
+pub mod model;
+pub mod params;
+pub mod patterns;
 pub mod shells;
 pub mod sliders;
+pub mod solids;
 pub mod tags;
 
-pub use {meshes::*, shells::*, sliders::*, tags::*};
+pub use {
+    blends::*, faces::*, hollow::*, meshes::*, model::*, params::*, patterns::*, shells::*,
+    sliders::*, solids::*, tags::*,
+};
 
 /// Used for generating [`CadShell`]s using this struct via `truck`'s modelling APIs.
 pub trait PmetraCad: Clone + Default {
     /// Get the [`CadShellsBuilders`] for this params struct.
     fn shells_builders(&self) -> Result<CadShellsBuilders<Self>>;
+
+    /// Optional hash of the params.
+    ///
+    /// Used to cache tessellation results of shells without a key.
+    /// Refer [`CadShellsBuilders::set_shell_key`] for keying individual shells.
+    ///
+    /// Derive `PmetraCad` with `#[pmetra(cache_key)]` to key by all the fields (refer [`ParamsKey`]).
+    fn params_key(&self) -> Option<u64> {
+        None
+    }
+
+    /// Validate the params, returning the [`ParamIssue`]s if invalid.
+    ///
+    /// Called on slider edits before applying them. Edits resulting in invalid params are
+    /// rejected (or clamped to the last valid value along the drag), so the shell builders
+    /// only ever see valid params.
+    fn validate(&self) -> Result<(), Vec<ParamIssue>> {
+        Ok(())
+    }
 }
 
 /// Trait for parametrically generating [`Mesh`]s from struct.
@@ -39,6 +68,20 @@ pub trait PmetraInteractions: PmetraModelling {
     ///
     /// Return `None` if no tooltip should be displayed.
     fn on_slider_tooltip(&self, name: CadSliderName) -> Result<Option<String>>;
+
+    /// Handler called whenever a face of a generated mesh is clicked.
+    ///
+    /// Return `true` if the params were changed (to regenerate the model).
+    fn on_face_click(&mut self, _hit: &CadFaceHit) -> bool {
+        false
+    }
+
+    /// Handler called whenever the hovered face of a generated mesh changes.
+    ///
+    /// Return `true` if the params were changed (to regenerate the model).
+    fn on_face_hover(&mut self, _hit: &CadFaceHit) -> bool {
+        false
+    }
 }
 
 mod test {
@@ -116,5 +159,8 @@ mod test {
             .unwrap()
             .build_cad_shell)(&cube)
         .unwrap();
+
+        // Caching by params is opt-in...
+        assert!(cube.params_key().is_none());
     }
 }
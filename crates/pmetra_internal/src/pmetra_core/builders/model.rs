@@ -0,0 +1,240 @@
+use anyhow::{anyhow, Context, Result};
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use super::{
+    CadElementTag, CadMeshFaces, CadMeshName, CadMeshOutlines, CadShellName, CadShellsByName,
+    PmetraModelling,
+};
+
+/// Model built without an app via [`build_model`].
+#[derive(Debug, Clone)]
+pub struct CadModel {
+    /// Shells the meshes are built from, eg: for exporting to STEP.
+    pub shells_by_name: CadShellsByName,
+    /// Meshes ordered by the shell name and then the mesh name.
+    pub meshes: Vec<CadModelMesh>,
+}
+
+/// Mesh of a model, built without an app via [`build_model`].
+#[derive(Debug, Clone)]
+pub struct CadModelMesh {
+    pub name: CadMeshName,
+    /// Name of the shell the mesh is built from.
+    pub shell_name: CadShellName,
+    /// Mesh of the shell (without the faces with face materials).
+    pub mesh: Mesh,
+    pub transform: Transform,
+    pub material: StandardMaterial,
+    /// Outlines of the shell, if built. Refer [`CadMeshesBuildersByCadShell::add_mesh_builder_with_outlines`](super::CadMeshesBuildersByCadShell::add_mesh_builder_with_outlines).
+    pub outlines: CadMeshOutlines,
+    /// Faces of the triangles of the mesh.
+    pub faces: CadMeshFaces,
+    /// Meshes (with materials) of the tagged faces.
+    pub face_meshes: HashMap<CadElementTag, (Mesh, StandardMaterial)>,
+    /// Name of the [`CadMeshPattern`](super::CadMeshPattern) this is an instance of (if any).
+    pub pattern_name: Option<CadMeshName>,
+    /// Levels of detail as `(distance, mesh)`, sorted by distance. Refer [`CadMeshBuilder::set_lods`](super::CadMeshBuilder::set_lods).
+    pub lods: Vec<(f32, Mesh)>,
+}
+
+/// Build the shells and meshes of the model for the `params` synchronously, without an app.
+///
+/// Runs the same pipeline as [`PmetraModellingPlugin`](crate::pmetra_plugins::plugins::PmetraModellingPlugin):
+/// [`shells_builders`](super::PmetraCad::shells_builders) → [`meshes_builders_by_shell`](PmetraModelling::meshes_builders_by_shell)
+/// → [`build_bevy_mesh`](super::CadMeshesBuilder::build_bevy_mesh).
+///
+/// Meshes are ordered by the shell name and then the mesh name. Useful for tests, asset pipelines and
+/// server side generation.
+///
+/// Fails with the [`ParamIssue`](super::ParamIssue)s if the params are invalid (refer [`PmetraCad::validate`](super::PmetraCad::validate)).
+pub fn build_model<Params: PmetraModelling>(params: &Params) -> Result<CadModel> {
+    params.validate().map_err(|issues| {
+        anyhow!(
+            "Invalid params: {}",
+            issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })?;
+    let shells_by_name = params.shells_builders()?.build_shells()?;
+    let meshes_builders_by_shell = params.meshes_builders_by_shell(&shells_by_name)?;
+    let mut meshes_builders = meshes_builders_by_shell
+        .meshes_builders
+        .into_iter()
+        .collect::<Vec<_>>();
+    meshes_builders.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+
+    let mut model_meshes = vec![];
+    for (shell_name, meshes_builder) in meshes_builders {
+        let build_result = meshes_builder
+            .build_bevy_mesh()
+            .with_context(|| format!("Could not build mesh for shell: {:?}", shell_name))?;
+        let mut mesh_builders = meshes_builder.mesh_builders.into_iter().collect::<Vec<_>>();
+        mesh_builders.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+        for (name, mesh_builder) in mesh_builders {
+            let outlines = if mesh_builder.build_outlines {
+                build_result.outlines.clone()
+            } else {
+                mesh_builder.outlines.clone()
+            };
+            let face_meshes = build_result
+                .face_meshes
+                .iter()
+                .map(|(tag, face_mesh)| {
+                    let material = mesh_builder
+                        .face_materials
+                        .get(tag)
+                        .unwrap_or(&mesh_builder.base_material)
+                        .clone();
+                    (tag.clone(), (face_mesh.clone(), material))
+                })
+                .collect();
+            let lods = mesh_builder
+                .lods
+                .iter()
+                .map(|lod| {
+                    let lod_mesh = build_result
+                        .lod_meshes
+                        .iter()
+                        .find(|lod_mesh| lod_mesh.tolerance == lod.tolerance)
+                        .ok_or_else(|| {
+                            anyhow!("No LOD mesh built for tolerance: {}", lod.tolerance)
+                        })?;
+                    Ok((lod.distance, lod_mesh.mesh.clone()))
+                })
+                .collect::<Result<_>>()?;
+            model_meshes.push(CadModelMesh {
+                name,
+                shell_name: shell_name.clone(),
+                mesh: build_result.mesh.clone(),
+                transform: mesh_builder.transform,
+                material: mesh_builder.base_material,
+                outlines,
+                faces: build_result.faces.clone(),
+                face_meshes,
+                pattern_name: mesh_builder.pattern_name,
+                lods,
+            });
+        }
+    }
+
+    Ok(CadModel {
+        shells_by_name,
+        meshes: model_meshes,
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::{
+        pmetra_core::builders::{
+            CadMeshBuilder, CadMeshPattern, CadMeshesBuildersByCadShell, CadShellsBuilders,
+            CadShellsByName, ParamIssue, PmetraCad,
+        },
+        test_utils::{cube_shell, cylinder_shell},
+    };
+    use truck_modeling::Point3;
+
+    #[test]
+    pub fn test_build_model() {
+        #[derive(Debug, Clone, Default)]
+        pub struct Cube {
+            pub side_length: f64,
+        }
+
+        impl PmetraCad for Cube {
+            fn shells_builders(&self) -> Result<CadShellsBuilders<Self>> {
+                CadShellsBuilders::new(self.clone())?
+                    .add_shell_builder(CadShellName("Cube".into()), |_: &Self| {
+                        Ok(cube_shell(Point3::new(0., 0., 0.)))
+                    })
+            }
+
+            fn validate(&self) -> Result<(), Vec<ParamIssue>> {
+                if self.side_length > 0. {
+                    Ok(())
+                } else {
+                    Err(vec![ParamIssue::new("side_length", "should be positive")])
+                }
+            }
+        }
+
+        impl PmetraModelling for Cube {
+            fn meshes_builders_by_shell(
+                &self,
+                shells_by_name: &CadShellsByName,
+            ) -> Result<CadMeshesBuildersByCadShell<Self>> {
+                CadMeshesBuildersByCadShell::new(self.clone(), shells_by_name.clone())?
+                    .add_mesh_builder_pattern_with_outlines(
+                        CadShellName("Cube".into()),
+                        "Cube".into(),
+                        CadMeshBuilder::new(self.clone(), CadShellName("Cube".into()))?,
+                        &CadMeshPattern::linear(Vec3::X, 2., 2),
+                    )
+            }
+        }
+
+        let model = build_model(&Cube { side_length: 1. }).unwrap();
+        assert_eq!(model.shells_by_name.len(), 1);
+        let model_meshes = model.meshes;
+        assert_eq!(model_meshes.len(), 2);
+        assert_eq!(model_meshes[0].name.0, "Cube0");
+        assert!(model_meshes[1]
+            .transform
+            .translation
+            .abs_diff_eq(Vec3::X * 2., 1e-6));
+        assert!(model_meshes[0].mesh.count_vertices() > 0);
+        assert_eq!(model_meshes[0].outlines.len(), 6);
+
+        // Invalid params are rejected before building...
+        let error = build_model(&Cube { side_length: -1. }).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid params: side_length: should be positive"
+        );
+    }
+
+    #[test]
+    pub fn test_build_model_tolerances_and_lods() {
+        #[derive(Debug, Clone, Default)]
+        pub struct Cylinder;
+
+        impl PmetraCad for Cylinder {
+            fn shells_builders(&self) -> Result<CadShellsBuilders<Self>> {
+                CadShellsBuilders::new(self.clone())?
+                    .add_shell_builder(CadShellName("Cylinder".into()), |_: &Self| {
+                        Ok(cylinder_shell())
+                    })
+            }
+        }
+
+        impl PmetraModelling for Cylinder {
+            fn meshes_builders_by_shell(
+                &self,
+                shells_by_name: &CadShellsByName,
+            ) -> Result<CadMeshesBuildersByCadShell<Self>> {
+                CadMeshesBuildersByCadShell::new(self.clone(), shells_by_name.clone())?
+                    .add_mesh_builder(
+                        CadShellName("Cylinder".into()),
+                        "Cylinder".into(),
+                        CadMeshBuilder::new(self.clone(), CadShellName("Cylinder".into()))?
+                            .set_tolerance(1e-2)?
+                            .set_lods([(50., 2e-1), (10., 5e-2)])?,
+                    )
+            }
+        }
+
+        let model_meshes = build_model(&Cylinder).unwrap().meshes;
+        let model_mesh = &model_meshes[0];
+        assert_eq!(model_mesh.lods.len(), 2);
+        // Sorted by distance, coarser further away...
+        assert_eq!(model_mesh.lods[0].0, 10.);
+        assert!(model_mesh.mesh.count_vertices() > model_mesh.lods[0].1.count_vertices());
+        assert!(model_mesh.lods[0].1.count_vertices() > model_mesh.lods[1].1.count_vertices());
+    }
+}
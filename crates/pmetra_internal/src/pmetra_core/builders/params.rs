@@ -0,0 +1,166 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use bevy::math::{DVec2, DVec3, Vec2, Vec3};
+
+/// Issue with the value of a param, reported by [`PmetraCad::validate`](super::PmetraCad::validate).
+///
+/// Shown in the tooltip of the slider whose edit was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamIssue {
+    /// Name of the param (field), eg: `profile_thickness`.
+    pub param: String,
+    /// What is wrong with the value, eg: `should be less than half of profile_width`.
+    pub message: String,
+}
+
+impl ParamIssue {
+    pub fn new(param: &str, message: &str) -> Self {
+        Self {
+            param: param.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParamIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.param, self.message)
+    }
+}
+
+/// Hash of a param (field) value, used for the params cache key.
+///
+/// Derive `PmetraCad` with `#[pmetra(cache_key)]` to key the tessellation cache by all the
+/// fields of the params (refer [`PmetraCad::params_key`](super::PmetraCad::params_key)).
+/// Implement for custom field types, eg: `self.hash(state)` for types implementing [`Hash`].
+pub trait ParamsKey {
+    /// Feed the value into the `state`.
+    fn hash_key<H: Hasher>(&self, state: &mut H);
+}
+
+macro_rules! impl_params_key_via_hash {
+    ($($ty:ty),*) => {
+        $(
+            impl ParamsKey for $ty {
+                fn hash_key<H: Hasher>(&self, state: &mut H) {
+                    self.hash(state);
+                }
+            }
+        )*
+    };
+}
+
+impl_params_key_via_hash!(
+    bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, String, str
+);
+
+impl ParamsKey for f64 {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        // Equal values (incl `0.0` and `-0.0`) give the same key, all NaNs the same key...
+        let value = if *self == 0. {
+            0.
+        } else if self.is_nan() {
+            f64::NAN
+        } else {
+            *self
+        };
+        value.to_bits().hash(state);
+    }
+}
+
+impl ParamsKey for f32 {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        (*self as f64).hash_key(state);
+    }
+}
+
+impl ParamsKey for DVec2 {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.to_array().hash_key(state);
+    }
+}
+
+impl ParamsKey for DVec3 {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.to_array().hash_key(state);
+    }
+}
+
+impl ParamsKey for Vec2 {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.to_array().hash_key(state);
+    }
+}
+
+impl ParamsKey for Vec3 {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.to_array().hash_key(state);
+    }
+}
+
+impl<T: ParamsKey + ?Sized> ParamsKey for &T {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        (**self).hash_key(state);
+    }
+}
+
+impl<T: ParamsKey> ParamsKey for Option<T> {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.is_some().hash(state);
+        if let Some(value) = self {
+            value.hash_key(state);
+        }
+    }
+}
+
+impl<T: ParamsKey> ParamsKey for [T] {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for value in self {
+            value.hash_key(state);
+        }
+    }
+}
+
+impl<T: ParamsKey, const N: usize> ParamsKey for [T; N] {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash_key(state);
+    }
+}
+
+impl<T: ParamsKey> ParamsKey for Vec<T> {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash_key(state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use std::hash::DefaultHasher;
+
+    #[allow(dead_code)]
+    fn key<T: ParamsKey + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash_key(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    pub fn test_params_key() {
+        assert_eq!(key(&1.5_f64), key(&1.5_f64));
+        assert_ne!(key(&1_f64), key(&(1_f64 + 1e-12)));
+        assert_eq!(key(&0_f64), key(&-0_f64));
+        assert_eq!(key(&f64::NAN), key(&-f64::NAN));
+        assert_eq!(key(&DVec3::new(1., 2., 3.)), key(&[1_f64, 2., 3.]));
+        assert_ne!(key(&Some(0_u32)), key(&None::<u32>));
+        assert_ne!(key(&vec![1_u32, 2]), key(&vec![1_u32]));
+        assert_eq!(key("a"), key(&"a".to_string()));
+    }
+}
@@ -0,0 +1,184 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::math::get_rotation_from_normals;
+
+/// Pattern for repeating a [`CadMeshBuilder`](super::CadMeshBuilder) via transforms.
+///
+/// All instances share the mesh of the (seed) shell, which is tessellated once.
+/// Refer [`CadMeshesBuildersByCadShell::add_mesh_builder_pattern`](super::CadMeshesBuildersByCadShell::add_mesh_builder_pattern).
+#[derive(Debug, Clone, PartialEq)]
+pub enum CadMeshPattern {
+    /// `count` instances spaced by `spacing` along `direction`.
+    Linear {
+        direction: Vec3,
+        spacing: f32,
+        count: usize,
+    },
+    /// `count` instances rotated around `axis` (through `center`), spread evenly over `angle` (radians).
+    ///
+    /// For a full circle (`TAU`), the last instance does not overlap the first.
+    Circular {
+        center: Vec3,
+        axis: Vec3,
+        angle: f32,
+        count: usize,
+    },
+    /// `count` instances spread evenly (by length) along the polyline through `points`,
+    /// from the first to the last point.
+    ///
+    /// If `align` is set, instances are rotated to follow the direction of the path (relative to its start).
+    Path {
+        points: Vec<Vec3>,
+        count: usize,
+        align: bool,
+    },
+}
+
+impl CadMeshPattern {
+    pub fn linear(direction: Vec3, spacing: f32, count: usize) -> Self {
+        Self::Linear {
+            direction,
+            spacing,
+            count,
+        }
+    }
+
+    pub fn circular(center: Vec3, axis: Vec3, angle: f32, count: usize) -> Self {
+        Self::Circular {
+            center,
+            axis,
+            angle,
+            count,
+        }
+    }
+
+    pub fn path(points: Vec<Vec3>, count: usize, align: bool) -> Self {
+        Self::Path {
+            points,
+            count,
+            align,
+        }
+    }
+
+    /// Number of instances of the pattern.
+    pub fn count(&self) -> usize {
+        match self {
+            Self::Linear { count, .. }
+            | Self::Circular { count, .. }
+            | Self::Path { count, .. } => *count,
+        }
+    }
+
+    /// Transforms of the instances, to be applied (as parent) on the transform of the seed.
+    ///
+    /// First instance is always at identity.
+    pub fn transforms(&self) -> Vec<Transform> {
+        match self {
+            Self::Linear {
+                direction,
+                spacing,
+                count,
+            } => {
+                let step = direction.normalize_or_zero() * *spacing;
+                (0..*count)
+                    .map(|idx| Transform::from_translation(step * idx as f32))
+                    .collect()
+            }
+            Self::Circular {
+                center,
+                axis,
+                angle,
+                count,
+            } => {
+                let axis = axis.normalize_or(Vec3::Y);
+                let step = if (angle.abs() - TAU).abs() < f32::EPSILON * TAU {
+                    angle / *count as f32
+                } else {
+                    angle / count.saturating_sub(1).max(1) as f32
+                };
+                (0..*count)
+                    .map(|idx| {
+                        let mut transform = Transform::default();
+                        transform
+                            .rotate_around(*center, Quat::from_axis_angle(axis, step * idx as f32));
+                        transform
+                    })
+                    .collect()
+            }
+            Self::Path {
+                points,
+                count,
+                align,
+            } => {
+                let Some(start) = points.first() else {
+                    return vec![Transform::default(); *count];
+                };
+                let segments = points
+                    .windows(2)
+                    .map(|pair| (pair[0], pair[1]))
+                    .filter(|(p0, p1)| p0.distance(*p1) > f32::EPSILON)
+                    .collect::<Vec<_>>();
+                let length = segments
+                    .iter()
+                    .map(|(p0, p1)| p0.distance(*p1))
+                    .sum::<f32>();
+                let start_dir = segments
+                    .first()
+                    .map(|(p0, p1)| (*p1 - *p0).normalize())
+                    .unwrap_or(Vec3::X);
+                let step = length / count.saturating_sub(1).max(1) as f32;
+                (0..*count)
+                    .map(|idx| {
+                        // Find the segment at the distance along the path...
+                        let mut distance = step * idx as f32;
+                        let mut point_dir = (*start, start_dir);
+                        for (p0, p1) in segments.iter() {
+                            let segment_length = p0.distance(*p1);
+                            let dir = (*p1 - *p0) / segment_length;
+                            point_dir = (*p0 + dir * distance.min(segment_length), dir);
+                            if distance <= segment_length {
+                                break;
+                            }
+                            distance -= segment_length;
+                        }
+                        let (point, dir) = point_dir;
+                        let mut transform = Transform::from_translation(point - *start);
+                        if *align {
+                            transform
+                                .rotate_around(point, get_rotation_from_normals(start_dir, dir));
+                        }
+                        transform
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    pub fn test_cad_mesh_pattern_transforms() {
+        let linear = CadMeshPattern::linear(Vec3::X * 2., 1.5, 3).transforms();
+        assert_eq!(linear.len(), 3);
+        assert!(linear[2].translation.abs_diff_eq(Vec3::X * 3., 1e-6));
+
+        let circular = CadMeshPattern::circular(Vec3::ZERO, Vec3::Y, TAU, 4).transforms();
+        assert_eq!(circular.len(), 4);
+        assert!((circular[1] * Transform::from_translation(Vec3::X))
+            .translation
+            .abs_diff_eq(Vec3::NEG_Z, 1e-6));
+
+        let path = CadMeshPattern::path(vec![Vec3::ZERO, Vec3::X, Vec3::X + Vec3::Z], 3, true)
+            .transforms();
+        assert!(path[1].translation.abs_diff_eq(Vec3::X, 1e-6));
+        assert!(path[2].translation.abs_diff_eq(Vec3::X + Vec3::Z, 1e-6));
+        assert!((path[2].rotation * Vec3::X).abs_diff_eq(Vec3::Z, 1e-6));
+    }
+}
@@ -1,3 +1,8 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+};
+
 use bevy::{platform::collections::HashMap, prelude::*};
 
 use anyhow::{anyhow, Result};
@@ -22,7 +27,7 @@ pub struct CadShellsBuilders<P: Default + Clone> {
     pub builders: HashMap<CadShellName, CadShellBuilder<P>>,
 }
 
-impl<P: Default + Clone> CadShellsBuilders<P> {
+impl<P: Default + Clone + 'static> CadShellsBuilders<P> {
     pub fn new(params: P) -> Result<Self> {
         let builder = Self {
             params,
@@ -37,14 +42,40 @@ impl<P: Default + Clone> CadShellsBuilders<P> {
         shell_name: CadShellName,
         build_fn: fn(&P) -> Result<CadShell>,
     ) -> Result<Self> {
-        let shell_builder = CadShellBuilder {
-            params: self.params.clone(),
-            build_cad_shell: build_fn,
-        };
+        self.add_shell_builder_closure(shell_name, build_fn)
+    }
+
+    /// Add new [`CadShellBuilder`] to builders using a closure.
+    ///
+    /// Allows capturing values (eg: index of an array item, shared pre-computed profile etc).
+    pub fn add_shell_builder_closure(
+        &mut self,
+        shell_name: CadShellName,
+        build_fn: impl Fn(&P) -> Result<CadShell> + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let shell_builder = CadShellBuilder::new_closure(self.params.clone(), build_fn);
         self.builders.insert(shell_name, shell_builder);
         Ok(self.clone())
     }
 
+    /// Set the key function for the [`CadShellBuilder`] with [`CadShellName`].
+    ///
+    /// The key should hash all the params the shell depends on (use `f64::to_bits` for floats).
+    /// On params change, the shell is only rebuilt (and re-meshed) if its key changes.
+    /// Shells without a key are always rebuilt.
+    pub fn set_shell_key<K: Hash>(
+        &mut self,
+        shell_name: CadShellName,
+        key_fn: impl Fn(&P) -> K + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let shell_builder = self
+            .builders
+            .get_mut(&shell_name)
+            .ok_or_else(|| anyhow!("Could not find shell with name: {:?}", shell_name))?;
+        shell_builder.set_key(key_fn);
+        Ok(self.clone())
+    }
+
     /// Build [`CadShell`] using the stored [`CadShellBuilder`] with [`CadShellName`].
     pub fn build_shell(&self, shell_name: CadShellName) -> Result<CadShell> {
         (self
@@ -53,26 +84,72 @@ impl<P: Default + Clone> CadShellsBuilders<P> {
             .ok_or_else(|| anyhow!("Could not find shell with name: {:?}", shell_name))?
             .build_cad_shell)(&self.params)
     }
+
+    /// Build all the [`CadShell`]s, eg: for using the shells outside of the app (headless).
+    pub fn build_shells(&self) -> Result<CadShellsByName> {
+        let mut shells_by_name = CadShellsByName::default();
+        for shell_name in self.builders.keys() {
+            shells_by_name.insert(shell_name.clone(), self.build_shell(shell_name.clone())?);
+        }
+        Ok(shells_by_name)
+    }
 }
 
+/// Function/closure used to build a [`CadShell`] from params.
+///
+/// `Send + Sync` so builders can be used from async tasks.
+pub type CadShellBuildFn<P> = Arc<dyn Fn(&P) -> Result<CadShell> + Send + Sync>;
+
+/// Function/closure used to get the key of a [`CadShell`] from params.
+///
+/// Refer [`CadShellsBuilders::set_shell_key`].
+pub type CadShellKeyFn<P> = Arc<dyn Fn(&P) -> u64 + Send + Sync>;
+
 /// Builder for building [`CadShell`]s.
 #[derive(Clone, Component)]
 pub struct CadShellBuilder<P: Default + Clone> {
     pub params: P,
-    pub build_cad_shell: fn(&P) -> Result<CadShell>,
+    pub build_cad_shell: CadShellBuildFn<P>,
+    /// Optional key of the params the shell depends on.
+    pub shell_key: Option<CadShellKeyFn<P>>,
 }
 
-impl<P: Default + Clone> CadShellBuilder<P> {
+impl<P: Default + Clone + 'static> CadShellBuilder<P> {
     pub fn new(params: P, build_fn: fn(&P) -> Result<CadShell>) -> Self {
+        Self::new_closure(params, build_fn)
+    }
+
+    /// Create new [`CadShellBuilder`] using a closure.
+    pub fn new_closure(
+        params: P,
+        build_fn: impl Fn(&P) -> Result<CadShell> + Send + Sync + 'static,
+    ) -> Self {
         Self {
             params,
-            build_cad_shell: build_fn,
+            build_cad_shell: Arc::new(build_fn),
+            shell_key: None,
         }
     }
 
+    /// Set the key function. Refer [`CadShellsBuilders::set_shell_key`].
+    pub fn set_key<K: Hash>(&mut self, key_fn: impl Fn(&P) -> K + Send + Sync + 'static) {
+        self.shell_key = Some(Arc::new(move |params: &P| {
+            let mut hasher = DefaultHasher::new();
+            key_fn(params).hash(&mut hasher);
+            hasher.finish()
+        }));
+    }
+
     pub fn build_cad_shell(&self) -> Result<CadShell> {
         (self.build_cad_shell)(&self.params)
     }
+
+    /// Get the key for the params. `None` if no key function is set.
+    pub fn key(&self) -> Option<u64> {
+        self.shell_key
+            .as_ref()
+            .map(|shell_key| shell_key(&self.params))
+    }
 }
 
 /// Component to store all generated [`CadShell`]s by [`CadShellName`].
@@ -127,3 +204,63 @@ impl BuildPolygon for CadShell {
         Ok(polygon_mesh)
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use truck_modeling::{builder, Point3, Vector3};
+
+    #[test]
+    pub fn test_add_shell_builder_closure() {
+        #[derive(Debug, Clone, Default)]
+        pub struct Steps {
+            pub count: usize,
+            pub side_length: f64,
+        }
+
+        let params = Steps {
+            count: 3,
+            side_length: 1.,
+        };
+        let gap = 0.5;
+        let mut builders = CadShellsBuilders::new(params.clone()).unwrap();
+        for index in 0..params.count {
+            // Capture the index and gap, the side length comes from the params...
+            builders = builders
+                .add_shell_builder_closure(
+                    CadShellName(format!("Step{}", index)),
+                    move |p: &Steps| {
+                        let x = index as f64 * (p.side_length + gap);
+                        let v = builder::vertex(Point3::new(x, 0., 0.));
+                        let e = builder::tsweep(&v, Vector3::unit_x() * p.side_length);
+                        let f = builder::tsweep(&e, Vector3::unit_y() * p.side_length);
+                        let solid = builder::tsweep(&f, Vector3::unit_z() * p.side_length);
+                        Ok(CadShell {
+                            shell: solid.into_boundaries().pop().unwrap(),
+                            ..default()
+                        })
+                    },
+                )
+                .unwrap();
+        }
+
+        let shells_by_name = builders.build_shells().unwrap();
+        assert_eq!(shells_by_name.len(), 3);
+        for index in 0..params.count {
+            let cad_shell = shells_by_name
+                .get(&CadShellName(format!("Step{}", index)))
+                .unwrap();
+            assert_eq!(cad_shell.shell.face_iter().count(), 6);
+            let min_x = cad_shell
+                .shell
+                .vertex_iter()
+                .map(|vertex| vertex.point().x)
+                .fold(f64::MAX, f64::min);
+            assert!((min_x - index as f64 * 1.5).abs() < 1e-9);
+        }
+        assert!(builders.build_shell(CadShellName("Step3".into())).is_err());
+    }
+}
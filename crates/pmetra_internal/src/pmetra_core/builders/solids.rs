@@ -0,0 +1,428 @@
+use anyhow::{anyhow, Result};
+use bevy::log::warn;
+use truck_meshalgo::prelude::TOLERANCE;
+use truck_modeling::{
+    BoundedCurve, Edge, EuclideanSpace, Face, InnerSpace, MetricSpace, ParametricCurve,
+    ParametricSurface, ParametricSurface3D, Point3, SPHint2D, SearchNearestParameter, Shell, Solid,
+    Vector3,
+};
+
+use crate::{
+    constants::{
+        SURFACE_SEARCH_TRIALS, TRUCK_BOOLEAN_RETRY_TOLERANCE_1, TRUCK_BOOLEAN_TOLERANCE_1,
+    },
+    pmetra_core::meshing::BuildPolygon,
+};
+
+use super::{CadElement, CadElementTag, CadShell, CadTaggedElements};
+
+/// Tolerance for matching the tagged elements to the elements of the boolean op result.
+///
+/// Tighter than the boolean tolerance, so that nearby (but different) elements are not matched.
+const TAG_MATCH_TOLERANCE: f64 = TOLERANCE * 10.;
+
+/// CAD generated [`Solid`].
+///
+/// Holds [`Solid`] with [`CadTaggedElements`].
+/// Supports boolean ops (via `truck_shapeops`) carrying forward the tags of surviving elements.
+#[derive(Debug, Clone)]
+pub struct CadSolid {
+    pub solid: Solid,
+    pub tagged_elements: CadTaggedElements,
+}
+
+/// Boolean operation on [`CadSolid`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CadBooleanOp {
+    Union,
+    Difference,
+    Intersection,
+}
+
+impl CadSolid {
+    pub fn new(solid: Solid) -> Self {
+        Self {
+            solid,
+            tagged_elements: CadTaggedElements::default(),
+        }
+    }
+
+    /// Tag the [`CadElement`] of the solid with [`CadElementTag`].
+    pub fn set_tag(&mut self, tag: CadElementTag, element: CadElement) -> Result<Self> {
+        self.tagged_elements.insert(tag, element);
+        Ok(self.clone())
+    }
+
+    pub fn get_element_by_tag(&self, tag: CadElementTag) -> Option<&CadElement> {
+        self.tagged_elements.get(&tag)
+    }
+
+    /// Union (`self + other`) of the solids.
+    pub fn union(&self, other: &CadSolid) -> Result<Self> {
+        self.boolean_op(other, CadBooleanOp::Union)
+    }
+
+    /// Difference (`self - other`) of the solids.
+    pub fn difference(&self, other: &CadSolid) -> Result<Self> {
+        self.boolean_op(other, CadBooleanOp::Difference)
+    }
+
+    /// Intersection (common part) of the solids.
+    pub fn intersection(&self, other: &CadSolid) -> Result<Self> {
+        self.boolean_op(other, CadBooleanOp::Intersection)
+    }
+
+    /// Run the boolean op with `other` solid.
+    ///
+    /// Retries with a looser tolerance ([`TRUCK_BOOLEAN_RETRY_TOLERANCE_1`]) on failure.
+    ///
+    /// Tags of both solids are carried forward if their elements survive the op
+    /// (matched within a tolerance tighter than the boolean op's):
+    /// - Faces: Largest result face lying within the tagged face (on its surface, with parallel normals).
+    ///   Tagged faces split into fragments by the op keep the tag on the largest fragment only.
+    /// - Edges/Vertices: Result edge with the same end points and mid point / vertex with the same point.
+    /// - Wires/Shells/Solids (and faces failing to polygonize): Not carried forward (a warning is logged).
+    pub fn boolean_op(&self, other: &CadSolid, op: CadBooleanOp) -> Result<Self> {
+        let (solid, tol) = with_boolean_tolerances(|tol| match op {
+            CadBooleanOp::Union => truck_shapeops::or(&self.solid, &other.solid, tol),
+            CadBooleanOp::Difference => {
+                let mut other_solid = other.solid.clone();
+                other_solid.not();
+                truck_shapeops::and(&self.solid, &other_solid, tol)
+            }
+            CadBooleanOp::Intersection => truck_shapeops::and(&self.solid, &other.solid, tol),
+        })
+        .ok_or_else(|| anyhow!("Boolean op: {:?} failed!", op))?;
+
+        // Get a point inside (on the surface), its normal and the area of each result face for matching the tagged faces...
+        let result_faces = solid
+            .face_iter()
+            .filter_map(|face| {
+                let polygon = face.build_polygon_with_tol(tol).ok()?;
+                let triangles = polygon
+                    .tri_faces()
+                    .iter()
+                    .map(|tri_face| tri_face.map(|v| polygon.positions()[v.pos]))
+                    .collect::<Vec<_>>();
+                let (point, normal) =
+                    face_point_normal(face, Point3::centroid(triangles.first()?))?;
+                let area = triangles
+                    .iter()
+                    .map(|[a, b, c]| (b - a).cross(c - a).magnitude() / 2.)
+                    .sum::<f64>();
+                Some((face.clone(), point, normal, area))
+            })
+            .collect::<Vec<_>>();
+
+        let mut tagged_elements = CadTaggedElements::default();
+        let mut dropped_tags = vec![];
+        for (tag, element) in self
+            .tagged_elements
+            .iter()
+            .chain(other.tagged_elements.iter())
+        {
+            let surviving_element = match element {
+                CadElement::Face(face) => {
+                    let Ok(polygon) = face.build_polygon_with_tol(tol) else {
+                        dropped_tags.push(tag);
+                        continue;
+                    };
+                    let triangles = polygon
+                        .tri_faces()
+                        .iter()
+                        .map(|tri_face| tri_face.map(|v| polygon.positions()[v.pos]))
+                        .collect::<Vec<_>>();
+                    result_faces
+                        .iter()
+                        .filter(|(_, point, normal, _)| {
+                            // Faces of `other` are inverted for difference, so normals can be opposite...
+                            face_point_normal(face, *point).is_some_and(
+                                |(face_point, face_normal)| {
+                                    face_point.distance(*point) < TAG_MATCH_TOLERANCE
+                                        && face_normal.dot(*normal).abs() > 1. - TAG_MATCH_TOLERANCE
+                                },
+                            ) && triangles
+                                .iter()
+                                .any(|triangle| is_point_in_triangle(*point, triangle, tol))
+                        })
+                        .max_by(|(.., a), (.., b)| a.total_cmp(b))
+                        .map(|(result_face, ..)| CadElement::Face(result_face.clone()))
+                }
+                CadElement::Edge(edge) => solid.edge_iter().find_map(|result_edge| {
+                    is_same_edge(edge, result_edge).then(|| CadElement::Edge(result_edge.clone()))
+                }),
+                CadElement::Vertex(vertex) => solid.vertex_iter().find_map(|result_vertex| {
+                    (vertex.point().distance(result_vertex.point()) < TAG_MATCH_TOLERANCE)
+                        .then(|| CadElement::Vertex(result_vertex.clone()))
+                }),
+                CadElement::Wire(_) | CadElement::Shell(_) | CadElement::Solid(_) => {
+                    dropped_tags.push(tag);
+                    None
+                }
+            };
+            if let Some(surviving_element) = surviving_element {
+                tagged_elements.insert(tag.clone(), surviving_element);
+            }
+        }
+        if !dropped_tags.is_empty() {
+            warn!(
+                "Boolean op: {:?} dropped wire/shell/solid (or unpolygonizable face) tags: {:?}",
+                op, dropped_tags
+            );
+        }
+
+        Ok(Self {
+            solid,
+            tagged_elements,
+        })
+    }
+}
+
+/// Run the boolean `op` with [`TRUCK_BOOLEAN_TOLERANCE_1`], retrying with [`TRUCK_BOOLEAN_RETRY_TOLERANCE_1`]
+/// on failure.
+///
+/// Returns the result with the tolerance used.
+fn with_boolean_tolerances<T>(mut op: impl FnMut(f64) -> Option<T>) -> Option<(T, f64)> {
+    [TRUCK_BOOLEAN_TOLERANCE_1, TRUCK_BOOLEAN_RETRY_TOLERANCE_1]
+        .into_iter()
+        .find_map(|tol| Some((op(tol)?, tol)))
+}
+
+impl TryFrom<CadShell> for CadSolid {
+    type Error = anyhow::Error;
+
+    /// Create [`CadSolid`] from closed [`CadShell`].
+    fn try_from(cad_shell: CadShell) -> Result<Self> {
+        let solid = Solid::try_new(vec![cad_shell.shell])
+            .map_err(|e| anyhow!("Could not create solid from shell: {:?}", e))?;
+        Ok(Self {
+            solid,
+            tagged_elements: cad_shell.tagged_elements,
+        })
+    }
+}
+
+impl From<CadSolid> for CadShell {
+    /// Create [`CadShell`] with the faces of all the boundaries of the [`CadSolid`].
+    fn from(cad_solid: CadSolid) -> Self {
+        let mut shell = Shell::new();
+        for boundary in cad_solid.solid.boundaries() {
+            shell.extend(boundary.clone());
+        }
+        Self {
+            shell,
+            tagged_elements: cad_solid.tagged_elements,
+        }
+    }
+}
+
+/// Get the nearest point (and unit normal) on the surface of the face to `point`.
+fn face_point_normal(face: &Face, point: Point3) -> Option<(Point3, Vector3)> {
+    let surface = face.oriented_surface();
+    let (u, v) = surface.search_nearest_parameter(point, SPHint2D::None, SURFACE_SEARCH_TRIALS)?;
+    Some((surface.subs(u, v), surface.normal(u, v)))
+}
+
+/// Check if the edges have the same end points (in either direction) and mid point, within [`TAG_MATCH_TOLERANCE`].
+///
+/// Mid points tell apart closed edges and different curves (eg: arc and line) with the same end points.
+fn is_same_edge(edge: &Edge, other: &Edge) -> bool {
+    let mid_point = |edge: &Edge| {
+        let curve = edge.oriented_curve();
+        let (t0, t1) = curve.range_tuple();
+        curve.subs((t0 + t1) / 2.)
+    };
+    let is_near = |a: Point3, b: Point3| a.distance(b) < TAG_MATCH_TOLERANCE;
+    let (front, back) = (edge.front().point(), edge.back().point());
+    let (other_front, other_back) = (other.front().point(), other.back().point());
+    ((is_near(front, other_front) && is_near(back, other_back))
+        || (is_near(front, other_back) && is_near(back, other_front)))
+        && is_near(mid_point(edge), mid_point(other))
+}
+
+/// Check if point lies within the triangle (within tolerance).
+fn is_point_in_triangle(point: Point3, triangle: &[Point3; 3], tol: f64) -> bool {
+    let [a, b, c] = *triangle;
+    let normal = (b - a).cross(c - a);
+    let area2 = normal.magnitude();
+    if area2 < f64::EPSILON {
+        return false;
+    }
+    let normal = normal / area2;
+    // Distance from the triangle plane...
+    if (point - a).dot(normal).abs() > tol {
+        return false;
+    }
+    // Barycentric coords using sub triangle areas...
+    let u = (c - b).cross(point - b).dot(normal) / area2;
+    let v = (a - c).cross(point - c).dot(normal) / area2;
+    let w = 1. - u - v;
+    let eps = -1e-9;
+    u >= eps && v >= eps && w >= eps
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use bevy::math::{DVec2, DVec3};
+    #[allow(unused_imports)]
+    use truck_modeling::builder;
+
+    /// Box from `min` with `size`, with its top and bottom faces tagged as `{name}Top`/`{name}Bottom`,
+    /// its vertical edges as `{name}SideEdge{index}` (from the `min` corner, counter-clockwise) and its
+    /// bottom wire as `{name}Wire`.
+    #[allow(dead_code)]
+    fn box_solid(name: &str, min: DVec3, size: DVec3) -> CadSolid {
+        let v = builder::vertex(Point3::new(min.x, min.y, min.z));
+        let e = builder::tsweep(&v, Vector3::unit_x() * size.x);
+        let f = builder::tsweep(&e, Vector3::unit_y() * size.y);
+        let mut cad_solid = CadSolid::new(builder::tsweep(&f, Vector3::unit_z() * size.z));
+        let is_at =
+            |point: Point3, x: f64, y: f64, z: f64| point.distance(Point3::new(x, y, z)) < 1e-6;
+        for (suffix, z) in [("Top", min.z + size.z), ("Bottom", min.z)] {
+            let face = cad_solid
+                .solid
+                .face_iter()
+                .find(|face| face.vertex_iter().all(|v| (v.point().z - z).abs() < 1e-6))
+                .unwrap()
+                .clone();
+            if suffix == "Bottom" {
+                cad_solid
+                    .set_tag(
+                        CadElementTag(format!("{}Wire", name)),
+                        CadElement::Wire(face.boundaries()[0].clone()),
+                    )
+                    .unwrap();
+            }
+            cad_solid
+                .set_tag(
+                    CadElementTag(format!("{}{}", name, suffix)),
+                    CadElement::Face(face),
+                )
+                .unwrap();
+        }
+        let max = min + size;
+        let corners = [
+            (min.x, min.y),
+            (max.x, min.y),
+            (max.x, max.y),
+            (min.x, max.y),
+        ];
+        for (idx, (x, y)) in corners.into_iter().enumerate() {
+            let edge = cad_solid
+                .solid
+                .edge_iter()
+                .find(|edge| {
+                    let (front, back) = (edge.front().point(), edge.back().point());
+                    (is_at(front, x, y, min.z) && is_at(back, x, y, max.z))
+                        || (is_at(front, x, y, max.z) && is_at(back, x, y, min.z))
+                })
+                .unwrap();
+            cad_solid
+                .set_tag(
+                    CadElementTag(format!("{}SideEdge{}", name, idx)),
+                    CadElement::Edge(edge),
+                )
+                .unwrap();
+        }
+        cad_solid
+    }
+
+    #[allow(dead_code)]
+    fn has_tag(cad_solid: &CadSolid, tag: &str) -> bool {
+        cad_solid
+            .get_element_by_tag(CadElementTag::new(tag))
+            .is_some()
+    }
+
+    #[test]
+    pub fn test_boolean_ops_carry_tags() {
+        let a = box_solid("A", DVec3::ZERO, DVec3::ONE);
+        let b = box_solid("B", DVec3::splat(0.5), DVec3::ONE);
+
+        let union = a.union(&b).unwrap();
+        assert!(has_tag(&union, "ATop"));
+        assert!(has_tag(&union, "BTop"));
+        assert!(has_tag(&union, "ABottom"));
+        // Side edge away from the other box is kept, the one inside it is cut...
+        assert!(has_tag(&union, "ASideEdge0"));
+        assert!(!has_tag(&union, "ASideEdge2"));
+        // Wire tags are dropped...
+        assert!(union
+            .tagged_elements
+            .values()
+            .all(|element| !matches!(element, CadElement::Wire(_))));
+
+        let difference = a.difference(&b).unwrap();
+        assert!(has_tag(&difference, "ATop"));
+        assert!(has_tag(&difference, "ASideEdge0"));
+        assert!(has_tag(&difference, "BBottom"));
+        assert!(!has_tag(&difference, "BTop"));
+
+        let intersection = a.intersection(&b).unwrap();
+        assert!(has_tag(&intersection, "ATop"));
+        assert!(has_tag(&intersection, "BBottom"));
+        assert!(!has_tag(&intersection, "ABottom"));
+        assert!(!has_tag(&intersection, "BTop"));
+        assert!(!has_tag(&intersection, "ASideEdge0"));
+    }
+
+    #[test]
+    pub fn test_boolean_op_split_face_tag() {
+        // Groove across the top face splits it into a narrow and a wide fragment...
+        let a = box_solid("A", DVec3::ZERO, DVec3::ONE);
+        let groove = box_solid(
+            "Groove",
+            DVec3::new(0.2, -0.5, 0.5),
+            DVec3::new(0.2, 2., 1.),
+        );
+        let grooved = a.difference(&groove).unwrap();
+        let Some(CadElement::Face(top)) = grooved.get_element_by_tag(CadElementTag::new("ATop"))
+        else {
+            panic!("Missing top face!");
+        };
+        // Tag is kept on the larger fragment...
+        assert!(top.vertex_iter().all(|v| v.point().x > 0.4 - 1e-6));
+    }
+
+    #[test]
+    pub fn test_boolean_retry_tolerance() {
+        // First tolerance succeeding...
+        assert_eq!(
+            with_boolean_tolerances(Some),
+            Some((TRUCK_BOOLEAN_TOLERANCE_1, TRUCK_BOOLEAN_TOLERANCE_1))
+        );
+        // Retried with the looser tolerance on failure...
+        let mut tried = vec![];
+        let result = with_boolean_tolerances(|tol| {
+            tried.push(tol);
+            (tol == TRUCK_BOOLEAN_RETRY_TOLERANCE_1).then_some("solid")
+        });
+        assert_eq!(result, Some(("solid", TRUCK_BOOLEAN_RETRY_TOLERANCE_1)));
+        assert_eq!(
+            tried,
+            vec![TRUCK_BOOLEAN_TOLERANCE_1, TRUCK_BOOLEAN_RETRY_TOLERANCE_1]
+        );
+        // Failing with both...
+        assert_eq!(with_boolean_tolerances(|_| None::<()>), None);
+    }
+
+    #[test]
+    pub fn test_is_same_edge() {
+        let v0 = builder::vertex(Point3::new(0., 0., 0.));
+        let v1 = builder::vertex(Point3::new(1., 0., 0.));
+        let line = builder::line(&v0, &v1);
+        // Same edge in either direction...
+        assert!(is_same_edge(&line, &line));
+        assert!(is_same_edge(&line, &line.inverse()));
+        // Arc with the same end points...
+        let arc = builder::circle_arc(&v0, &v1, Point3::new(0.5, 0.5, 0.));
+        assert!(!is_same_edge(&line, &arc));
+        // Ends within the boolean tolerance (but not the match tolerance)...
+        let v2 = builder::vertex(Point3::new(1. + TRUCK_BOOLEAN_TOLERANCE_1 / 2., 0., 0.));
+        assert!(!is_same_edge(&line, &builder::line(&v0, &v2)));
+    }
+}
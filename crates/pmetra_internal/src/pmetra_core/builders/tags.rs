@@ -8,7 +8,7 @@ use truck_modeling::{Edge, Face, Shell, Solid, Vertex, Wire};
 pub struct CadTaggedElements(pub HashMap<CadElementTag, CadElement>);
 
 /// Used for tagging a [`CadElement`]
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CadElementTag(pub String);
 
 impl CadElementTag {
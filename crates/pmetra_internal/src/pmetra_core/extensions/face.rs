@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
 use truck_meshalgo::{rexport_polymesh::PolygonMesh, tessellation::MeshedShape};
-use truck_modeling::{Face, Shell, Wire};
+use truck_modeling::{builder, Face, Shell, Surface, Wire};
 
 use crate::{
     constants::CUSTOM_TRUCK_TOLERANCE_1,
@@ -11,6 +11,9 @@ use crate::{
 pub trait FaceCadExtension: Sized {
     /// Gets the last boundary [`Wire`] from [`Face`].
     fn get_last_boundary_wire(&self) -> Result<Wire>;
+
+    /// Gets the planar [`Face`] offset along its (oriented) normal by `distance`.
+    fn offset_planar(&self, distance: f64) -> Result<Self>;
 }
 
 impl FaceCadExtension for Face {
@@ -22,6 +25,15 @@ impl FaceCadExtension for Face {
 
         Ok(wire.clone())
     }
+
+    fn offset_planar(&self, distance: f64) -> Result<Self> {
+        let Surface::Plane(plane) = self.oriented_surface() else {
+            return Err(anyhow!("Face is not planar!"));
+        };
+        let face = builder::translated(self, plane.normal() * distance);
+
+        Ok(face)
+    }
 }
 
 impl BuildPolygon for Face {
@@ -1,10 +1,22 @@
 use anyhow::{anyhow, Result};
-use truck_modeling::{builder, Wire};
+use bevy::math::DVec3;
+use truck_modeling::{builder, Vector3, Wire};
+
+use crate::pmetra_core::{
+    dimensions::AsBevyDVec3,
+    sketch::{Sketch, SketchPlane},
+};
 
 /// Extensions to [`Wire`] primitive.
 pub trait WireCadExtension: Sized {
     /// Gets the closed [`Wire`] using a line [`Edge`] connecting back vertex to front vertex.
     fn get_closed_wire_with_line(&self) -> Result<Self>;
+
+    /// Gets the offset of the closed planar [`Wire`] (with plane `normal`) by `distance`.
+    ///
+    /// Grows the enclosed area if positive (shrinks if negative). Edges should be lines or circular arcs.
+    /// Refer [`Sketch::offset`].
+    fn offset_planar(&self, distance: f64, normal: Vector3) -> Result<Self>;
 }
 
 impl WireCadExtension for Wire {
@@ -24,4 +36,31 @@ impl WireCadExtension for Wire {
 
         Ok(wire)
     }
+
+    fn offset_planar(&self, distance: f64, normal: Vector3) -> Result<Self> {
+        let first_edge = self
+            .front()
+            .ok_or_else(|| anyhow!("Could not get front edge!"))?;
+        let (origin, back) = (
+            first_edge.front().point().as_bevy_dvec3(),
+            first_edge.back().point().as_bevy_dvec3(),
+        );
+        let normal = DVec3::new(normal.x, normal.y, normal.z);
+        let x_axis = (back - origin).reject_from(normal);
+        let plane = SketchPlane::new(origin, x_axis, normal.cross(x_axis))?;
+        let sketch = Sketch::from_wire("Offset", plane, self)?;
+        let is_ccw = sketch.loops[0].signed_area() > 0.;
+        let mut wire = sketch
+            .offset(distance)?
+            .build()?
+            .wires
+            .pop()
+            .ok_or_else(|| anyhow!("Could not build offset wire!"))?;
+        // Keep the orientation of the wire (built ones are counter-clockwise)...
+        if !is_ccw {
+            wire.invert();
+        }
+
+        Ok(wire)
+    }
 }
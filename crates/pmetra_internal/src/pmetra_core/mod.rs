@@ -8,5 +8,10 @@ pub mod dimensions;
 pub mod extensions;
 /// Traits for meshing primitives into [`bevy::prelude::Mesh`] via [`PolygonMesh`].
 pub mod meshing;
+/// 2D sketches producing closed wires for extrusion.
+pub mod sketch;
+/// STEP export/import of [`builders::CadShell`]s.
+#[cfg(feature = "step")]
+pub mod step;
 /// Custom Tessellation adapted from [`truck_meshalgo::tessellation`].
 pub mod tessellation;
@@ -0,0 +1,372 @@
+use anyhow::{anyhow, Result};
+use bevy::math::DVec2;
+
+use crate::constants::{SKETCH_SOLVER_MAX_ITERATIONS, SKETCH_SOLVER_TOLERANCE};
+
+/// Id of a point in [`SketchConstraints`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SketchPointId(pub usize);
+
+/// Line between 2 points of [`SketchConstraints`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SketchLine(pub SketchPointId, pub SketchPointId);
+
+impl SketchLine {
+    pub fn new(start: SketchPointId, end: SketchPointId) -> Self {
+        Self(start, end)
+    }
+}
+
+/// Geometric constraint/dimension between the points/lines of [`SketchConstraints`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SketchConstraint {
+    /// Point is fixed at position.
+    Fixed(SketchPointId, DVec2),
+    /// Points are at the same position.
+    Coincident(SketchPointId, SketchPointId),
+    /// Line is parallel to the x axis.
+    Horizontal(SketchLine),
+    /// Line is parallel to the y axis.
+    Vertical(SketchLine),
+    Parallel(SketchLine, SketchLine),
+    Perpendicular(SketchLine, SketchLine),
+    /// Line is tangent to the circle/arc at `center` with `radius`.
+    Tangent {
+        line: SketchLine,
+        center: SketchPointId,
+        radius: f64,
+    },
+    /// Lines have equal lengths.
+    EqualLength(SketchLine, SketchLine),
+    /// Distance between the points.
+    Distance(SketchPointId, SketchPointId, f64),
+    /// Signed distance along x from the first to the second point.
+    HorizontalDistance(SketchPointId, SketchPointId, f64),
+    /// Signed distance along y from the first to the second point.
+    VerticalDistance(SketchPointId, SketchPointId, f64),
+    /// Angle (radians, counter-clockwise) from the first to the second line.
+    Angle(SketchLine, SketchLine, f64),
+}
+
+impl SketchConstraint {
+    /// Ids of all the points used in the constraint.
+    fn point_ids(&self) -> Vec<SketchPointId> {
+        match self {
+            SketchConstraint::Fixed(p, _) => vec![*p],
+            SketchConstraint::Coincident(p0, p1)
+            | SketchConstraint::Distance(p0, p1, _)
+            | SketchConstraint::HorizontalDistance(p0, p1, _)
+            | SketchConstraint::VerticalDistance(p0, p1, _) => vec![*p0, *p1],
+            SketchConstraint::Horizontal(l) | SketchConstraint::Vertical(l) => vec![l.0, l.1],
+            SketchConstraint::Parallel(l0, l1)
+            | SketchConstraint::Perpendicular(l0, l1)
+            | SketchConstraint::EqualLength(l0, l1)
+            | SketchConstraint::Angle(l0, l1, _) => vec![l0.0, l0.1, l1.0, l1.1],
+            SketchConstraint::Tangent { line, center, .. } => vec![line.0, line.1, *center],
+        }
+    }
+
+    /// Residuals of the constraint for point positions (all zero when satisfied).
+    fn residuals(&self, points: &[DVec2]) -> Vec<f64> {
+        let pt = |id: &SketchPointId| points[id.0];
+        let dir = |line: &SketchLine| pt(&line.1) - pt(&line.0);
+        let unit_dir = |line: &SketchLine| dir(line).try_normalize().unwrap_or(DVec2::ZERO);
+        match self {
+            SketchConstraint::Fixed(p, pos) => {
+                let offset = pt(p) - *pos;
+                vec![offset.x, offset.y]
+            }
+            SketchConstraint::Coincident(p0, p1) => {
+                let offset = pt(p1) - pt(p0);
+                vec![offset.x, offset.y]
+            }
+            SketchConstraint::Horizontal(line) => vec![dir(line).y],
+            SketchConstraint::Vertical(line) => vec![dir(line).x],
+            SketchConstraint::Parallel(l0, l1) => vec![unit_dir(l0).perp_dot(unit_dir(l1))],
+            SketchConstraint::Perpendicular(l0, l1) => vec![unit_dir(l0).dot(unit_dir(l1))],
+            SketchConstraint::Tangent {
+                line,
+                center,
+                radius,
+            } => {
+                let dist = unit_dir(line).perp_dot(pt(center) - pt(&line.0));
+                vec![dist.abs() - radius]
+            }
+            SketchConstraint::EqualLength(l0, l1) => vec![dir(l1).length() - dir(l0).length()],
+            SketchConstraint::Distance(p0, p1, dist) => vec![pt(p0).distance(pt(p1)) - dist],
+            SketchConstraint::HorizontalDistance(p0, p1, dist) => vec![pt(p1).x - pt(p0).x - dist],
+            SketchConstraint::VerticalDistance(p0, p1, dist) => vec![pt(p1).y - pt(p0).y - dist],
+            SketchConstraint::Angle(l0, l1, angle) => {
+                // sin(current - angle), zero when the angle is reached...
+                let (u0, u1) = (unit_dir(l0), unit_dir(l1));
+                vec![u0.perp_dot(u1) * angle.cos() - u0.dot(u1) * angle.sin()]
+            }
+        }
+    }
+}
+
+/// Points with [`SketchConstraint`]s, solved for the point positions.
+///
+/// Allows describing a profile by intent (dimensions/relations) instead of hand computed coordinates.
+/// Point positions passed in [`Self::add_point`] are the initial guesses, which also pick the
+/// solution when there are many (eg: which side of a line a point lies).
+///
+/// # Example
+///
+/// ```
+/// # use bevy::math::DVec2;
+/// # use pmetra_internal::prelude::*;
+/// # fn main() -> anyhow::Result<()> {
+/// let width = 2.;
+/// let mut constraints = SketchConstraints::default();
+/// let p0 = constraints.add_point(DVec2::ZERO);
+/// let p1 = constraints.add_point(DVec2::new(1., 0.1));
+/// let p2 = constraints.add_point(DVec2::new(0.1, 1.));
+/// let (base, side) = (SketchLine(p0, p1), SketchLine(p0, p2));
+/// let constraints = constraints
+///     .add_constraint(SketchConstraint::Fixed(p0, DVec2::ZERO))?
+///     .add_constraint(SketchConstraint::Horizontal(base))?
+///     .add_constraint(SketchConstraint::Distance(p0, p1, width))?
+///     .add_constraint(SketchConstraint::Perpendicular(base, side))?
+///     .add_constraint(SketchConstraint::Distance(p0, p2, width / 2.))?
+///     .solve()?;
+/// let profile = Sketch::new("Profile", SketchPlane::xy())
+///     .add_polygon(&constraints.points_of(&[p0, p1, p2]))?
+///     .build()?;
+/// assert_eq!(profile.wires.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SketchConstraints {
+    pub points: Vec<DVec2>,
+    pub constraints: Vec<SketchConstraint>,
+}
+
+impl SketchConstraints {
+    /// Add point with the (initial guess) position.
+    pub fn add_point(&mut self, position: DVec2) -> SketchPointId {
+        self.points.push(position);
+        SketchPointId(self.points.len() - 1)
+    }
+
+    pub fn add_constraint(&mut self, constraint: SketchConstraint) -> Result<Self> {
+        if let Some(id) = constraint
+            .point_ids()
+            .into_iter()
+            .find(|id| id.0 >= self.points.len())
+        {
+            return Err(anyhow!("Invalid sketch point id: {:?}", id));
+        }
+        self.constraints.push(constraint);
+        Ok(self.clone())
+    }
+
+    /// Get position of point.
+    pub fn point(&self, id: SketchPointId) -> DVec2 {
+        self.points[id.0]
+    }
+
+    /// Get positions of points in order (eg: for [`Sketch::add_polygon`](super::Sketch::add_polygon)).
+    pub fn points_of(&self, ids: &[SketchPointId]) -> Vec<DVec2> {
+        ids.iter().map(|id| self.point(*id)).collect()
+    }
+
+    /// Get (non-normalized) direction of line.
+    pub fn line_direction(&self, line: SketchLine) -> DVec2 {
+        self.point(line.1) - self.point(line.0)
+    }
+
+    pub fn line_length(&self, line: SketchLine) -> f64 {
+        self.line_direction(line).length()
+    }
+
+    /// Solve the point positions satisfying all the constraints.
+    ///
+    /// Uses Levenberg-Marquardt (damped least squares) starting from the current positions.
+    /// Under-constrained points move as little as needed. Fails if the constraints are inconsistent.
+    pub fn solve(&mut self) -> Result<Self> {
+        let mut vars = self
+            .points
+            .iter()
+            .flat_map(|point| [point.x, point.y])
+            .collect::<Vec<_>>();
+        let mut residuals = self.residuals(&vars);
+        let mut cost = squared_norm(&residuals);
+        let mut damping = 1e-3;
+        for _ in 0..SKETCH_SOLVER_MAX_ITERATIONS {
+            if cost.sqrt() < SKETCH_SOLVER_TOLERANCE {
+                break;
+            }
+            // Solve (JᵀJ + λI) δ = -Jᵀr ...
+            let columns = self.jacobian_columns(&vars, &residuals);
+            let lhs = columns
+                .iter()
+                .map(|col_i| columns.iter().map(|col_j| dot(col_i, col_j)).collect())
+                .collect::<Vec<Vec<f64>>>();
+            let rhs = columns
+                .iter()
+                .map(|col| -dot(col, &residuals))
+                .collect::<Vec<_>>();
+            let mut improved = false;
+            while damping < 1e12 {
+                let mut damped_lhs = lhs.clone();
+                for (i, lhs_row) in damped_lhs.iter_mut().enumerate() {
+                    lhs_row[i] += damping;
+                }
+                let Some(step) = solve_linear(damped_lhs, rhs.clone()) else {
+                    damping *= 10.;
+                    continue;
+                };
+                let new_vars = vars
+                    .iter()
+                    .zip(step.iter())
+                    .map(|(var, delta)| var + delta)
+                    .collect::<Vec<_>>();
+                let new_residuals = self.residuals(&new_vars);
+                let new_cost = squared_norm(&new_residuals);
+                if new_cost < cost {
+                    (vars, residuals, cost) = (new_vars, new_residuals, new_cost);
+                    damping = (damping / 10.).max(1e-12);
+                    improved = true;
+                    break;
+                }
+                damping *= 10.;
+            }
+            if !improved {
+                break;
+            }
+        }
+        if cost.sqrt() >= SKETCH_SOLVER_TOLERANCE {
+            return Err(anyhow!(
+                "Sketch constraints could not be solved (residual: {})! Check for inconsistent constraints.",
+                cost.sqrt()
+            ));
+        }
+        self.points = vars
+            .chunks(2)
+            .map(|var| DVec2::new(var[0], var[1]))
+            .collect();
+        Ok(self.clone())
+    }
+
+    fn residuals(&self, vars: &[f64]) -> Vec<f64> {
+        let points = vars
+            .chunks(2)
+            .map(|var| DVec2::new(var[0], var[1]))
+            .collect::<Vec<_>>();
+        self.constraints
+            .iter()
+            .flat_map(|constraint| constraint.residuals(&points))
+            .collect()
+    }
+
+    /// Columns (per variable) of the jacobian via forward differences.
+    fn jacobian_columns(&self, vars: &[f64], residuals: &[f64]) -> Vec<Vec<f64>> {
+        (0..vars.len())
+            .map(|col| {
+                let mut stepped_vars = vars.to_vec();
+                let step = 1e-8 * vars[col].abs().max(1.);
+                stepped_vars[col] += step;
+                self.residuals(&stepped_vars)
+                    .iter()
+                    .zip(residuals)
+                    .map(|(stepped, residual)| (stepped - residual) / step)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn squared_norm(values: &[f64]) -> f64 {
+    values.iter().map(|value| value * value).sum()
+}
+
+/// Solve the linear system via gaussian elimination (with partial pivoting).
+fn solve_linear(mut lhs: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let len = rhs.len();
+    for col in 0..len {
+        let pivot = (col..len).max_by(|a, b| lhs[*a][col].abs().total_cmp(&lhs[*b][col].abs()))?;
+        if lhs[pivot][col].abs() < f64::EPSILON {
+            return None;
+        }
+        lhs.swap(col, pivot);
+        rhs.swap(col, pivot);
+        let (pivot_row, pivot_rhs) = (lhs[col].clone(), rhs[col]);
+        for (lhs_row, rhs_value) in lhs.iter_mut().zip(rhs.iter_mut()).skip(col + 1) {
+            let factor = lhs_row[col] / pivot_row[col];
+            for (value, pivot_value) in lhs_row.iter_mut().zip(&pivot_row).skip(col) {
+                *value -= factor * pivot_value;
+            }
+            *rhs_value -= factor * pivot_rhs;
+        }
+    }
+    let mut solution = vec![0.; len];
+    for (row, (lhs_row, rhs_value)) in lhs.iter().zip(&rhs).enumerate().rev() {
+        let sum = dot(&lhs_row[row + 1..], &solution[row + 1..]);
+        solution[row] = (rhs_value - sum) / lhs_row[row];
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    pub fn test_solve_sketch_constraints_rect() {
+        let mut constraints = SketchConstraints::default();
+        // Rough guesses of a 2 x 1 rectangle...
+        let p0 = constraints.add_point(DVec2::new(0.1, -0.1));
+        let p1 = constraints.add_point(DVec2::new(1.8, 0.2));
+        let p2 = constraints.add_point(DVec2::new(2.2, 1.1));
+        let p3 = constraints.add_point(DVec2::new(-0.2, 0.9));
+        let (l0, l1, l2, l3) = (
+            SketchLine(p0, p1),
+            SketchLine(p1, p2),
+            SketchLine(p2, p3),
+            SketchLine(p3, p0),
+        );
+        let mut constraints = constraints
+            .add_constraint(SketchConstraint::Fixed(p0, DVec2::ZERO))
+            .unwrap()
+            .add_constraint(SketchConstraint::Horizontal(l0))
+            .unwrap()
+            .add_constraint(SketchConstraint::Perpendicular(l0, l1))
+            .unwrap()
+            .add_constraint(SketchConstraint::Parallel(l0, l2))
+            .unwrap()
+            .add_constraint(SketchConstraint::Vertical(l3))
+            .unwrap()
+            .add_constraint(SketchConstraint::Distance(p0, p1, 2.))
+            .unwrap()
+            .add_constraint(SketchConstraint::EqualLength(l1, l3))
+            .unwrap()
+            .add_constraint(SketchConstraint::VerticalDistance(p0, p3, 1.))
+            .unwrap()
+            .solve()
+            .unwrap();
+
+        let expected = [
+            DVec2::ZERO,
+            DVec2::new(2., 0.),
+            DVec2::new(2., 1.),
+            DVec2::new(0., 1.),
+        ];
+        for (point, expected) in constraints.points.iter().zip(expected) {
+            assert!(point.distance(expected) < 1e-6, "{point} != {expected}");
+        }
+        // Inconsistent constraints should fail...
+        assert!(constraints
+            .add_constraint(SketchConstraint::Distance(p0, p2, 5.))
+            .unwrap()
+            .solve()
+            .is_err());
+    }
+}
@@ -0,0 +1,655 @@
+use std::f64::consts::PI;
+
+use anyhow::{anyhow, Context, Result};
+use bevy::math::{DVec2, DVec3};
+use truck_meshalgo::prelude::TOLERANCE;
+use truck_modeling::{
+    builder, BoundedCurve, Curve, Face, MetricSpace, ParametricCurve, Point3, Shell, Vector3,
+    Vertex, Wire,
+};
+
+use crate::pmetra_core::{
+    builders::{CadElement, CadElementTag, CadShell, CadTaggedElements},
+    dimensions::AsBevyDVec3,
+    extensions::shell::ShellCadExtension,
+};
+
+/// Geometric constraints solver for sketch points.
+pub mod constraints;
+/// Offsetting of sketch loops.
+mod offset;
+/// Placement plane of the sketch.
+pub mod plane;
+
+pub use {constraints::*, offset::SketchOffsetArcs, plane::*};
+
+/// Segment of a [`SketchLoop`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SketchSegment {
+    /// Straight line from `start` to `end`.
+    Line { start: DVec2, end: DVec2 },
+    /// Circular arc from `start` to `end` passing through `transit`.
+    Arc {
+        start: DVec2,
+        transit: DVec2,
+        end: DVec2,
+    },
+}
+
+impl SketchSegment {
+    pub fn start(&self) -> DVec2 {
+        match self {
+            SketchSegment::Line { start, .. } | SketchSegment::Arc { start, .. } => *start,
+        }
+    }
+
+    pub fn end(&self) -> DVec2 {
+        match self {
+            SketchSegment::Line { end, .. } | SketchSegment::Arc { end, .. } => *end,
+        }
+    }
+
+    pub fn set_start(&mut self, point: DVec2) {
+        match self {
+            SketchSegment::Line { start, .. } | SketchSegment::Arc { start, .. } => *start = point,
+        }
+    }
+
+    pub fn set_end(&mut self, point: DVec2) {
+        match self {
+            SketchSegment::Line { end, .. } | SketchSegment::Arc { end, .. } => *end = point,
+        }
+    }
+
+    /// Points along the segment (start, transit if any, end). Used for orientation checks.
+    fn points(&self) -> Vec<DVec2> {
+        match self {
+            SketchSegment::Line { start, end } => vec![*start, *end],
+            SketchSegment::Arc {
+                start,
+                transit,
+                end,
+            } => vec![*start, *transit, *end],
+        }
+    }
+}
+
+/// [`SketchSegment`] with an optional [`CadElementTag`] for its edge.
+#[derive(Debug, Clone)]
+pub struct SketchEdge {
+    pub segment: SketchSegment,
+    /// Tag of the edge. Auto generated on build if `None`. Refer [`Sketch::build`].
+    pub tag: Option<CadElementTag>,
+}
+
+/// Chain of [`SketchEdge`]s starting at `start`.
+///
+/// Only closed loops can be built into [`Wire`]s.
+#[derive(Debug, Clone, Default)]
+pub struct SketchLoop {
+    pub start: DVec2,
+    pub edges: Vec<SketchEdge>,
+    pub closed: bool,
+}
+
+impl SketchLoop {
+    /// End of the last edge (or start if no edges).
+    pub fn cursor(&self) -> DVec2 {
+        self.edges
+            .last()
+            .map(|edge| edge.segment.end())
+            .unwrap_or(self.start)
+    }
+
+    /// Signed area (positive for counter-clockwise loops).
+    pub fn signed_area(&self) -> f64 {
+        let points = self
+            .edges
+            .iter()
+            .flat_map(|edge| {
+                let mut points = edge.segment.points();
+                points.pop();
+                points
+            })
+            .collect::<Vec<_>>();
+        let area2 = (0..points.len())
+            .map(|idx| points[idx].perp_dot(points[(idx + 1) % points.len()]))
+            .sum::<f64>();
+        area2 / 2.
+    }
+}
+
+/// Construction geometry of the [`Sketch`].
+///
+/// Not part of the profile [`Wire`]s, but built as tagged elements (eg: for placing sliders).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SketchConstruction {
+    Point(DVec2),
+    Line { start: DVec2, end: DVec2 },
+}
+
+/// 2D sketch on a [`SketchPlane`].
+///
+/// Draw closed loops with lines, arcs, circles and fillets, then [`Sketch::build`] them into
+/// closed [`Wire`]s with tagged edges, ready for extrusion.
+///
+/// The first loop is the outer boundary of the profile, the rest are holes.
+///
+/// # Example
+///
+/// ```ignore
+/// let profile = Sketch::new("Profile", SketchPlane::xy())
+///     .move_to(DVec2::ZERO)?
+///     .line_to(DVec2::new(2., 0.))?
+///     .tag_last(CadElementTag::new("BottomEdge"))?
+///     .line_to(DVec2::new(2., 1.))?
+///     .arc_around(DVec2::new(1., 1.), PI)?
+///     .close()?
+///     .add_circle(DVec2::new(1., 0.5), 0.2)?
+///     .build()?;
+/// let cad_shell = profile.extrude(1.)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Sketch {
+    /// Name of the sketch. Used as prefix for the auto generated tags.
+    pub name: String,
+    pub plane: SketchPlane,
+    pub loops: Vec<SketchLoop>,
+    pub construction: Vec<(CadElementTag, SketchConstruction)>,
+}
+
+impl Sketch {
+    pub fn new(name: &str, plane: SketchPlane) -> Self {
+        Self {
+            name: name.to_string(),
+            plane,
+            ..Default::default()
+        }
+    }
+
+    /// Create new [`Sketch`] with a closed loop from the [`Wire`] (on the plane).
+    ///
+    /// Edges should be lines or circular arcs (other curves are treated as arcs through their mid points).
+    pub fn from_wire(name: &str, plane: SketchPlane, wire: &Wire) -> Result<Self> {
+        if !wire.is_closed() {
+            return Err(anyhow!("Wire is not closed!"));
+        }
+        let mut sketch = Self::new(name, plane);
+        for edge in wire.edge_iter() {
+            let (front, back) = (
+                plane.to_sketch(edge.front().point().as_bevy_dvec3()),
+                plane.to_sketch(edge.back().point().as_bevy_dvec3()),
+            );
+            if sketch.loops.is_empty() {
+                sketch.move_to(front)?;
+            }
+            let curve = edge.oriented_curve();
+            if matches!(curve, Curve::Line(_)) {
+                sketch.line_to(back)?;
+            } else {
+                let (t0, t1) = curve.range_tuple();
+                let transit = plane.to_sketch(curve.subs((t0 + t1) / 2.).as_bevy_dvec3());
+                sketch.arc_to(transit, back)?;
+            }
+        }
+        sketch.close()
+    }
+
+    /// Start a new loop at `point`.
+    ///
+    /// The previous loop (if any) must be closed.
+    pub fn move_to(&mut self, point: DVec2) -> Result<Self> {
+        match self.loops.last_mut() {
+            Some(last) if !last.closed && !last.edges.is_empty() => {
+                return Err(anyhow!(
+                    "Sketch loop is not closed! Call `close` before starting a new loop."
+                ));
+            }
+            Some(last) if !last.closed => last.start = point,
+            _ => self.loops.push(SketchLoop {
+                start: point,
+                ..Default::default()
+            }),
+        }
+        Ok(self.clone())
+    }
+
+    /// Add line from the current point to `point`.
+    pub fn line_to(&mut self, point: DVec2) -> Result<Self> {
+        let current_loop = self.open_loop_mut()?;
+        let start = current_loop.cursor();
+        if start.distance(point) < TOLERANCE {
+            return Err(anyhow!("Sketch line has zero length at: {:?}", point));
+        }
+        current_loop.edges.push(SketchEdge {
+            segment: SketchSegment::Line { start, end: point },
+            tag: None,
+        });
+        Ok(self.clone())
+    }
+
+    /// Add arc from the current point to `end` passing through `transit`.
+    pub fn arc_to(&mut self, transit: DVec2, end: DVec2) -> Result<Self> {
+        let current_loop = self.open_loop_mut()?;
+        let start = current_loop.cursor();
+        if (transit - start).perp_dot(end - start).abs() < TOLERANCE {
+            return Err(anyhow!("Sketch arc points are collinear!"));
+        }
+        current_loop.edges.push(SketchEdge {
+            segment: SketchSegment::Arc {
+                start,
+                transit,
+                end,
+            },
+            tag: None,
+        });
+        Ok(self.clone())
+    }
+
+    /// Add arc from the current point around `center` by `angle` (radians, counter-clockwise if positive).
+    ///
+    /// `angle` must be less than a full turn (use [`Sketch::add_circle`] for circles).
+    pub fn arc_around(&mut self, center: DVec2, angle: f64) -> Result<Self> {
+        if angle.abs() < TOLERANCE || angle.abs() >= 2. * PI {
+            return Err(anyhow!("Invalid sketch arc angle: {}", angle));
+        }
+        let start = self.open_loop_mut()?.cursor();
+        let radius_vec = start - center;
+        let transit = center + DVec2::from_angle(angle / 2.).rotate(radius_vec);
+        let end = center + DVec2::from_angle(angle).rotate(radius_vec);
+        self.arc_to(transit, end)
+    }
+
+    /// Close the current loop, adding a line back to its start if needed.
+    pub fn close(&mut self) -> Result<Self> {
+        let current_loop = self.open_loop_mut()?;
+        let (start, cursor) = (current_loop.start, current_loop.cursor());
+        if start.distance(cursor) >= TOLERANCE {
+            current_loop.edges.push(SketchEdge {
+                segment: SketchSegment::Line {
+                    start: cursor,
+                    end: start,
+                },
+                tag: None,
+            });
+        } else if let Some(last) = current_loop.edges.last_mut() {
+            // Snap the last edge to the start...
+            last.segment.set_end(start);
+        }
+        if current_loop.edges.len() < 2 {
+            return Err(anyhow!("Sketch loop needs at least 2 edges to close!"));
+        }
+        current_loop.closed = true;
+        Ok(self.clone())
+    }
+
+    /// Tag the last added edge.
+    pub fn tag_last(&mut self, tag: CadElementTag) -> Result<Self> {
+        let last = self
+            .loops
+            .last_mut()
+            .and_then(|last| last.edges.last_mut())
+            .ok_or_else(|| anyhow!("No sketch edge to tag!"))?;
+        last.tag = Some(tag);
+        Ok(self.clone())
+    }
+
+    /// Add closed polygon loop through `points`.
+    pub fn add_polygon(&mut self, points: &[DVec2]) -> Result<Self> {
+        let [first, rest @ ..] = points else {
+            return Err(anyhow!("Sketch polygon has no points!"));
+        };
+        if rest.len() < 2 {
+            return Err(anyhow!("Sketch polygon needs at least 3 points!"));
+        }
+        self.move_to(*first)?;
+        for point in rest {
+            self.line_to(*point)?;
+        }
+        self.close()
+    }
+
+    /// Add closed rectangle loop from `min` to `max` corners.
+    pub fn add_rect(&mut self, min: DVec2, max: DVec2) -> Result<Self> {
+        self.add_polygon(&[min, DVec2::new(max.x, min.y), max, DVec2::new(min.x, max.y)])
+    }
+
+    /// Add closed circle loop (made of 2 arcs).
+    pub fn add_circle(&mut self, center: DVec2, radius: f64) -> Result<Self> {
+        if !radius.is_finite() || radius < TOLERANCE {
+            return Err(anyhow!("Invalid sketch circle radius: {}", radius));
+        }
+        self.move_to(center + DVec2::X * radius)?;
+        self.arc_around(center, PI)?;
+        self.arc_around(center, PI)?;
+        self.close()
+    }
+
+    /// Round the corner (at the start of edge with `corner` index) of the last loop with an arc of `radius`.
+    ///
+    /// The loop must be closed and both edges at the corner must be lines.
+    pub fn fillet(&mut self, corner: usize, radius: f64) -> Result<Self> {
+        if !radius.is_finite() || radius <= TOLERANCE {
+            return Err(anyhow!("Invalid sketch fillet radius: {}", radius));
+        }
+        let last = self
+            .loops
+            .last_mut()
+            .filter(|last| last.closed)
+            .ok_or_else(|| anyhow!("Sketch fillet needs a closed loop!"))?;
+        let edges_len = last.edges.len();
+        if corner >= edges_len {
+            return Err(anyhow!("Invalid sketch fillet corner: {}", corner));
+        }
+        let prev_idx = (corner + edges_len - 1) % edges_len;
+        let (
+            SketchSegment::Line {
+                start: prev_start,
+                end: point,
+            },
+            SketchSegment::Line { end: next_end, .. },
+        ) = (
+            last.edges[prev_idx].segment.clone(),
+            last.edges[corner].segment.clone(),
+        )
+        else {
+            return Err(anyhow!(
+                "Sketch fillet corner {} is not between lines!",
+                corner
+            ));
+        };
+        let dir_in = (point - prev_start).normalize();
+        let dir_out = (next_end - point).normalize();
+        let angle = (-dir_in).dot(dir_out).clamp(-1., 1.).acos();
+        if angle < TOLERANCE || PI - angle < TOLERANCE {
+            return Err(anyhow!("Sketch fillet corner {} is straight!", corner));
+        }
+        let trim = radius / (angle / 2.).tan();
+        if trim >= point.distance(prev_start) || trim >= point.distance(next_end) {
+            return Err(anyhow!(
+                "Sketch fillet radius {} is too large for corner {}!",
+                radius,
+                corner
+            ));
+        }
+        let bisector = (dir_out - dir_in).normalize();
+        let center = point + bisector * radius / (angle / 2.).sin();
+        let (arc_start, arc_end) = (point - dir_in * trim, point + dir_out * trim);
+        if let SketchSegment::Line { end, .. } = &mut last.edges[prev_idx].segment {
+            *end = arc_start;
+        }
+        if let SketchSegment::Line { start, .. } = &mut last.edges[corner].segment {
+            *start = arc_end;
+        }
+        last.edges.insert(
+            corner,
+            SketchEdge {
+                segment: SketchSegment::Arc {
+                    start: arc_start,
+                    transit: center - bisector * radius,
+                    end: arc_end,
+                },
+                tag: None,
+            },
+        );
+        last.start = last.edges[0].segment.start();
+        Ok(self.clone())
+    }
+
+    /// Round all the corners between lines of the last loop with arcs of `radius`.
+    ///
+    /// The sketch is left unchanged if any of the corners can't be rounded.
+    pub fn fillet_all(&mut self, radius: f64) -> Result<Self> {
+        let last = self
+            .loops
+            .last()
+            .filter(|last| last.closed)
+            .ok_or_else(|| anyhow!("Sketch fillet needs a closed loop!"))?;
+        let edges_len = last.edges.len();
+        let is_line = |idx: usize| matches!(last.edges[idx].segment, SketchSegment::Line { .. });
+        let corners = (0..edges_len)
+            .filter(|corner| is_line(*corner) && is_line((corner + edges_len - 1) % edges_len))
+            .collect::<Vec<_>>();
+        // Fillet in reverse so the inserted arcs do not shift the pending corners...
+        let mut filleted = self.clone();
+        for corner in corners.into_iter().rev() {
+            filleted.fillet(corner, radius)?;
+        }
+        *self = filleted;
+        Ok(self.clone())
+    }
+
+    /// Add tagged construction point.
+    pub fn add_construction_point(&mut self, tag: CadElementTag, point: DVec2) -> Result<Self> {
+        self.construction
+            .push((tag, SketchConstruction::Point(point)));
+        Ok(self.clone())
+    }
+
+    /// Add tagged construction line.
+    pub fn add_construction_line(
+        &mut self,
+        tag: CadElementTag,
+        start: DVec2,
+        end: DVec2,
+    ) -> Result<Self> {
+        if start.distance(end) < TOLERANCE {
+            return Err(anyhow!("Sketch construction line has zero length!"));
+        }
+        self.construction
+            .push((tag, SketchConstruction::Line { start, end }));
+        Ok(self.clone())
+    }
+
+    /// Build the loops into closed [`Wire`]s.
+    ///
+    /// Wires are oriented so the outer (first) one is counter-clockwise and the holes are clockwise.
+    /// All edges are tagged. Untagged ones get auto generated tags: `{name}Edge{index}`
+    /// (index counts edges across all loops in order). Wires are tagged as `{name}Wire{index}`.
+    pub fn build(&self) -> Result<SketchProfile> {
+        if self.loops.is_empty() {
+            return Err(anyhow!("Sketch has no loops!"));
+        }
+        let mut tagged_elements = CadTaggedElements::default();
+        let mut insert_tag = |tag: CadElementTag, element: CadElement| {
+            if tagged_elements.insert(tag.clone(), element).is_some() {
+                return Err(anyhow!("Duplicate sketch tag: {:?}", tag));
+            }
+            Ok(())
+        };
+        let mut wires = vec![];
+        let mut profile_vertices = vec![];
+        let mut edge_idx = 0;
+        for (loop_idx, sketch_loop) in self.loops.iter().enumerate() {
+            if !sketch_loop.closed {
+                return Err(anyhow!("Sketch loop {} is not closed!", loop_idx));
+            }
+            let vertices = sketch_loop
+                .edges
+                .iter()
+                .map(|edge| builder::vertex(self.plane.point3(edge.segment.start())))
+                .collect::<Vec<_>>();
+            let mut wire = Wire::new();
+            for (idx, edge) in sketch_loop.edges.iter().enumerate() {
+                let (v0, v1) = (&vertices[idx], &vertices[(idx + 1) % vertices.len()]);
+                let cad_edge = match &edge.segment {
+                    SketchSegment::Line { .. } => builder::line(v0, v1),
+                    SketchSegment::Arc { transit, .. } => {
+                        builder::circle_arc(v0, v1, self.plane.point3(*transit))
+                    }
+                };
+                let tag = edge
+                    .tag
+                    .clone()
+                    .unwrap_or_else(|| CadElementTag(format!("{}Edge{}", self.name, edge_idx)));
+                insert_tag(tag, CadElement::Edge(cad_edge.clone()))?;
+                wire.push_back(cad_edge);
+                edge_idx += 1;
+            }
+            if !wire.is_closed() || !wire.is_simple() {
+                return Err(anyhow!(
+                    "Sketch loop {} is not a simple closed wire!",
+                    loop_idx
+                ));
+            }
+            let is_ccw = sketch_loop.signed_area() > 0.;
+            if is_ccw != (loop_idx == 0) {
+                wire.invert();
+            }
+            insert_tag(
+                CadElementTag(format!("{}Wire{}", self.name, loop_idx)),
+                CadElement::Wire(wire.clone()),
+            )?;
+            wires.push(wire);
+            profile_vertices.extend(vertices);
+        }
+        for (tag, construction) in self.construction.iter() {
+            let element = match construction {
+                SketchConstruction::Point(point) => {
+                    CadElement::Vertex(builder::vertex(self.plane.point3(*point)))
+                }
+                SketchConstruction::Line { start, end } => CadElement::Edge(builder::line(
+                    &builder::vertex(self.plane.point3(*start)),
+                    &builder::vertex(self.plane.point3(*end)),
+                )),
+            };
+            insert_tag(tag.clone(), element)?;
+        }
+
+        Ok(SketchProfile {
+            name: self.name.clone(),
+            plane: self.plane,
+            wires,
+            vertices: profile_vertices,
+            tagged_elements,
+        })
+    }
+
+    /// Get the open (last) loop.
+    fn open_loop_mut(&mut self) -> Result<&mut SketchLoop> {
+        self.loops
+            .last_mut()
+            .filter(|last| !last.closed)
+            .ok_or_else(|| anyhow!("No open sketch loop! Call `move_to` first."))
+    }
+}
+
+/// Closed [`Wire`]s with [`CadTaggedElements`] built from a [`Sketch`].
+#[derive(Debug, Clone)]
+pub struct SketchProfile {
+    /// Name of the [`Sketch`].
+    pub name: String,
+    pub plane: SketchPlane,
+    /// The first wire is the outer boundary, the rest are holes.
+    pub wires: Vec<Wire>,
+    /// Vertices at the start of each edge (same order as the edge tag indices).
+    pub vertices: Vec<Vertex>,
+    pub tagged_elements: CadTaggedElements,
+}
+
+impl SketchProfile {
+    /// Planar [`Face`] of the profile, facing along the [`SketchPlane::normal`].
+    pub fn face(&self) -> Result<Face> {
+        builder::try_attach_plane(&self.wires).with_context(|| "Could not attach plane to sketch!")
+    }
+
+    /// Extrude the profile along the [`SketchPlane::normal`] by `length` (negative to extrude backwards).
+    ///
+    /// The [`CadShell`] keeps the tags of the sketch. The side edges (swept from the [`Self::vertices`])
+    /// are tagged as `{name}SideEdge{index}`.
+    pub fn extrude(&self, length: f64) -> Result<CadShell> {
+        self.extrude_along(self.plane.normal() * length)
+    }
+
+    /// Extrude the profile along `direction` (should not be parallel to the plane).
+    pub fn extrude_along(&self, direction: DVec3) -> Result<CadShell> {
+        let mut face = self.face()?;
+        if direction.dot(self.plane.normal()) < 0. {
+            face.invert();
+        }
+        let solid = builder::tsweep(&face, Vector3::from(direction.to_array()));
+        let shell = Shell::try_from_solid(&solid)?;
+
+        let mut tagged_elements = self.tagged_elements.clone();
+        for (idx, vertex) in self.vertices.iter().enumerate() {
+            let start = vertex.point();
+            let end = start + Vector3::from(direction.to_array());
+            let Some(side_edge) = shell.edge_iter().find(|edge| {
+                let (front, back) = (edge.front().point(), edge.back().point());
+                (is_same_point(front, start) && is_same_point(back, end))
+                    || (is_same_point(front, end) && is_same_point(back, start))
+            }) else {
+                continue;
+            };
+            tagged_elements.insert(
+                CadElementTag(format!("{}SideEdge{}", self.name, idx)),
+                CadElement::Edge(side_edge),
+            );
+        }
+
+        Ok(CadShell {
+            shell,
+            tagged_elements,
+        })
+    }
+}
+
+fn is_same_point(a: Point3, b: Point3) -> bool {
+    a.distance(b) < TOLERANCE
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    pub fn test_sketch_rounded_rect_with_hole() {
+        let profile = Sketch::new("Profile", SketchPlane::xy())
+            .add_rect(DVec2::ZERO, DVec2::new(2., 1.))
+            .unwrap()
+            .fillet_all(0.1)
+            .unwrap()
+            .add_circle(DVec2::new(1., 0.5), 0.2)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(profile.wires.len(), 2);
+        assert_eq!(profile.wires[0].len(), 8);
+        assert_eq!(profile.wires[1].len(), 2);
+        assert!(profile.wires.iter().all(|wire| wire.is_closed()));
+        // 10 edges + 2 wires...
+        assert_eq!(profile.tagged_elements.len(), 12);
+        assert!(profile
+            .tagged_elements
+            .contains_key(&CadElementTag::new("ProfileEdge9")));
+        assert!(profile.extrude(1.).is_ok());
+    }
+
+    #[test]
+    pub fn test_sketch_fillet_invalid_radius() {
+        let mut sketch = Sketch::new("Profile", SketchPlane::xy())
+            .add_rect(DVec2::ZERO, DVec2::new(2., 1.))
+            .unwrap();
+        for radius in [0., -0.1, f64::NAN, f64::INFINITY] {
+            let error = sketch.fillet(0, radius).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("Invalid sketch fillet radius: {}", radius)
+            );
+            assert!(sketch.clone().add_circle(DVec2::ONE, radius).is_err());
+        }
+        assert!(sketch.fillet_all(0.).is_err());
+        // Fits the first 2 corners but not the 3rd (whose edge the 1st fillet shortened),
+        // leaving the sketch unchanged...
+        let error = sketch.fillet_all(0.6).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Sketch fillet radius 0.6 is too large for corner 1!"
+        );
+        assert_eq!(sketch.loops[0].edges.len(), 4);
+        assert!(sketch.fillet(0, 0.1).is_ok());
+    }
+}
@@ -0,0 +1,396 @@
+use anyhow::{anyhow, Result};
+use bevy::math::DVec2;
+use truck_meshalgo::prelude::TOLERANCE;
+
+use super::{Sketch, SketchEdge, SketchLoop, SketchSegment};
+
+/// How the arcs of a [`Sketch`] are offset. Refer [`Sketch::offset_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SketchOffsetArcs {
+    /// Arcs stay concentric, their radius changing by the offset distance.
+    #[default]
+    Concentric,
+    /// Arcs keep their radius, moving along with the corner of their neighbouring lines.
+    ///
+    /// Eg: for walls with the same inner and outer corner radius (thicker at the corners).
+    KeepRadius,
+}
+
+impl Sketch {
+    /// Offset all the loops by `distance`, growing the profile if positive (shrinking if negative).
+    ///
+    /// The outer (first) loop moves outwards and the holes move inwards for positive `distance`.
+    /// Lines move along their normals and meet at their new intersections, arcs stay concentric.
+    /// Arcs collapsing to zero radius are removed (leaving a sharp corner).
+    ///
+    /// Edge tags are not carried forward (the offset edges get auto generated tags on build).
+    pub fn offset(&self, distance: f64) -> Result<Self> {
+        self.offset_with(distance, SketchOffsetArcs::Concentric)
+    }
+
+    /// Offset all the loops by `distance`, with the `arcs` offset as given. Refer [`Sketch::offset`].
+    pub fn offset_with(&self, distance: f64, arcs: SketchOffsetArcs) -> Result<Self> {
+        if !distance.is_finite() {
+            return Err(anyhow!("Invalid sketch offset distance: {}", distance));
+        }
+        let loops = self
+            .loops
+            .iter()
+            .enumerate()
+            .map(|(idx, sketch_loop)| {
+                offset_loop(
+                    sketch_loop,
+                    if idx == 0 { distance } else { -distance },
+                    arcs,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            loops,
+            ..self.clone()
+        })
+    }
+
+    /// Thicken the (single) loop inwards into a wall of `thickness`, by adding its offset loop as a hole.
+    pub fn thicken(&self, thickness: f64) -> Result<Self> {
+        self.thicken_with(thickness, SketchOffsetArcs::Concentric)
+    }
+
+    /// Thicken the (single) loop, with the `arcs` offset as given. Refer [`Sketch::thicken`].
+    ///
+    /// Use [`SketchOffsetArcs::KeepRadius`] for the inner corners to keep the outer corner radius.
+    pub fn thicken_with(&self, thickness: f64, arcs: SketchOffsetArcs) -> Result<Self> {
+        let [outer] = self.loops.as_slice() else {
+            return Err(anyhow!("Sketch thicken needs a single loop!"));
+        };
+        if !thickness.is_finite() || thickness < TOLERANCE {
+            return Err(anyhow!("Invalid sketch thickness: {}", thickness));
+        }
+        let inner = offset_loop(outer, -thickness, arcs)?;
+        Ok(Self {
+            loops: vec![outer.clone(), inner],
+            ..self.clone()
+        })
+    }
+}
+
+/// Offset the closed loop by `distance` (outwards of the enclosed area if positive).
+fn offset_loop(
+    sketch_loop: &SketchLoop,
+    distance: f64,
+    arcs: SketchOffsetArcs,
+) -> Result<SketchLoop> {
+    if !sketch_loop.closed {
+        return Err(anyhow!("Sketch offset needs closed loops!"));
+    }
+    // Outwards is on the right of the edges for counter-clockwise loops...
+    let right_offset = if sketch_loop.signed_area() > 0. {
+        distance
+    } else {
+        -distance
+    };
+    let mut segments = vec![];
+    for edge in sketch_loop.edges.iter() {
+        match edge.segment {
+            SketchSegment::Line { start, end } => {
+                let right = -(end - start).normalize().perp();
+                segments.push((
+                    SketchSegment::Line {
+                        start: start + right * right_offset,
+                        end: end + right * right_offset,
+                    },
+                    end - start,
+                ));
+            }
+            SketchSegment::Arc {
+                start,
+                transit,
+                end,
+            } => {
+                let center = circle_center(start, transit, end)?;
+                let radius = start.distance(center);
+                // Arcs turning left have their center on the left...
+                let is_ccw = (transit - start).perp_dot(end - transit) > 0.;
+                if arcs == SketchOffsetArcs::KeepRadius {
+                    // Move with the (tangent) lines at its ends, ie: by the offset along both
+                    // the right normals (radial for arcs) at the ends...
+                    let sign = if is_ccw { 1. } else { -1. };
+                    let (normal_start, normal_end) = (
+                        (start - center) / radius * sign,
+                        (end - center) / radius * sign,
+                    );
+                    let denom = normal_start.perp_dot(normal_end);
+                    if denom.abs() < TOLERANCE {
+                        return Err(anyhow!(
+                            "Could not offset arc keeping its radius! Only arcs turning less than half a circle are supported."
+                        ));
+                    }
+                    let translation =
+                        DVec2::new(normal_end.y - normal_start.y, normal_start.x - normal_end.x)
+                            * right_offset
+                            / denom;
+                    segments.push((
+                        SketchSegment::Arc {
+                            start: start + translation,
+                            transit: transit + translation,
+                            end: end + translation,
+                        },
+                        end - start,
+                    ));
+                    continue;
+                }
+                let new_radius = radius + if is_ccw { right_offset } else { -right_offset };
+                if new_radius < TOLERANCE {
+                    continue;
+                }
+                let scaled = |point: DVec2| center + (point - center) * new_radius / radius;
+                segments.push((
+                    SketchSegment::Arc {
+                        start: scaled(start),
+                        transit: scaled(transit),
+                        end: scaled(end),
+                    },
+                    end - start,
+                ));
+            }
+        }
+    }
+    let segments_len = segments.len();
+    if segments_len < 2 {
+        return Err(anyhow!("Sketch offset collapsed the loop!"));
+    }
+    // Join the offset segments...
+    let joins = (0..segments_len)
+        .map(|idx| {
+            let (segment, _) = &segments[idx];
+            let (next_segment, _) = &segments[(idx + 1) % segments_len];
+            if segment.end().distance(next_segment.start()) < TOLERANCE {
+                return Ok(segment.end());
+            }
+            match (segment, next_segment) {
+                (
+                    SketchSegment::Line { start, end },
+                    SketchSegment::Line {
+                        start: next_start,
+                        end: next_end,
+                    },
+                ) => line_intersection(*start, *end, *next_start, *next_end)
+                    .ok_or_else(|| anyhow!("Could not join parallel offset lines!")),
+                // Arcs moved with their tangent lines end on the offset lines...
+                (
+                    SketchSegment::Arc { end, .. },
+                    SketchSegment::Line {
+                        start,
+                        end: line_end,
+                    },
+                ) if line_distance(*end, *start, *line_end) < TOLERANCE => Ok(*end),
+                (
+                    SketchSegment::Line { start, end },
+                    SketchSegment::Arc {
+                        start: arc_start, ..
+                    },
+                ) if line_distance(*arc_start, *start, *end) < TOLERANCE => Ok(*arc_start),
+                _ => Err(anyhow!(
+                    "Could not join offset arc at a sharp corner! Only tangent arcs are supported."
+                )),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for (idx, join) in joins.into_iter().enumerate() {
+        segments[idx].0.set_end(join);
+        segments[(idx + 1) % segments_len].0.set_start(join);
+    }
+    // Lines flipping direction means the offset is too large...
+    for (segment, original_dir) in segments.iter() {
+        if let SketchSegment::Line { start, end } = segment {
+            if (*end - *start).dot(*original_dir) <= 0. {
+                return Err(anyhow!("Sketch offset distance {} is too large!", distance));
+            }
+        }
+    }
+
+    Ok(SketchLoop {
+        start: segments[0].0.start(),
+        edges: segments
+            .into_iter()
+            .map(|(segment, _)| SketchEdge { segment, tag: None })
+            .collect(),
+        closed: true,
+    })
+}
+
+/// Center of the circle through the 3 points.
+pub(super) fn circle_center(a: DVec2, b: DVec2, c: DVec2) -> Result<DVec2> {
+    let denom = 2. * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+    if denom.abs() < TOLERANCE * TOLERANCE {
+        return Err(anyhow!("Arc points are collinear!"));
+    }
+    let (a2, b2, c2) = (a.length_squared(), b.length_squared(), c.length_squared());
+    Ok(DVec2::new(
+        a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y),
+        a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x),
+    ) / denom)
+}
+
+/// Distance of the `point` from the (infinite) line through the points.
+fn line_distance(point: DVec2, p0: DVec2, p1: DVec2) -> f64 {
+    (point - p0).perp_dot((p1 - p0).normalize()).abs()
+}
+
+/// Intersection of the (infinite) lines through the points.
+fn line_intersection(p0: DVec2, p1: DVec2, q0: DVec2, q1: DVec2) -> Option<DVec2> {
+    let (dir_p, dir_q) = (p1 - p0, q1 - q0);
+    let denom = dir_p.perp_dot(dir_q);
+    if denom.abs() < TOLERANCE * TOLERANCE {
+        return None;
+    }
+    Some(p0 + dir_p * (q0 - p0).perp_dot(dir_q) / denom)
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::pmetra_core::sketch::SketchPlane;
+
+    #[allow(dead_code)]
+    fn rounded_rect() -> Sketch {
+        Sketch::new("Profile", SketchPlane::xy())
+            .add_rect(DVec2::ZERO, DVec2::new(2., 1.))
+            .unwrap()
+            .fillet_all(0.2)
+            .unwrap()
+    }
+
+    /// Radii of the arcs of the loop.
+    #[allow(dead_code)]
+    fn arc_radii(sketch_loop: &SketchLoop) -> Vec<f64> {
+        sketch_loop
+            .edges
+            .iter()
+            .filter_map(|edge| match edge.segment {
+                SketchSegment::Arc {
+                    start,
+                    transit,
+                    end,
+                } => Some(start.distance(circle_center(start, transit, end).unwrap())),
+                SketchSegment::Line { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_offset_rounded_rect() {
+        let sketch = rounded_rect();
+        assert_eq!(sketch.loops[0].edges.len(), 8);
+
+        // Growing keeps the arcs concentric...
+        let grown = sketch.offset(0.1).unwrap();
+        assert_eq!(grown.loops[0].edges.len(), 8);
+        assert!(arc_radii(&grown.loops[0])
+            .iter()
+            .all(|radius| (radius - 0.3).abs() < TOLERANCE));
+        assert!((grown.loops[0].signed_area().abs() - sketch.loops[0].signed_area().abs()) > 0.);
+
+        // Shrinking past the radius leaves sharp corners...
+        let shrunk = sketch.offset(-0.3).unwrap();
+        assert_eq!(shrunk.loops[0].edges.len(), 4);
+        assert!(arc_radii(&shrunk.loops[0]).is_empty());
+
+        // Shrinking past the half height collapses the profile...
+        assert!(sketch.offset(-0.6).is_err());
+
+        for distance in [f64::NAN, f64::INFINITY] {
+            let error = sketch.offset(distance).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("Invalid sketch offset distance: {}", distance)
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_thicken_rounded_rect() {
+        let thickened = rounded_rect().thicken(0.1).unwrap();
+        let [outer, inner] = thickened.loops.as_slice() else {
+            panic!("Thicken should add an inner loop!");
+        };
+        assert_eq!(outer.edges.len(), 8);
+        assert_eq!(inner.edges.len(), 8);
+        assert!(arc_radii(inner)
+            .iter()
+            .all(|radius| (radius - 0.1).abs() < TOLERANCE));
+        // Inner loop spans the profile shrunk by the thickness...
+        let points = inner
+            .edges
+            .iter()
+            .flat_map(|edge| edge.segment.points())
+            .collect::<Vec<_>>();
+        let (min, max) = points.iter().fold(
+            (DVec2::splat(f64::MAX), DVec2::splat(f64::MIN)),
+            |(min, max), point| (min.min(*point), max.max(*point)),
+        );
+        assert!(min.distance(DVec2::new(0.1, 0.1)) < TOLERANCE);
+        assert!(max.distance(DVec2::new(1.9, 0.9)) < TOLERANCE);
+
+        let profile = thickened.build().unwrap();
+        assert_eq!(profile.wires.len(), 2);
+        assert!(profile.extrude(1.).is_ok());
+
+        for thickness in [0., -0.1, f64::NAN, f64::INFINITY] {
+            let error = rounded_rect().thicken(thickness).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("Invalid sketch thickness: {}", thickness)
+            );
+        }
+        assert!(rounded_rect()
+            .add_circle(DVec2::new(1., 0.5), 0.2)
+            .unwrap()
+            .thicken(0.1)
+            .is_err());
+    }
+
+    #[test]
+    pub fn test_thicken_rounded_rect_keep_radius() {
+        let thickened = rounded_rect()
+            .thicken_with(0.1, SketchOffsetArcs::KeepRadius)
+            .unwrap();
+        let [_, inner] = thickened.loops.as_slice() else {
+            panic!("Thicken should add an inner loop!");
+        };
+        assert_eq!(inner.edges.len(), 8);
+        // Inner arcs keep the radius, moved in diagonally with their corners...
+        assert!(arc_radii(inner)
+            .iter()
+            .all(|radius| (radius - 0.2).abs() < TOLERANCE));
+        let SketchSegment::Arc { start, end, .. } = inner.edges[0].segment else {
+            panic!("First edge should be the corner arc!");
+        };
+        assert!(start.distance(DVec2::new(0.1, 0.3)) < TOLERANCE);
+        assert!(end.distance(DVec2::new(0.3, 0.1)) < TOLERANCE);
+        // Lines join the moved arcs...
+        for (idx, edge) in inner.edges.iter().enumerate() {
+            let next = &inner.edges[(idx + 1) % inner.edges.len()];
+            assert!(edge.segment.end().distance(next.segment.start()) < TOLERANCE);
+        }
+        assert!(thickened.build().unwrap().extrude(1.).is_ok());
+
+        // Straight edges need room between the corners...
+        assert!(rounded_rect()
+            .thicken_with(0.35, SketchOffsetArcs::KeepRadius)
+            .is_err());
+        // Concentric offset is the default...
+        assert_eq!(
+            rounded_rect().thicken(0.1).unwrap().loops[1].edges[0].segment,
+            rounded_rect()
+                .thicken_with(0.1, SketchOffsetArcs::Concentric)
+                .unwrap()
+                .loops[1]
+                .edges[0]
+                .segment
+        );
+    }
+}
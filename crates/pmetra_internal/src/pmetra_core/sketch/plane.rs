@@ -0,0 +1,96 @@
+use anyhow::{anyhow, Result};
+use bevy::math::{DVec2, DVec3};
+use truck_meshalgo::prelude::TOLERANCE;
+use truck_modeling::Point3;
+
+use crate::pmetra_core::dimensions::FromBevyDVec3;
+
+/// Placement plane of a [`Sketch`](super::Sketch).
+///
+/// Maps the 2D sketch coordinates to 3D: `origin + x * x_axis + y * y_axis`.
+/// Counter-clockwise (in sketch coordinates) profiles face along [`Self::normal`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SketchPlane {
+    pub origin: DVec3,
+    pub x_axis: DVec3,
+    pub y_axis: DVec3,
+}
+
+impl Default for SketchPlane {
+    fn default() -> Self {
+        Self::xy()
+    }
+}
+
+impl SketchPlane {
+    /// Create new [`SketchPlane`]. Axes are normalized and must be perpendicular.
+    pub fn new(origin: DVec3, x_axis: DVec3, y_axis: DVec3) -> Result<Self> {
+        let x_axis = x_axis
+            .try_normalize()
+            .ok_or_else(|| anyhow!("Invalid sketch plane x axis: {:?}", x_axis))?;
+        let y_axis = y_axis
+            .try_normalize()
+            .ok_or_else(|| anyhow!("Invalid sketch plane y axis: {:?}", y_axis))?;
+        if x_axis.dot(y_axis).abs() > TOLERANCE {
+            return Err(anyhow!("Sketch plane axes are not perpendicular!"));
+        }
+        Ok(Self {
+            origin,
+            x_axis,
+            y_axis,
+        })
+    }
+
+    /// XY plane, facing +Z.
+    pub fn xy() -> Self {
+        Self {
+            origin: DVec3::ZERO,
+            x_axis: DVec3::X,
+            y_axis: DVec3::Y,
+        }
+    }
+
+    /// YZ plane, facing +X.
+    pub fn yz() -> Self {
+        Self {
+            origin: DVec3::ZERO,
+            x_axis: DVec3::Y,
+            y_axis: DVec3::Z,
+        }
+    }
+
+    /// ZX plane, facing +Y.
+    pub fn zx() -> Self {
+        Self {
+            origin: DVec3::ZERO,
+            x_axis: DVec3::Z,
+            y_axis: DVec3::X,
+        }
+    }
+
+    /// Same plane moved to `origin`.
+    pub fn with_origin(self, origin: DVec3) -> Self {
+        Self { origin, ..self }
+    }
+
+    /// Normal of the plane (`x_axis` cross `y_axis`).
+    pub fn normal(&self) -> DVec3 {
+        self.x_axis.cross(self.y_axis)
+    }
+
+    /// Convert sketch point to world point.
+    pub fn to_world(&self, point: DVec2) -> DVec3 {
+        self.origin + self.x_axis * point.x + self.y_axis * point.y
+    }
+
+    /// Project world point onto the plane, as sketch point.
+    pub fn to_sketch(&self, point: DVec3) -> DVec2 {
+        let offset = point - self.origin;
+        DVec2::new(offset.dot(self.x_axis), offset.dot(self.y_axis))
+    }
+
+    /// Convert sketch point to truck [`Point3`].
+    pub fn point3(&self, point: DVec2) -> Point3 {
+        Point3::from_bevy_dvec3(self.to_world(point))
+    }
+}
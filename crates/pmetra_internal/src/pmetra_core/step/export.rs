@@ -0,0 +1,311 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use bevy::prelude::*;
+use truck_modeling::{builder, Matrix4};
+use truck_stepio::out::{CompleteStepDisplay, StepHeaderDescriptor, StepModels};
+
+use crate::pmetra_core::builders::{
+    CadMeshName, CadMeshesBuildersByCadShell, CadShellName, CadShellsByName,
+};
+
+/// `FILE_SCHEMA` of the exported STEP files, ie: AP214 (automotive design).
+const STEP_FILE_SCHEMA: &str = "AUTOMOTIVE_DESIGN { 1 0 10303 214 1 1 1 1 }";
+
+impl CadShellsByName {
+    /// Export the shells as a STEP (ISO 10303-21) file content, via [`truck_stepio`].
+    ///
+    /// Each shell is written as its own product, named by its [`CadShellName`] (in name order).
+    /// The `transform` (ie: of the root) is applied to the shells.
+    ///
+    /// Transforms of the meshes are not applied, use [`Self::to_step_instances`] (or
+    /// [`CadMeshesBuildersByCadShell::to_step`]) to export the shells as placed by the meshes.
+    pub fn to_step(&self, transform: Transform) -> Result<String> {
+        let mut shells = self.keys().collect::<Vec<_>>();
+        shells.sort_by(|name_a, name_b| name_a.0.cmp(&name_b.0));
+        let instances = shells
+            .into_iter()
+            .map(|shell_name| (shell_name.0.as_str(), shell_name, Transform::IDENTITY));
+        self.to_step_instances(instances, transform)
+    }
+
+    /// Export instances of the shells as a STEP (ISO 10303-21) file content, via [`truck_stepio`].
+    ///
+    /// Each instance is `(name, shell_name, transform)` (eg: of a [`CadMeshBuilder`](crate::pmetra_core::builders::CadMeshBuilder)),
+    /// written (in order) as its own product with the shell placed by `transform` and then the root `transform`.
+    ///
+    /// The file schema is AP214 (`AUTOMOTIVE_DESIGN`).
+    pub fn to_step_instances<'a>(
+        &self,
+        instances: impl IntoIterator<Item = (&'a str, &'a CadShellName, Transform)>,
+        transform: Transform,
+    ) -> Result<String> {
+        let mut names = vec![];
+        let mut compressed_shells = vec![];
+        for (name, shell_name, instance_transform) in instances {
+            let cad_shell = self
+                .get(shell_name)
+                .ok_or_else(|| anyhow!("Could not find shell with name: {:?}", shell_name))?;
+            let matrix = transform_to_matrix4(transform.mul_transform(instance_transform));
+            compressed_shells.push(builder::transformed(&cad_shell.shell, matrix).compress());
+            names.push(name);
+        }
+        if compressed_shells.is_empty() {
+            return Err(anyhow!("No shells to export to STEP!"));
+        }
+        let step_models = compressed_shells.iter().collect::<StepModels<_, _, _>>();
+        let step = CompleteStepDisplay::new(
+            step_models,
+            StepHeaderDescriptor {
+                origination_system: "bevy_pmetra".to_owned(),
+                ..Default::default()
+            },
+        )
+        .to_string();
+
+        name_step_products(&set_step_file_schema(&step)?, &names)
+    }
+
+    /// Export the shells to a STEP file at `path`. Refer [`CadShellsByName::to_step`].
+    pub fn save_step(&self, path: impl AsRef<Path>, transform: Transform) -> Result<()> {
+        write_step(path.as_ref(), self.to_step(transform)?)
+    }
+
+    /// Export instances of the shells to a STEP file at `path`. Refer [`CadShellsByName::to_step_instances`].
+    pub fn save_step_instances<'a>(
+        &self,
+        path: impl AsRef<Path>,
+        instances: impl IntoIterator<Item = (&'a str, &'a CadShellName, Transform)>,
+        transform: Transform,
+    ) -> Result<()> {
+        write_step(path.as_ref(), self.to_step_instances(instances, transform)?)
+    }
+}
+
+impl<P: Default + Clone> CadMeshesBuildersByCadShell<P> {
+    /// Export the shells as placed by the [`CadMeshBuilder`](crate::pmetra_core::builders::CadMeshBuilder)s
+    /// as a STEP (ISO 10303-21) file content.
+    ///
+    /// Each mesh builder (incl. instances of patterns) is written as its own product, named by its [`CadMeshName`]
+    /// (ordered by the shell name and then the mesh name). Refer [`CadShellsByName::to_step_instances`].
+    pub fn to_step(&self, transform: Transform) -> Result<String> {
+        let mut instances =
+            self.meshes_builders
+                .iter()
+                .flat_map(|(shell_name, meshes_builder)| {
+                    meshes_builder.mesh_builders.iter().map(
+                        move |(CadMeshName(name), mesh_builder)| {
+                            (name.as_str(), shell_name, mesh_builder.transform)
+                        },
+                    )
+                })
+                .collect::<Vec<_>>();
+        instances.sort_by(|(name_a, shell_a, _), (name_b, shell_b, _)| {
+            (&shell_a.0, name_a).cmp(&(&shell_b.0, name_b))
+        });
+        self.shells_by_name.to_step_instances(instances, transform)
+    }
+
+    /// Export the shells as placed by the mesh builders to a STEP file at `path`. Refer [`Self::to_step`].
+    pub fn save_step(&self, path: impl AsRef<Path>, transform: Transform) -> Result<()> {
+        write_step(path.as_ref(), self.to_step(transform)?)
+    }
+}
+
+/// Write the STEP file content to `path`.
+fn write_step(path: &Path, step: String) -> Result<()> {
+    std::fs::write(path, step).with_context(|| format!("Could not write STEP file: {:?}", path))
+}
+
+/// Get the truck [`Matrix4`] of the [`Transform`].
+fn transform_to_matrix4(transform: Transform) -> Matrix4 {
+    Matrix4::from(
+        Mat4::from(transform.compute_affine())
+            .as_dmat4()
+            .to_cols_array_2d(),
+    )
+}
+
+/// Set the `FILE_SCHEMA` (in the header) of the STEP file content to [`STEP_FILE_SCHEMA`].
+fn set_step_file_schema(step: &str) -> Result<String> {
+    const FILE_SCHEMA: &str = "FILE_SCHEMA(";
+    let start = step
+        .find(FILE_SCHEMA)
+        .ok_or_else(|| anyhow!("Could not find FILE_SCHEMA in STEP file!"))?;
+    let end = step[start..]
+        .find(';')
+        .map(|idx| start + idx)
+        .ok_or_else(|| anyhow!("Could not find end of FILE_SCHEMA in STEP file!"))?;
+
+    Ok(format!(
+        "{}FILE_SCHEMA(('{}')){}",
+        &step[..start],
+        STEP_FILE_SCHEMA,
+        &step[end..]
+    ))
+}
+
+/// Set the names of the `PRODUCT` entities (in order) of the STEP file content.
+fn name_step_products(step: &str, names: &[&str]) -> Result<String> {
+    const PRODUCT: &str = "PRODUCT(";
+    let mut named = String::with_capacity(step.len());
+    let mut rest = step;
+    let mut names_iter = names.iter();
+    while let Some(idx) = rest.find(PRODUCT) {
+        // Skip entities like `PRODUCT_DEFINITION(`, only `= PRODUCT(` matches...
+        let is_product = rest[..idx].trim_end().ends_with('=');
+        let (head, tail) = rest.split_at(idx + PRODUCT.len());
+        named.push_str(head);
+        rest = tail;
+        if !is_product {
+            continue;
+        }
+        let Some(name) = names_iter.next() else {
+            return Err(anyhow!("STEP file has more products than shells!"));
+        };
+        // Replace the `id` and `name` (first 2 string attributes) of the product...
+        let name = name.replace('\'', "''");
+        for _ in 0..2 {
+            let (string_start, string_end) = step_string_bounds(rest)
+                .ok_or_else(|| anyhow!("Could not find product attributes in STEP file!"))?;
+            named.push_str(&rest[..string_start]);
+            named.push('\'');
+            named.push_str(&name);
+            named.push('\'');
+            rest = &rest[string_end..];
+        }
+    }
+    named.push_str(rest);
+    if names_iter.next().is_some() {
+        return Err(anyhow!("STEP file has fewer products than shells!"));
+    }
+
+    Ok(named)
+}
+
+/// Get the byte bounds (incl quotes) of the first STEP string (`'...'`, with `''` as escaped quote).
+fn step_string_bounds(text: &str) -> Option<(usize, usize)> {
+    let start = text.find('\'')?;
+    let bytes = text.as_bytes();
+    let mut idx = start + 1;
+    while idx < bytes.len() {
+        if bytes[idx] == b'\'' {
+            if bytes.get(idx + 1) == Some(&b'\'') {
+                idx += 2;
+                continue;
+            }
+            return Some((start, idx + 1));
+        }
+        idx += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::{
+        pmetra_core::builders::{CadMeshBuilder, CadShell},
+        test_utils::cube_shell,
+    };
+    #[allow(unused_imports)]
+    use truck_modeling::{InnerSpace, Point3};
+
+    #[test]
+    pub fn test_name_step_products() {
+        let step = "#7 = PRODUCT_DEFINITION('design', '', #8, #9);\n\
+            #8 = PRODUCT('', '', '', (#10));\n\
+            #18 = PRODUCT('', '', '', (#20));\n";
+        let named = name_step_products(step, &["Cube", "Rock's"]).unwrap();
+        assert_eq!(
+            named,
+            "#7 = PRODUCT_DEFINITION('design', '', #8, #9);\n\
+            #8 = PRODUCT('Cube', 'Cube', '', (#10));\n\
+            #18 = PRODUCT('Rock''s', 'Rock''s', '', (#20));\n"
+        );
+        assert!(name_step_products(step, &["Cube"]).is_err());
+    }
+
+    #[test]
+    pub fn test_name_step_products_of_truck_output() {
+        let shells = [
+            cube_shell(Point3::new(0., 0., 0.)).shell.compress(),
+            cube_shell(Point3::new(0., 0., 0.)).shell.compress(),
+        ];
+        let step = CompleteStepDisplay::new(
+            shells.iter().collect::<StepModels<_, _, _>>(),
+            StepHeaderDescriptor::default(),
+        )
+        .to_string();
+        // Truck writes a `FILE_SCHEMA` and an (unnamed) `= PRODUCT(` entity per shell...
+        assert_eq!(step.matches("FILE_SCHEMA(").count(), 1, "{}", step);
+
+        let step = set_step_file_schema(&step).unwrap();
+        assert_eq!(step.matches("FILE_SCHEMA(").count(), 1, "{}", step);
+        assert!(
+            step.contains("FILE_SCHEMA(('AUTOMOTIVE_DESIGN { 1 0 10303 214 1 1 1 1 }'));"),
+            "{}",
+            step
+        );
+        let named = name_step_products(&step, &["CubeA", "CubeB"]).unwrap();
+        assert!(named.contains("PRODUCT('CubeA', 'CubeA'"), "{}", named);
+        assert!(named.contains("PRODUCT('CubeB', 'CubeB'"), "{}", named);
+        assert!(name_step_products(&step, &["CubeA"]).is_err());
+        assert!(name_step_products(&step, &["CubeA", "CubeB", "CubeC"]).is_err());
+    }
+
+    #[test]
+    pub fn test_meshes_builders_to_step() {
+        let shell_name = CadShellName("Cube".into());
+        let mut shells_by_name = CadShellsByName::default();
+        shells_by_name.insert(shell_name.clone(), cube_shell(Point3::new(0., 0., 0.)));
+        let mut meshes_builders = CadMeshesBuildersByCadShell::new((), shells_by_name).unwrap();
+        for (mesh_name, x) in [("CubeA", 0.), ("CubeB", 3.)] {
+            let mesh_builder = CadMeshBuilder::new((), shell_name.clone())
+                .unwrap()
+                .set_transform(Transform::from_xyz(x, 0., 0.))
+                .unwrap();
+            meshes_builders
+                .add_mesh_builder(shell_name.clone(), mesh_name.into(), mesh_builder)
+                .unwrap();
+        }
+
+        let step = meshes_builders
+            .to_step(Transform::from_xyz(0., 1., 0.))
+            .unwrap();
+        assert!(step.contains("PRODUCT('CubeA', 'CubeA'"));
+        assert!(step.contains("PRODUCT('CubeB', 'CubeB'"));
+        assert!(step.contains(&format!("FILE_SCHEMA(('{}'));", STEP_FILE_SCHEMA)));
+        let shells = CadShell::from_step(&step, 1e-3).unwrap();
+        assert_eq!(shells.len(), 2);
+        for (cad_shell, x) in shells.iter().zip([0., 3.]) {
+            let min = cad_shell
+                .shell
+                .vertex_iter()
+                .map(|vertex| vertex.point())
+                .reduce(|a, b| Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)))
+                .unwrap();
+            assert!(
+                (min - Point3::new(x, 1., 0.)).magnitude() < 1e-6,
+                "{:?}",
+                min
+            );
+        }
+
+        // Shells of mesh builders must exist...
+        let mut missing = CadShellsByName::default();
+        missing.insert(shell_name.clone(), cube_shell(Point3::new(0., 0., 0.)));
+        let error = missing
+            .to_step_instances(
+                [("Rock", &CadShellName("Rock".into()), Transform::IDENTITY)],
+                Transform::IDENTITY,
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not find shell with name: CadShellName(\"Rock\")"
+        );
+    }
+}
@@ -0,0 +1,445 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use truck_meshalgo::tessellation::{MeshableSurface, PolylineableCurve};
+use truck_modeling::{
+    BSplineCurve, BSplineSurface, BoundedCurve, Curve, InnerSpace, KnotVec, Line, MetricSpace,
+    ParametricCurve, ParametricSurface, Plane, Point3, SPHint2D, SearchParameter, Shell, Surface,
+    Vector3,
+};
+use truck_stepio::r#in::Table;
+use truck_topology::compress::{CompressedEdge, CompressedFace, CompressedShell};
+
+use crate::{
+    constants::SURFACE_SEARCH_TRIALS,
+    pmetra_core::builders::{CadShell, CadTaggedElements},
+};
+
+/// Number of samples per curve, used for detecting lines and the parameter range of faces.
+const CURVE_SAMPLES: usize = 16;
+/// Number of samples per direction, used for detecting planes.
+const SURFACE_SAMPLES: usize = 8;
+/// Max number of (cubic) segments per direction to approximate curves/surfaces.
+const MAX_SEGMENTS: usize = 64;
+
+impl CadShell {
+    /// Import the shells of the STEP file content (via [`truck_stepio`]).
+    ///
+    /// Straight curves and planar surfaces are imported as lines and planes. Other curves and
+    /// surfaces are approximated by (piecewise cubic) B-splines within `tolerance`.
+    ///
+    /// Imported shells have no tagged elements. Since importing is slow, return them from a
+    /// shell builder with a constant key (refer [`CadShellsBuilders::set_shell_key`](crate::pmetra_core::builders::CadShellsBuilders::set_shell_key))
+    /// so they are only imported once.
+    pub fn from_step(step: &str, tolerance: f64) -> Result<Vec<Self>> {
+        if !(tolerance.is_finite() && tolerance > 0.) {
+            return Err(anyhow!("Invalid STEP import tolerance: {}", tolerance));
+        }
+        let table = Table::from_step(step)
+            .ok_or_else(|| anyhow!("Could not parse STEP file! Only ISO 10303-21 is supported."))?;
+        if table.shell.is_empty() {
+            return Err(anyhow!("STEP file has no shells!"));
+        }
+        let mut step_shells = table.shell.iter().collect::<Vec<_>>();
+        step_shells.sort_by_key(|(id, _)| **id);
+        step_shells
+            .into_iter()
+            .map(|(id, step_shell)| {
+                let compressed = table
+                    .to_compressed_shell(step_shell)
+                    .map_err(|e| anyhow!("Could not read STEP shell #{}: {:?}", id, e))?;
+                let shell = import_shell(compressed, tolerance)
+                    .with_context(|| format!("Could not import STEP shell #{}", id))?;
+                Ok(Self {
+                    shell,
+                    tagged_elements: CadTaggedElements::default(),
+                })
+            })
+            .collect()
+    }
+
+    /// Import the shells of the STEP file at `path`. Refer [`CadShell::from_step`].
+    pub fn load_step(path: impl AsRef<Path>, tolerance: f64) -> Result<Vec<Self>> {
+        let path = path.as_ref();
+        let step = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read STEP file: {:?}", path))?;
+        Self::from_step(&step, tolerance)
+            .with_context(|| format!("Could not import STEP file: {:?}", path))
+    }
+}
+
+/// Convert the curves/surfaces of the (STEP) shell to [`Curve`]/[`Surface`].
+fn import_shell<C: PolylineableCurve, S: MeshableSurface>(
+    compressed: CompressedShell<Point3, C, S>,
+    tolerance: f64,
+) -> Result<Shell> {
+    let CompressedShell {
+        vertices,
+        edges,
+        faces,
+    } = compressed;
+    let mut edges_points = vec![];
+    let edges = edges
+        .into_iter()
+        .enumerate()
+        .map(|(idx, edge)| {
+            let CompressedEdge {
+                vertices: (front, back),
+                curve,
+            } = edge;
+            let (front_point, back_point) = (vertices[front], vertices[back]);
+            let (t0, t1) = curve_range(&curve, front_point);
+            edges_points.push(
+                (0..=CURVE_SAMPLES)
+                    .map(|i| curve.subs(t0 + (t1 - t0) * i as f64 / CURVE_SAMPLES as f64))
+                    .collect::<Vec<_>>(),
+            );
+            let curve = import_curve(&curve, (t0, t1), (front_point, back_point), tolerance)
+                .with_context(|| format!("Could not import curve of edge {}", idx))?;
+            Ok(CompressedEdge {
+                vertices: (front, back),
+                curve,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let faces = faces
+        .into_iter()
+        .enumerate()
+        .map(|(idx, face)| {
+            let boundary_points = face
+                .boundaries
+                .iter()
+                .flatten()
+                .flat_map(|edge_idx| edges_points[edge_idx.index].iter().copied())
+                .collect::<Vec<_>>();
+            let surface = import_surface(&face.surface, &boundary_points, tolerance)
+                .with_context(|| format!("Could not import surface of face {}", idx))?;
+            Ok(CompressedFace {
+                boundaries: face.boundaries,
+                orientation: face.orientation,
+                surface,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Shell::extract(CompressedShell {
+        vertices,
+        edges,
+        faces,
+    })
+    .map_err(|e| anyhow!("Could not create shell: {:?}", e))
+}
+
+/// Parameter range of the (bounded) curve, from its end nearest to the `front` point.
+fn curve_range<C: PolylineableCurve>(curve: &C, front: Point3) -> (f64, f64) {
+    let (t0, t1) = curve.range_tuple();
+    if curve.subs(t0).distance2(front) <= curve.subs(t1).distance2(front) {
+        (t0, t1)
+    } else {
+        (t1, t0)
+    }
+}
+
+fn import_curve<C: PolylineableCurve>(
+    curve: &C,
+    (t0, t1): (f64, f64),
+    (front, back): (Point3, Point3),
+    tolerance: f64,
+) -> Result<Curve> {
+    // Straight curves as lines...
+    let direction = back - front;
+    if direction.magnitude() > tolerance {
+        let direction = direction.normalize();
+        let is_straight = (0..=CURVE_SAMPLES).all(|i| {
+            let offset = curve.subs(t0 + (t1 - t0) * i as f64 / CURVE_SAMPLES as f64) - front;
+            (offset - direction * offset.dot(direction)).magnitude() < tolerance
+        });
+        if is_straight {
+            return Ok(Curve::Line(Line(front, back)));
+        }
+    }
+    // Rest as cubic B-splines from the (Hermite) points and derivatives, refined till within tolerance...
+    let mut segments = 1;
+    while segments <= MAX_SEGMENTS {
+        let dt = (t1 - t0) / segments as f64;
+        let mut control_points = (0..=segments * 3)
+            .map(|idx| {
+                let (i, sign) = hermite_index(idx);
+                let t = t0 + dt * i as f64;
+                curve.subs(t) + curve.der(t) * (sign * dt / 3.)
+            })
+            .collect::<Vec<_>>();
+        // Snap the ends to the vertices...
+        control_points[0] = front;
+        control_points[segments * 3] = back;
+        let bspline = BSplineCurve::new(bezier_chain_knots(segments), control_points);
+        let is_within_tolerance = (0..segments).all(|i| {
+            [0.25, 0.5, 0.75].iter().all(|frac| {
+                let s = i as f64 + frac;
+                bspline.subs(s).distance(curve.subs(t0 + dt * s)) < tolerance
+            })
+        });
+        if is_within_tolerance {
+            return Ok(Curve::BSplineCurve(bspline));
+        }
+        segments *= 2;
+    }
+    Err(anyhow!(
+        "Could not approximate curve within tolerance: {}",
+        tolerance
+    ))
+}
+
+fn import_surface<S: MeshableSurface>(
+    surface: &S,
+    boundary_points: &[Point3],
+    tolerance: f64,
+) -> Result<Surface> {
+    let (u_range, v_range) = surface_range(surface, boundary_points)?;
+    let ((u0, u1), (v0, v1)) = (u_range, v_range);
+    let grid_point = |i: usize, j: usize| {
+        surface.subs(
+            u0 + (u1 - u0) * i as f64 / SURFACE_SAMPLES as f64,
+            v0 + (v1 - v0) * j as f64 / SURFACE_SAMPLES as f64,
+        )
+    };
+    // Planar surfaces as planes...
+    let (origin, u_point, v_point) = (
+        grid_point(0, 0),
+        grid_point(SURFACE_SAMPLES, 0),
+        grid_point(0, SURFACE_SAMPLES),
+    );
+    let normal = (u_point - origin).cross(v_point - origin);
+    if normal.magnitude() > tolerance * tolerance {
+        let normal = normal.normalize();
+        let is_planar = (0..=SURFACE_SAMPLES).all(|i| {
+            (0..=SURFACE_SAMPLES).all(|j| (grid_point(i, j) - origin).dot(normal).abs() < tolerance)
+        });
+        if is_planar {
+            return Ok(Surface::Plane(Plane::new(origin, u_point, v_point)));
+        }
+    }
+    // Rest as bicubic B-splines from the (Hermite) points, derivatives and twists,
+    // refined till within tolerance...
+    let mut segments = 1;
+    while segments <= MAX_SEGMENTS {
+        let (du, dv) = ((u1 - u0) / segments as f64, (v1 - v0) / segments as f64);
+        let control_points = (0..=segments * 3)
+            .map(|idx_u| {
+                let (i, sign_u) = hermite_index(idx_u);
+                let u = u0 + du * i as f64;
+                (0..=segments * 3)
+                    .map(|idx_v| {
+                        let (j, sign_v) = hermite_index(idx_v);
+                        let v = v0 + dv * j as f64;
+                        surface.subs(u, v)
+                            + surface.uder(u, v) * (sign_u * du / 3.)
+                            + surface.vder(u, v) * (sign_v * dv / 3.)
+                            + surface.uvder(u, v) * (sign_u * sign_v * du * dv / 9.)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let bspline = BSplineSurface::new(
+            (bezier_chain_knots(segments), bezier_chain_knots(segments)),
+            control_points,
+        );
+        let is_within_tolerance = (0..segments * 2).all(|i| {
+            (0..segments * 2).all(|j| {
+                let (s, r) = (i as f64 / 2. + 0.25, j as f64 / 2. + 0.25);
+                bspline
+                    .subs(s, r)
+                    .distance(surface.subs(u0 + du * s, v0 + dv * r))
+                    < tolerance
+            })
+        });
+        if is_within_tolerance {
+            return Ok(Surface::BSplineSurface(bspline));
+        }
+        segments *= 2;
+    }
+    Err(anyhow!(
+        "Could not approximate surface within tolerance: {}",
+        tolerance
+    ))
+}
+
+/// Parameter range (u, v) of the face on the surface, from the points on its boundaries.
+///
+/// Parameters of periodic surfaces are unwrapped along the boundaries (to not jump at the seam).
+fn surface_range<S: MeshableSurface>(
+    surface: &S,
+    boundary_points: &[Point3],
+) -> Result<((f64, f64), (f64, f64))> {
+    let unwrap = |value: f64, previous: Option<f64>, period: Option<f64>| match (previous, period) {
+        (Some(previous), Some(period)) => value + ((previous - value) / period).round() * period,
+        _ => value,
+    };
+    let (mut u_range, mut v_range) = ((f64::MAX, f64::MIN), (f64::MAX, f64::MIN));
+    let mut previous: Option<(f64, f64)> = None;
+    for point in boundary_points {
+        let hint = previous.map_or(SPHint2D::None, SPHint2D::Parameter);
+        let (u, v) = surface
+            .search_parameter(*point, hint, SURFACE_SEARCH_TRIALS)
+            .or_else(|| surface.search_parameter(*point, SPHint2D::None, SURFACE_SEARCH_TRIALS))
+            .ok_or_else(|| anyhow!("Could not find boundary point {:?} on surface!", point))?;
+        let (u, v) = (
+            unwrap(u, previous.map(|(u, _)| u), surface.u_period()),
+            unwrap(v, previous.map(|(_, v)| v), surface.v_period()),
+        );
+        u_range = (u_range.0.min(u), u_range.1.max(u));
+        v_range = (v_range.0.min(v), v_range.1.max(v));
+        previous = Some((u, v));
+    }
+    if u_range.0 >= u_range.1 || v_range.0 >= v_range.1 {
+        return Err(anyhow!("Face has an empty parameter range on its surface!"));
+    }
+
+    Ok((u_range, v_range))
+}
+
+/// Knots of a chain of cubic Bezier segments, ie: `[0, 0, 0, 0, 1, 1, 1, .., n, n, n, n]`.
+fn bezier_chain_knots(segments: usize) -> KnotVec {
+    let mut knots = vec![0.; 4];
+    for idx in 1..segments {
+        knots.extend([idx as f64; 3]);
+    }
+    knots.extend([segments as f64; 4]);
+    KnotVec::from(knots)
+}
+
+/// Grid index and derivative sign of the control point of a cubic Bezier chain (from Hermite data).
+///
+/// Control points `3i`, `3i + 1`, `3i + 2` are at `i`, after `i` and before `i + 1` respectively.
+fn hermite_index(idx: usize) -> (usize, f64) {
+    match idx % 3 {
+        0 => (idx / 3, 0.),
+        1 => (idx / 3, 1.),
+        _ => (idx / 3 + 1, -1.),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::{
+        pmetra_core::builders::{CadShellName, CadShellsByName},
+        test_utils::{cube_shell, cylinder_shell},
+    };
+    #[allow(unused_imports)]
+    use bevy::prelude::Transform;
+
+    #[test]
+    pub fn test_step_round_trip() {
+        let mut shells_by_name = CadShellsByName::default();
+        shells_by_name.insert(
+            CadShellName("CubeA".into()),
+            cube_shell(Point3::new(0., 0., 0.)),
+        );
+        shells_by_name.insert(
+            CadShellName("CubeB".into()),
+            cube_shell(Point3::new(2., 0., 0.)),
+        );
+        let step = shells_by_name.to_step(Transform::default()).unwrap();
+        let shells = CadShell::from_step(&step, 1e-3).unwrap();
+        assert_eq!(shells.len(), 2);
+        for cad_shell in shells.iter() {
+            assert_eq!(cad_shell.shell.face_iter().count(), 6);
+            assert_eq!(cad_shell.shell.edge_iter().count(), 24);
+            assert!(cad_shell
+                .shell
+                .face_iter()
+                .all(|face| matches!(face.surface(), Surface::Plane(_))));
+            assert!(cad_shell.tagged_elements.is_empty());
+        }
+    }
+
+    #[test]
+    pub fn test_step_import_errors() {
+        let error = CadShell::from_step("not a step file", 1e-3).unwrap_err();
+        assert!(error.to_string().contains("STEP file"), "{}", error);
+
+        let mut shells_by_name = CadShellsByName::default();
+        shells_by_name.insert(
+            CadShellName("Cube".into()),
+            cube_shell(Point3::new(0., 0., 0.)),
+        );
+        let step = shells_by_name.to_step(Transform::default()).unwrap();
+        for tolerance in [0., -1e-3, f64::NAN, f64::INFINITY] {
+            let error = CadShell::from_step(&step, tolerance).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("Invalid STEP import tolerance: {}", tolerance)
+            );
+        }
+
+        let error = CadShell::load_step("missing.step", 1e-3).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not read STEP file: \"missing.step\""
+        );
+    }
+
+    #[test]
+    pub fn test_step_import_cylinder_as_bspline() {
+        let tolerance = 1e-3;
+        let mut shells_by_name = CadShellsByName::default();
+        shells_by_name.insert(CadShellName("Cylinder".into()), cylinder_shell());
+        let step = shells_by_name.to_step(Transform::default()).unwrap();
+        let shells = CadShell::from_step(&step, tolerance).unwrap();
+        assert_eq!(shells.len(), 1);
+        let surfaces = shells[0]
+            .shell
+            .face_iter()
+            .map(|face| face.surface())
+            .collect::<Vec<_>>();
+        let planes = surfaces
+            .iter()
+            .filter(|surface| matches!(surface, Surface::Plane(_)))
+            .count();
+        let bsplines = surfaces
+            .iter()
+            .filter_map(|surface| match surface {
+                Surface::BSplineSurface(bspline) => Some(bspline),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Caps as planes, curved faces (one per circle edge) as B-splines...
+        assert_eq!(planes, 2);
+        assert_eq!(bsplines.len(), 2);
+        for bspline in bsplines {
+            let (u_knots, v_knots) = bspline.knot_vecs();
+            for i in 0..=16 {
+                for j in 0..=16 {
+                    let point = bspline.subs(
+                        u_knots[0] + u_knots.range_length() * i as f64 / 16.,
+                        v_knots[0] + v_knots.range_length() * j as f64 / 16.,
+                    );
+                    let radius = (point.x * point.x + point.z * point.z).sqrt();
+                    assert!((radius - 1.).abs() < tolerance, "{:?}", point);
+                    assert!(point.y > -tolerance && point.y < 1. + tolerance);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_bezier_chain_from_hermite() {
+        let knots = bezier_chain_knots(2);
+        assert_eq!(knots.len(), 10);
+        assert_eq!(hermite_index(3), (1, 0.));
+        assert_eq!(hermite_index(5), (2, -1.));
+        // Quarter circle as a single cubic segment...
+        let (p0, p1) = (Point3::new(1., 0., 0.), Point3::new(0., 1., 0.));
+        let (d0, d1) = (Vector3::new(0., 1., 0.), Vector3::new(-1., 0., 0.));
+        let dt = std::f64::consts::FRAC_PI_2;
+        let bspline = BSplineCurve::new(
+            bezier_chain_knots(1),
+            vec![p0, p0 + d0 * (dt / 3.), p1 - d1 * (dt / 3.), p1],
+        );
+        let mid = bspline.subs(0.5);
+        assert!(((mid - Point3::new(0., 0., 0.)).magnitude() - 1.).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,4 @@
+/// STEP export of [`CadShellsByName`](super::builders::CadShellsByName).
+pub mod export;
+/// STEP import of [`CadShell`](super::builders::CadShell)s.
+pub mod import;
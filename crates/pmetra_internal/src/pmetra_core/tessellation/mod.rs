@@ -70,9 +70,11 @@ impl<C: PolylineableCurve, S: MeshableSurface> CustomMeshableShape<S> for Shell<
 #[derive(Debug, Clone)]
 pub struct CadMeshedShell<S> {
     pub meshed_shell: MeshedShell,
-    pub meshed_faces_by_brep_face:
-        HashMap<FaceID<S>, Face<Point3, PolylineCurve, Option<PolygonMesh>>>,
+    pub meshed_faces_by_brep_face: HashMap<FaceID<S>, MeshedFace>,
 }
 
 type PolylineCurve = truck_modeling::PolylineCurve<Point3>;
-type MeshedShell = Shell<Point3, PolylineCurve, Option<PolygonMesh>>;
+/// Tessellated (meshed) [`Face`].
+pub type MeshedFace = Face<Point3, PolylineCurve, Option<PolygonMesh>>;
+/// Tessellated (meshed) [`Shell`].
+pub type MeshedShell = Shell<Point3, PolylineCurve, Option<PolygonMesh>>;
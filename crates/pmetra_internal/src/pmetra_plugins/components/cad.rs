@@ -1,6 +1,11 @@
-use bevy::prelude::*;
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
 
-use crate::pmetra_core::builders::{CadMeshOutlines, CadSliderType};
+use crate::pmetra_core::builders::{
+    CadElementTag, CadMeshFaces, CadMeshOutlines, CadShellName, CadSliderType, ParamIssue,
+};
 
 /// Marker for CAD generated entities root.
 #[derive(Debug, Component, Reflect)]
@@ -22,10 +27,96 @@ pub enum CadGeneratedRootSelectionState {
 #[derive(Debug, Component, Clone, Reflect)]
 pub struct BelongsToCadGeneratedRoot(pub Entity);
 
+/// Params and creation index the [`CadShellsByName`] on this entity were built with.
+///
+/// Shells are built async, so these can lag behind the params on the [`CadGeneratedRoot`].
+#[derive(Debug, Component, Clone)]
+pub struct CadShellsBuiltWith<Params: Component + Clone> {
+    pub params: Params,
+    /// Index of the [`SpawnShellsBuilder`] the shells were built from.
+    pub created_at_idx: usize,
+    /// Keys of the shells. Refer [`CadShellsBuilders::set_shell_key`].
+    pub shell_keys: HashMap<CadShellName, u64>,
+    /// Shells changed (or added) in the last build. Only these need to be re-meshed.
+    pub changed_shells: HashSet<CadShellName>,
+}
+
+/// Undo/redo history of the [`Params`] on a [`CadGeneratedRoot`].
+///
+/// One snapshot is recorded per completed slider drag.
+/// Refer [`UndoCadModel`](crate::pmetra_plugins::events::cad::UndoCadModel) and
+/// [`RedoCadModel`](crate::pmetra_plugins::events::cad::RedoCadModel).
+#[derive(Debug, Component, Clone)]
+pub struct CadParamsHistory<Params: Component + Clone> {
+    /// Snapshots before each edit (latest last).
+    pub undo_stack: Vec<Params>,
+    /// Snapshots of the undone edits (latest last).
+    pub redo_stack: Vec<Params>,
+    /// Snapshot at the start of the current slider drag.
+    ///
+    /// Recorded (and taken) on the first edit of the drag.
+    pub drag_start: Option<Params>,
+}
+
+impl<Params: Component + Clone> Default for CadParamsHistory<Params> {
+    fn default() -> Self {
+        Self {
+            undo_stack: vec![],
+            redo_stack: vec![],
+            drag_start: None,
+        }
+    }
+}
+
+impl<Params: Component + Clone> CadParamsHistory<Params> {
+    /// Record the snapshot (before an edit), dropping the oldest over `capacity`.
+    ///
+    /// Clears the redo stack.
+    pub fn record(&mut self, snapshot: Params, capacity: usize) {
+        self.redo_stack.clear();
+        if capacity == 0 {
+            return;
+        }
+        self.undo_stack.push(snapshot);
+        if self.undo_stack.len() > capacity {
+            let excess = self.undo_stack.len() - capacity;
+            self.undo_stack.drain(..excess);
+        }
+    }
+
+    /// Pop the last snapshot to restore, pushing the `current` params to the redo stack.
+    pub fn undo(&mut self, current: Params) -> Option<Params> {
+        let snapshot = self.undo_stack.pop()?;
+        self.redo_stack.push(current);
+        Some(snapshot)
+    }
+
+    /// Pop the last undone snapshot to restore, pushing the `current` params to the undo stack.
+    pub fn redo(&mut self, current: Params) -> Option<Params> {
+        let snapshot = self.redo_stack.pop()?;
+        self.undo_stack.push(current);
+        Some(snapshot)
+    }
+}
+
+/// Marks a [`CadGeneratedRoot`] with meshes tessellated with the coarse preview tolerance (while dragging a slider).
+///
+/// Holds the names of the shells with preview meshes, which keep the preview tolerance for the rest of the drag.
+/// Removed once the meshes are queued for rebuilding with the full quality tolerance.
+/// Refer [`PmetraGlobalSettings::slider_drag_preview_tolerance`](crate::pmetra_plugins::resources::PmetraGlobalSettings::slider_drag_preview_tolerance).
+#[derive(Debug, Default, Component)]
+pub struct CadGeneratedRootPreviewMeshes(pub HashSet<CadShellName>);
+
 /// Marker for CAD generated mesh root.
 #[derive(Debug, Component, Reflect)]
 pub struct CadGeneratedMesh;
 
+/// Mesh of a tagged face (with its own material) of a [`CadGeneratedMesh`].
+///
+/// Spawned as a child of the [`CadGeneratedMesh`].
+#[derive(Debug, Component, Clone)]
+pub struct CadGeneratedFaceMesh(pub CadElementTag);
+
 /// Marker indicating which [`CadGeneratedMesh`] [`Entity`] it belongs to.
 #[derive(Debug, Component, Reflect)]
 pub struct BelongsToCadGeneratedMesh(pub Entity);
@@ -35,6 +126,36 @@ pub struct BelongsToCadGeneratedMesh(pub Entity);
 #[derive(Debug, Component)]
 pub struct CadGeneratedMeshOutlines(pub CadMeshOutlines);
 
+/// Maps the triangles of the [`CadGeneratedMesh`] to the B-rep faces of its shell.
+///
+/// Used for face picking.
+#[derive(Debug, Component)]
+pub struct CadGeneratedMeshFaces(pub CadMeshFaces);
+
+/// Levels of detail of the [`CadGeneratedMesh`]. Refer [`CadMeshBuilder::set_lods`](crate::pmetra_core::builders::CadMeshBuilder::set_lods).
+///
+/// Its [`Mesh3d`] (and [`CadGeneratedMeshFaces`]) are swapped by the distance to the [`CadCamera`](super::camera::CadCamera).
+#[derive(Debug, Component, Clone)]
+pub struct CadGeneratedMeshLods {
+    /// Mesh (and faces) used closer than the first level of detail.
+    pub base: (Handle<Mesh>, CadMeshFaces),
+    /// Levels of detail as `(distance, mesh, faces)`, sorted by distance.
+    pub lods: Vec<(f32, Handle<Mesh>, CadMeshFaces)>,
+}
+
+impl CadGeneratedMeshLods {
+    /// Get the mesh (and faces) to use at the distance from the camera.
+    pub fn at_distance(&self, distance: f32) -> (&Handle<Mesh>, &CadMeshFaces) {
+        self.lods
+            .iter()
+            .rev()
+            .find(|(lod_distance, ..)| distance >= *lod_distance)
+            .map_or((&self.base.0, &self.base.1), |(_, mesh_hdl, faces)| {
+                (mesh_hdl, faces)
+            })
+    }
+}
+
 #[derive(Debug, Component)]
 pub struct CadGeneratedSlider;
 
@@ -55,6 +176,12 @@ pub enum CadGeneratedSliderState {
 #[derive(Debug, Component)]
 pub struct CadGeneratedSliderPreviousTransform(pub Transform);
 
+/// [`ParamIssue`]s of the last rejected (or clamped) edit by the slider.
+///
+/// Cleared on drag end or on the next valid edit.
+#[derive(Debug, Component, Default)]
+pub struct CadGeneratedSliderIssues(pub Vec<ParamIssue>);
+
 #[derive(Debug, Component)]
 pub struct BelongsToCadGeneratedSlider(pub Entity);
 
@@ -0,0 +1,355 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use bevy::{platform::collections::HashMap, prelude::*, reflect::TypePath};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::pmetra_core::builders::PmetraModelling;
+
+use super::{
+    cleanup_manager::Cleanup, components::cad::CadGeneratedRoot, events::cad::GenerateCadModel,
+};
+
+/// Document of the parametric models (ie: [`CadGeneratedRoot`]s) in the world.
+///
+/// Records the params (and root transform) of each model, to regenerate them on load.
+/// Serialized as RON or JSON (refer [`CadDocumentFormat`]).
+///
+/// Only params types registered via [`PmetraDocumentPlugin<Params>`](super::plugins::PmetraDocumentPlugin)
+/// are saved/loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CadDocument {
+    /// Version of the document format.
+    #[serde(default = "CadDocument::current_version")]
+    pub version: u32,
+    pub entries: Vec<CadDocumentEntry>,
+}
+
+/// Entry of a parametric model in the [`CadDocument`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CadDocumentEntry {
+    /// Name of the params type. Refer [`params_type_name`].
+    pub params_type: String,
+    /// Params values.
+    pub params: serde_json::Value,
+    /// Transform of the root of the model.
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+/// Serialization format of the [`CadDocument`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CadDocumentFormat {
+    #[default]
+    Ron,
+    Json,
+}
+
+impl CadDocumentFormat {
+    /// Get the format from the extension of the path (`.ron` or `.json`).
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ron") => Ok(Self::Ron),
+            Some("json") => Ok(Self::Json),
+            _ => Err(anyhow!("Unknown document format for path: {:?}", path)),
+        }
+    }
+}
+
+impl Default for CadDocument {
+    fn default() -> Self {
+        Self {
+            version: Self::current_version(),
+            entries: vec![],
+        }
+    }
+}
+
+impl CadDocument {
+    pub const VERSION: u32 = 1;
+
+    fn current_version() -> u32 {
+        Self::VERSION
+    }
+
+    /// Add an entry for the params (with the root transform).
+    pub fn add_params<Params: Serialize + TypePath>(
+        &mut self,
+        params: &Params,
+        transform: Transform,
+    ) -> Result<Self> {
+        self.entries
+            .push(to_entry(params_type_name::<Params>(), params, transform)?);
+        Ok(self.clone())
+    }
+
+    /// Serialize the document in the `format`.
+    pub fn to_text(&self, format: CadDocumentFormat) -> Result<String> {
+        match format {
+            CadDocumentFormat::Ron => ron::ser::to_string_pretty(self, default())
+                .with_context(|| "Could not serialize document to RON!"),
+            CadDocumentFormat::Json => serde_json::to_string_pretty(self)
+                .with_context(|| "Could not serialize document to JSON!"),
+        }
+    }
+
+    /// Deserialize the document from `text` in the `format`.
+    pub fn from_text(text: &str, format: CadDocumentFormat) -> Result<Self> {
+        match format {
+            CadDocumentFormat::Ron => {
+                ron::from_str(text).with_context(|| "Could not deserialize RON document!")
+            }
+            CadDocumentFormat::Json => {
+                serde_json::from_str(text).with_context(|| "Could not deserialize JSON document!")
+            }
+        }
+    }
+
+    /// Save the document to the file at `path`. Format is based on the extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let text = self.to_text(CadDocumentFormat::from_path(path)?)?;
+        std::fs::write(path, text).with_context(|| format!("Could not write document: {:?}", path))
+    }
+
+    /// Load the document from the file at `path`. Format is based on the extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read document: {:?}", path))?;
+        Self::from_text(&text, CadDocumentFormat::from_path(path)?)
+    }
+
+    /// Create the document from the models in the world.
+    ///
+    /// Entries are ordered by the root entities.
+    pub fn from_world(world: &mut World) -> Result<Self> {
+        let params_types = world
+            .get_resource::<CadDocumentRegistry>()
+            .map(|registry| registry.params_types.clone())
+            .unwrap_or_default();
+        let mut entries = vec![];
+        for (params_type, CadDocumentParamsType { save, .. }) in params_types.iter() {
+            entries.extend(save(world, params_type)?);
+        }
+        entries.sort_by_key(|(root, _)| *root);
+
+        Ok(Self {
+            entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+            ..default()
+        })
+    }
+
+    /// Fire [`GenerateCadModel<Params>`] for each entry, via the [`CadDocumentRegistry`].
+    ///
+    /// Entries with unregistered params types (or invalid params) are skipped and returned as errors.
+    pub fn generate_in_world(&self, world: &mut World) -> Result<()> {
+        let params_types = world
+            .get_resource::<CadDocumentRegistry>()
+            .map(|registry| registry.params_types.clone())
+            .unwrap_or_default();
+        let errors = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let Some(CadDocumentParamsType { load, .. }) = params_types.get(&entry.params_type)
+                else {
+                    return Some(format!("Unregistered params type: {}", entry.params_type));
+                };
+                load(world, entry)
+                    .err()
+                    .map(|e| format!("{}: {:#}", entry.params_type, e))
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(anyhow!(
+                "Could not load document entries:\n{}",
+                errors.join("\n")
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Functions to save/load the entries of a params type.
+#[derive(Debug, Clone, Copy)]
+struct CadDocumentParamsType {
+    save: fn(&mut World, &str) -> Result<Vec<(Entity, CadDocumentEntry)>>,
+    load: fn(&mut World, &CadDocumentEntry) -> Result<()>,
+    root_entry: fn(EntityRef, &str) -> Option<Result<CadDocumentEntry>>,
+}
+
+/// Registry of the params types (by [`params_type_name`]) that can be saved/loaded in a [`CadDocument`].
+///
+/// Params types are registered via [`PmetraDocumentPlugin<Params>`](super::plugins::PmetraDocumentPlugin).
+#[derive(Debug, Resource, Default)]
+pub struct CadDocumentRegistry {
+    params_types: HashMap<String, CadDocumentParamsType>,
+}
+
+impl CadDocumentRegistry {
+    /// Register the params type.
+    ///
+    /// Params types with an already registered name are skipped (with a warning).
+    pub fn register<
+        Params: PmetraModelling + Component + TypePath + Serialize + DeserializeOwned,
+    >(
+        &mut self,
+    ) {
+        let params_type = params_type_name::<Params>();
+        if self.params_types.contains_key(params_type) {
+            warn!(
+                "Params type: {} is already registered, skipping!",
+                params_type
+            );
+            return;
+        }
+        self.params_types.insert(
+            params_type.to_string(),
+            CadDocumentParamsType {
+                save: save_entries::<Params>,
+                load: load_entry::<Params>,
+                root_entry: root_entry::<Params>,
+            },
+        );
+    }
+
+    /// If the params type (by name) is registered.
+    pub fn contains(&self, params_type: &str) -> bool {
+        self.params_types.contains_key(params_type)
+    }
+
+    /// Entry of the model on the `root` entity, if its params type is registered.
+    pub fn root_entry(&self, root: EntityRef) -> Option<Result<CadDocumentEntry>> {
+        self.params_types.iter().find_map(
+            |(params_type, CadDocumentParamsType { root_entry, .. })| root_entry(root, params_type),
+        )
+    }
+}
+
+/// Name of the params type used in the [`CadDocument`], ie: the (stable) full [`TypePath`] of the type.
+///
+/// The module path can be set explicitly via `#[type_path = ".."]` on the `Reflect`/`TypePath` derive,
+/// eg: to keep the saved documents loading after moving the type.
+pub fn params_type_name<Params: TypePath>() -> &'static str {
+    Params::type_path()
+}
+
+fn save_entries<Params: Component + Serialize>(
+    world: &mut World,
+    params_type: &str,
+) -> Result<Vec<(Entity, CadDocumentEntry)>> {
+    let mut roots = world.query_filtered::<
+        (Entity, &Params, &Transform),
+        (With<CadGeneratedRoot>, Without<Cleanup>),
+    >();
+    roots
+        .iter(world)
+        .map(|(root, params, transform)| Ok((root, to_entry(params_type, params, *transform)?)))
+        .collect()
+}
+
+fn root_entry<Params: Component + Serialize>(
+    root: EntityRef,
+    params_type: &str,
+) -> Option<Result<CadDocumentEntry>> {
+    let params = root.get::<Params>()?;
+    let transform = root.get::<Transform>().copied().unwrap_or_default();
+    Some(to_entry(params_type, params, transform))
+}
+
+fn to_entry<Params: Serialize>(
+    params_type: &str,
+    params: &Params,
+    transform: Transform,
+) -> Result<CadDocumentEntry> {
+    Ok(CadDocumentEntry {
+        params_type: params_type.to_string(),
+        params: serde_json::to_value(params).with_context(|| "Could not serialize params!")?,
+        transform,
+    })
+}
+
+fn load_entry<Params: PmetraModelling + Component + DeserializeOwned>(
+    world: &mut World,
+    entry: &CadDocumentEntry,
+) -> Result<()> {
+    let params = serde_json::from_value::<Params>(entry.params.clone())
+        .with_context(|| "Could not deserialize params!")?;
+    world.write_message(GenerateCadModel {
+        params,
+        transform: entry.transform,
+        remove_existing_models: false,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::test_utils::MeshPerShell;
+
+    #[test]
+    pub fn test_cad_document_round_trip() {
+        let mut document = CadDocument::default();
+        document.entries.push(CadDocumentEntry {
+            params_type: "SimpleCube".to_string(),
+            params: serde_json::json!({ "side_length": 0.5, "array_count": 3 }),
+            transform: Transform::from_xyz(1., 2., 3.),
+        });
+        for format in [CadDocumentFormat::Ron, CadDocumentFormat::Json] {
+            let text = document.to_text(format).unwrap();
+            assert_eq!(CadDocument::from_text(&text, format).unwrap(), document);
+        }
+        assert_eq!(
+            CadDocumentFormat::from_path("models.json").unwrap(),
+            CadDocumentFormat::Json
+        );
+        assert!(CadDocumentFormat::from_path("models.txt").is_err());
+    }
+
+    #[allow(dead_code)]
+    mod a {
+        #[derive(bevy::reflect::TypePath)]
+        pub struct Part;
+    }
+
+    #[allow(dead_code)]
+    mod b {
+        #[derive(bevy::reflect::TypePath)]
+        pub struct Part;
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypePath)]
+    struct Assembly<T>(std::marker::PhantomData<T>);
+
+    #[test]
+    pub fn test_params_type_name() {
+        // Types with the same name (and generics) in different modules don't collide...
+        assert_eq!(
+            params_type_name::<Assembly<a::Part>>(),
+            format!("{0}::Assembly<{0}::a::Part>", module_path!())
+        );
+        assert_ne!(
+            params_type_name::<Assembly<a::Part>>(),
+            params_type_name::<Assembly<b::Part>>()
+        );
+        assert_ne!(params_type_name::<a::Part>(), params_type_name::<b::Part>());
+    }
+
+    #[test]
+    pub fn test_cad_document_registry_duplicates() {
+        let mut registry = CadDocumentRegistry::default();
+        registry.register::<MeshPerShell>();
+        registry.register::<MeshPerShell>();
+        assert_eq!(registry.params_types.len(), 1);
+        assert!(registry.contains(params_type_name::<MeshPerShell>()));
+        assert!(!registry.contains("MeshPerShell"));
+    }
+}
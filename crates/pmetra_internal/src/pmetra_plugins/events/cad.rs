@@ -1,7 +1,10 @@
-use bevy::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
 
 use crate::{
-    pmetra_core::builders::{CadMeshesBuilder, CadShellName, PmetraModelling},
+    pmetra_core::builders::{
+        CadFaceHit, CadMeshesBuilder, CadShellName, CadShellsBuilders, CadShellsByName,
+        PmetraModelling,
+    },
     prelude::BelongsToCadGeneratedRoot,
 };
 
@@ -29,12 +32,124 @@ impl<Params: PmetraModelling + Component + Default> Default for GenerateCadModel
     }
 }
 
+/// Event used to spawn shells builders for building shells off the main thread.
+#[derive(Message, Clone)]
+pub struct SpawnShellsBuilder<Params: PmetraModelling + Component> {
+    pub belongs_to_root: BelongsToCadGeneratedRoot,
+    pub shells_builders: CadShellsBuilders<Params>,
+    /// Keys of the shells for the params. Refer [`CadShellsBuilders::set_shell_key`].
+    pub shell_keys: HashMap<CadShellName, u64>,
+    /// Existing shells with unchanged keys. These are reused instead of being rebuilt.
+    pub reused_shells: CadShellsByName,
+    /// Index count at the time of creation. Used to check for the latest shells build.
+    pub created_at_idx: usize,
+}
+
 /// Event used to spawn individual mesh builders for parallel meshing.
 #[derive(Debug, Message, Reflect, Clone)]
 pub struct SpawnMeshesBuilder<Params: PmetraModelling + Component> {
     pub belongs_to_root: BelongsToCadGeneratedRoot,
     pub shell_name: CadShellName,
     pub meshes_builder: CadMeshesBuilder<Params>,
+    /// If the shell changed since the last build.
+    ///
+    /// If `false`, the existing mesh of the shell is reused (skipping tessellation) when available.
+    pub shell_changed: bool,
+    /// Key of the shell (or params) used for caching the tessellation.
+    ///
+    /// Refer [`MeshesBuilderCache`](crate::pmetra_plugins::resources::MeshesBuilderCache).
+    pub cache_key: Option<u64>,
     /// Index count at the time of creation. Used to check for the latest mesh build.
     pub created_at_idx: usize,
 }
+
+/// Event fired when a face of a [`CadGeneratedMesh`] is clicked.
+///
+/// Prerequisites:
+/// - Add plugin: [`PmetraInteractionsPlugin<Params>`].
+#[derive(Debug, Message, Clone)]
+pub struct CadFaceClicked {
+    /// Root of the clicked model.
+    pub root: Entity,
+    /// Clicked mesh entity.
+    pub mesh: Entity,
+    pub hit: CadFaceHit,
+}
+
+/// Event fired when the hovered face of a [`CadGeneratedMesh`] changes.
+///
+/// Prerequisites:
+/// - Add plugin: [`PmetraInteractionsPlugin<Params>`].
+#[derive(Debug, Message, Clone)]
+pub struct CadFaceHovered {
+    /// Root of the hovered model.
+    pub root: Entity,
+    /// Hovered mesh entity.
+    pub mesh: Entity,
+    pub hit: CadFaceHit,
+}
+
+/// Event when fired, **undoes** the last params edit of the [`CadGeneratedRoot`].
+///
+/// Prerequisites:
+/// - Add plugin: [`PmetraModellingPlugin<Params>`].
+#[derive(Debug, Message, Clone, Default)]
+pub struct UndoCadModel {
+    /// Root of the model. Undoes on all selected roots if `None`.
+    pub root: Option<Entity>,
+}
+
+/// Event when fired, **redoes** the last undone params edit of the [`CadGeneratedRoot`].
+///
+/// Prerequisites:
+/// - Add plugin: [`PmetraModellingPlugin<Params>`].
+#[derive(Debug, Message, Clone, Default)]
+pub struct RedoCadModel {
+    /// Root of the model. Redoes on all selected roots if `None`.
+    pub root: Option<Entity>,
+}
+
+/// Event when fired, **loads** the [`CadDocument`](crate::pmetra_plugins::document::CadDocument),
+/// ie: fires [`GenerateCadModel<Params>`] for each of its entries.
+///
+/// Prerequisites:
+/// - Enable feature: `serde`.
+/// - Add plugin: [`PmetraDocumentPlugin<Params>`] for each params type in the document.
+#[cfg(feature = "serde")]
+#[derive(Debug, Message, Clone)]
+pub struct LoadCadDocument {
+    pub document: crate::pmetra_plugins::document::CadDocument,
+    /// Remove all existing models (of any params type).
+    pub remove_existing_models: bool,
+}
+
+/// Event when fired, **exports** the [`CadGeneratedRoot`] hierarchy to a binary glTF (`.glb`).
+///
+/// Each [`CadGeneratedMesh`](crate::pmetra_plugins::components::cad::CadGeneratedMesh) is exported
+/// as a node (with its transform) under the node of the root. Refer [`CadGltfBuilder`](crate::pmetra_plugins::gltf_export::CadGltfBuilder).
+///
+/// The params are written in the `extras` of the node of the root, to re-import it as a parametric
+/// model via [`CadDocument::from_glb`](crate::pmetra_plugins::document::CadDocument::from_glb).
+///
+/// The bytes are returned via [`CadModelExported`].
+///
+/// Prerequisites:
+/// - Enable feature: `gltf`.
+/// - Add plugin: [`PmetraDocumentPlugin<Params>`] (to export the params).
+#[cfg(feature = "gltf")]
+#[derive(Debug, Message, Clone)]
+pub struct ExportCadModel {
+    /// Root of the model.
+    pub root: Entity,
+    /// Also write the bytes to the file at `path` (only in native environments).
+    pub path: Option<std::path::PathBuf>,
+}
+
+/// Event fired with the binary glTF (`.glb`) bytes of the [`ExportCadModel`].
+#[cfg(feature = "gltf")]
+#[derive(Debug, Message, Clone)]
+pub struct CadModelExported {
+    /// Root of the model.
+    pub root: Entity,
+    pub glb: Vec<u8>,
+}
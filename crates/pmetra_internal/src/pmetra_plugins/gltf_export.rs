@@ -0,0 +1,657 @@
+use std::{borrow::Cow, collections::BTreeMap, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use bevy::{
+    mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use gltf::json::{
+    self,
+    validation::{Checked::Valid, USize64},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::pmetra_core::builders::CadModelMesh;
+
+use super::document::{CadDocument, CadDocumentEntry};
+
+/// `extras` of the root node of an exported model, to re-import it as a parametric model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CadGltfExtras {
+    pmetra: CadDocumentEntry,
+}
+
+/// Builder of a binary glTF (`.glb`) from the nodes/meshes/materials of a model.
+///
+/// Meshes are deduplicated by their [`AssetId`]s, ie: instances of patterns share the same glTF mesh.
+/// Materials are deduplicated by value and exported without textures.
+///
+/// Refer [`ExportCadModel`](crate::pmetra_plugins::events::cad::ExportCadModel) to export a
+/// [`CadGeneratedRoot`](crate::pmetra_plugins::components::cad::CadGeneratedRoot).
+#[derive(Debug, Default)]
+pub struct CadGltfBuilder {
+    root: json::Root,
+    /// Nodes at the root of the scene.
+    scene_nodes: Vec<json::Index<json::Node>>,
+    /// Binary buffer of the vertex/index data.
+    buffer: Vec<u8>,
+    meshes: HashMap<(AssetId<Mesh>, AssetId<StandardMaterial>), json::Index<json::Mesh>>,
+    materials: HashMap<AssetId<StandardMaterial>, json::Index<json::Material>>,
+    /// glTF materials by their (debug formatted) value.
+    materials_by_value: HashMap<String, json::Index<json::Material>>,
+}
+
+impl CadGltfBuilder {
+    /// Add a node with the (local) `transform` as child of the `parent` (or at root of the scene).
+    pub fn add_node(
+        &mut self,
+        name: &str,
+        transform: Transform,
+        parent: Option<json::Index<json::Node>>,
+        mesh: Option<json::Index<json::Mesh>>,
+    ) -> Result<json::Index<json::Node>> {
+        let node = self.root.push(json::Node {
+            camera: None,
+            children: None,
+            extensions: Default::default(),
+            extras: Default::default(),
+            matrix: None,
+            mesh,
+            name: Some(name.to_string()),
+            rotation: Some(json::scene::UnitQuaternion(transform.rotation.to_array())),
+            scale: Some(transform.scale.to_array()),
+            translation: Some(transform.translation.to_array()),
+            skin: None,
+            weights: None,
+        });
+        match parent {
+            Some(parent) => {
+                let parent = self
+                    .root
+                    .nodes
+                    .get_mut(parent.value())
+                    .ok_or_else(|| anyhow!("Could not find parent node: {}", parent.value()))?;
+                parent.children.get_or_insert_with(Vec::new).push(node);
+            }
+            None => self.scene_nodes.push(node),
+        }
+        Ok(node)
+    }
+
+    /// Set the `extras` (serialized as JSON) of the node.
+    pub fn set_node_extras(
+        &mut self,
+        node: json::Index<json::Node>,
+        extras: &impl Serialize,
+    ) -> Result<()> {
+        let node = self
+            .root
+            .nodes
+            .get_mut(node.value())
+            .ok_or_else(|| anyhow!("Could not find node: {}", node.value()))?;
+        node.extras = Some(
+            serde_json::value::to_raw_value(extras)
+                .with_context(|| "Could not serialize node extras!")?,
+        );
+        Ok(())
+    }
+
+    /// Set the [`CadDocumentEntry`] (ie: params) of the model in the `extras` of the (root) node.
+    ///
+    /// Refer [`CadDocument::from_glb`].
+    pub fn set_node_document_entry(
+        &mut self,
+        node: json::Index<json::Node>,
+        entry: CadDocumentEntry,
+    ) -> Result<()> {
+        self.set_node_extras(node, &CadGltfExtras { pmetra: entry })
+    }
+
+    /// Add the (triangle list) mesh with the material, if not added already.
+    ///
+    /// Exports positions, normals, UVs (if present) and indices.
+    pub fn add_mesh(
+        &mut self,
+        mesh_id: AssetId<Mesh>,
+        mesh: &Mesh,
+        material_id: AssetId<StandardMaterial>,
+        material: &StandardMaterial,
+    ) -> Result<json::Index<json::Mesh>> {
+        if let Some(gltf_mesh) = self.meshes.get(&(mesh_id, material_id)) {
+            return Ok(*gltf_mesh);
+        }
+        let material = self.add_material(material_id, material);
+        let gltf_mesh = self.push_mesh(mesh, material)?;
+        self.meshes.insert((mesh_id, material_id), gltf_mesh);
+        Ok(gltf_mesh)
+    }
+
+    /// Add the material (without textures), if not added already.
+    pub fn add_material(
+        &mut self,
+        material_id: AssetId<StandardMaterial>,
+        material: &StandardMaterial,
+    ) -> json::Index<json::Material> {
+        if let Some(gltf_material) = self.materials.get(&material_id) {
+            return *gltf_material;
+        }
+        let gltf_material = self.push_material(material);
+        self.materials.insert(material_id, gltf_material);
+        gltf_material
+    }
+
+    /// Add the [`CadModelMesh`]s (ie: built via [`build_model`](crate::pmetra_core::builders::build_model))
+    /// as nodes under the `parent` node.
+    ///
+    /// Meshes of the same shell and pattern (or mesh name) are deduplicated, materials by value.
+    pub fn add_model_meshes(
+        &mut self,
+        model_meshes: &[CadModelMesh],
+        parent: Option<json::Index<json::Node>>,
+    ) -> Result<()> {
+        let mut gltf_meshes = HashMap::new();
+        for model_mesh in model_meshes {
+            // Instances of a pattern share the mesh and materials...
+            let name = model_mesh.pattern_name.as_ref().unwrap_or(&model_mesh.name);
+            let mesh_key = (model_mesh.shell_name.clone(), name.clone(), None);
+            let gltf_mesh = match gltf_meshes.get(&mesh_key) {
+                Some(gltf_mesh) => *gltf_mesh,
+                None => {
+                    let material = self.push_material(&model_mesh.material);
+                    let gltf_mesh = self.push_mesh(&model_mesh.mesh, material)?;
+                    gltf_meshes.insert(mesh_key, gltf_mesh);
+                    gltf_mesh
+                }
+            };
+            let mesh_node = self.add_node(
+                &model_mesh.name,
+                model_mesh.transform,
+                parent,
+                Some(gltf_mesh),
+            )?;
+            // Sort face meshes for a stable output...
+            let mut face_meshes = model_mesh.face_meshes.iter().collect::<Vec<_>>();
+            face_meshes.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+            for (tag, (face_mesh, face_material)) in face_meshes {
+                let face_key = (
+                    model_mesh.shell_name.clone(),
+                    name.clone(),
+                    Some(tag.clone()),
+                );
+                let gltf_face_mesh = match gltf_meshes.get(&face_key) {
+                    Some(gltf_face_mesh) => *gltf_face_mesh,
+                    None => {
+                        let material = self.push_material(face_material);
+                        let gltf_face_mesh = self.push_mesh(face_mesh, material)?;
+                        gltf_meshes.insert(face_key, gltf_face_mesh);
+                        gltf_face_mesh
+                    }
+                };
+                self.add_node(
+                    &format!("{}:{}", model_mesh.name.0, tag.0),
+                    Transform::default(),
+                    Some(mesh_node),
+                    Some(gltf_face_mesh),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Push the mesh (with the material) without deduplication.
+    fn push_mesh(
+        &mut self,
+        mesh: &Mesh,
+        material: json::Index<json::Material>,
+    ) -> Result<json::Index<json::Mesh>> {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return Err(anyhow!(
+                "Unsupported primitive topology: {:?}",
+                mesh.primitive_topology()
+            ));
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return Err(anyhow!("Could not get Float32x3 for ATTRIBUTE_POSITION!"));
+        };
+        let (min, max) = positions
+            .iter()
+            .fold((Vec3::MAX, Vec3::MIN), |(min, max), position| {
+                let position = Vec3::from_array(*position);
+                (min.min(position), max.max(position))
+            });
+
+        let mut attributes = BTreeMap::new();
+        let positions = self.push_accessor(
+            positions.iter().flatten().copied(),
+            positions.len(),
+            json::accessor::Type::Vec3,
+            Some((min.to_array(), max.to_array())),
+        );
+        attributes.insert(Valid(json::mesh::Semantic::Positions), positions);
+        if let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        {
+            let normals = self.push_accessor(
+                normals.iter().flatten().copied(),
+                normals.len(),
+                json::accessor::Type::Vec3,
+                None,
+            );
+            attributes.insert(Valid(json::mesh::Semantic::Normals), normals);
+        }
+        if let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            let uvs = self.push_accessor(
+                uvs.iter().flatten().copied(),
+                uvs.len(),
+                json::accessor::Type::Vec2,
+                None,
+            );
+            attributes.insert(Valid(json::mesh::Semantic::TexCoords(0)), uvs);
+        }
+        let indices = mesh.indices().map(|indices| {
+            let indices = match indices {
+                Indices::U16(indices) => indices.iter().map(|i| *i as u32).collect::<Vec<_>>(),
+                Indices::U32(indices) => indices.clone(),
+            };
+            self.push_indices_accessor(&indices)
+        });
+
+        Ok(self.root.push(json::Mesh {
+            extensions: Default::default(),
+            extras: Default::default(),
+            name: None,
+            primitives: vec![json::mesh::Primitive {
+                attributes,
+                extensions: Default::default(),
+                extras: Default::default(),
+                indices,
+                material: Some(material),
+                mode: Valid(json::mesh::Mode::Triangles),
+                targets: None,
+            }],
+            weights: None,
+        }))
+    }
+
+    /// Push the material, if no material with the same (exported) value was pushed already.
+    fn push_material(&mut self, material: &StandardMaterial) -> json::Index<json::Material> {
+        let gltf_material = gltf_material(material);
+        let key = format!("{:?}", gltf_material);
+        if let Some(gltf_material) = self.materials_by_value.get(&key) {
+            return *gltf_material;
+        }
+        let gltf_material = self.root.push(gltf_material);
+        self.materials_by_value.insert(key, gltf_material);
+        gltf_material
+    }
+
+    /// Build the binary glTF (`.glb`) bytes.
+    pub fn build_glb(mut self) -> Result<Vec<u8>> {
+        if !self.buffer.is_empty() {
+            self.root.push(json::Buffer {
+                byte_length: USize64::from(self.buffer.len()),
+                extensions: Default::default(),
+                extras: Default::default(),
+                name: None,
+                uri: None,
+            });
+        }
+        let scene = self.root.push(json::Scene {
+            extensions: Default::default(),
+            extras: Default::default(),
+            name: None,
+            nodes: self.scene_nodes,
+        });
+        self.root.scene = Some(scene);
+
+        let mut json_bytes = json::serialize::to_vec(&self.root)?;
+        // Chunks are aligned to 4 bytes (json padded with spaces)...
+        while json_bytes.len() % 4 != 0 {
+            json_bytes.push(b' ');
+        }
+        let bin_chunk_length = if self.buffer.is_empty() {
+            0
+        } else {
+            8 + self.buffer.len()
+        };
+        let glb = gltf::binary::Glb {
+            header: gltf::binary::Header {
+                magic: *b"glTF",
+                version: 2,
+                length: (12 + 8 + json_bytes.len() + bin_chunk_length) as u32,
+            },
+            bin: (!self.buffer.is_empty()).then_some(Cow::Owned(self.buffer)),
+            json: Cow::Owned(json_bytes),
+        };
+        let mut bytes = vec![];
+        glb.to_writer(&mut bytes)
+            .map_err(|e| anyhow!("Could not write glb: {:?}", e))?;
+
+        Ok(bytes)
+    }
+
+    /// Push the `f32` components into the buffer, with a view and accessor for them.
+    fn push_accessor(
+        &mut self,
+        components: impl Iterator<Item = f32>,
+        count: usize,
+        type_: json::accessor::Type,
+        min_max: Option<([f32; 3], [f32; 3])>,
+    ) -> json::Index<json::Accessor> {
+        let byte_offset = self.buffer.len();
+        for component in components {
+            self.buffer.extend_from_slice(&component.to_le_bytes());
+        }
+        let view = self.push_buffer_view(byte_offset, json::buffer::Target::ArrayBuffer);
+        let (min, max) = min_max
+            .map(|(min, max)| {
+                (
+                    Some(json::Value::from(min.to_vec())),
+                    Some(json::Value::from(max.to_vec())),
+                )
+            })
+            .unwrap_or_default();
+        self.root.push(json::Accessor {
+            buffer_view: Some(view),
+            byte_offset: None,
+            count: USize64::from(count),
+            component_type: Valid(json::accessor::GenericComponentType(
+                json::accessor::ComponentType::F32,
+            )),
+            extensions: Default::default(),
+            extras: Default::default(),
+            type_: Valid(type_),
+            min,
+            max,
+            name: None,
+            normalized: false,
+            sparse: None,
+        })
+    }
+
+    /// Push the indices into the buffer, with a view and accessor for them.
+    fn push_indices_accessor(&mut self, indices: &[u32]) -> json::Index<json::Accessor> {
+        let byte_offset = self.buffer.len();
+        for index in indices {
+            self.buffer.extend_from_slice(&index.to_le_bytes());
+        }
+        let view = self.push_buffer_view(byte_offset, json::buffer::Target::ElementArrayBuffer);
+        self.root.push(json::Accessor {
+            buffer_view: Some(view),
+            byte_offset: None,
+            count: USize64::from(indices.len()),
+            component_type: Valid(json::accessor::GenericComponentType(
+                json::accessor::ComponentType::U32,
+            )),
+            extensions: Default::default(),
+            extras: Default::default(),
+            type_: Valid(json::accessor::Type::Scalar),
+            min: None,
+            max: None,
+            name: None,
+            normalized: false,
+            sparse: None,
+        })
+    }
+
+    /// Push a view of the buffer from `byte_offset` to its end.
+    ///
+    /// All components are 4 bytes, so views are always aligned.
+    fn push_buffer_view(
+        &mut self,
+        byte_offset: usize,
+        target: json::buffer::Target,
+    ) -> json::Index<json::buffer::View> {
+        self.root.push(json::buffer::View {
+            buffer: json::Index::new(0),
+            byte_length: USize64::from(self.buffer.len() - byte_offset),
+            byte_offset: Some(USize64::from(byte_offset)),
+            byte_stride: None,
+            extensions: Default::default(),
+            extras: Default::default(),
+            name: None,
+            target: Some(Valid(target)),
+        })
+    }
+}
+
+/// glTF material of the [`StandardMaterial`] (without textures).
+fn gltf_material(material: &StandardMaterial) -> json::Material {
+    let (alpha_mode, alpha_cutoff) = match material.alpha_mode {
+        AlphaMode::Opaque => (json::material::AlphaMode::Opaque, None),
+        AlphaMode::Mask(cutoff) => (
+            json::material::AlphaMode::Mask,
+            Some(json::material::AlphaCutoff(cutoff)),
+        ),
+        _ => (json::material::AlphaMode::Blend, None),
+    };
+    // glTF color factors are linear (emissive in 0..1)...
+    let emissive = material.emissive.to_f32_array_no_alpha();
+    json::Material {
+        alpha_cutoff,
+        alpha_mode: Valid(alpha_mode),
+        double_sided: material.double_sided,
+        emissive_factor: json::material::EmissiveFactor(emissive.map(|c| c.clamp(0., 1.))),
+        pbr_metallic_roughness: json::material::PbrMetallicRoughness {
+            base_color_factor: json::material::PbrBaseColorFactor(
+                material.base_color.to_linear().to_f32_array(),
+            ),
+            metallic_factor: json::material::StrengthFactor(material.metallic),
+            roughness_factor: json::material::StrengthFactor(material.perceptual_roughness),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+impl CadDocument {
+    /// Create the document from the params in the `extras` of the nodes of the binary glTF (`.glb`),
+    /// ie: exported via [`ExportCadModel`](crate::pmetra_plugins::events::cad::ExportCadModel).
+    ///
+    /// Transforms of the entries are the (global) transforms of the nodes.
+    pub fn from_glb(glb: &[u8]) -> Result<Self> {
+        let gltf = gltf::Gltf::from_slice(glb).with_context(|| "Could not parse glb!")?;
+        let mut entries = vec![];
+        for scene in gltf.scenes() {
+            for node in scene.nodes() {
+                collect_node_entries(&node, Transform::default(), &mut entries)?;
+            }
+        }
+        if entries.is_empty() {
+            return Err(anyhow!("No parametric models found in glb!"));
+        }
+
+        Ok(Self {
+            entries,
+            ..default()
+        })
+    }
+
+    /// Load the document from the binary glTF (`.glb`) file at `path`. Refer [`Self::from_glb`].
+    pub fn load_glb(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let glb = std::fs::read(path).with_context(|| format!("Could not read glb: {:?}", path))?;
+        Self::from_glb(&glb)
+    }
+}
+
+/// Collect the [`CadDocumentEntry`]s from the `extras` of the node and its descendants.
+fn collect_node_entries(
+    node: &gltf::Node,
+    parent_transform: Transform,
+    entries: &mut Vec<CadDocumentEntry>,
+) -> Result<()> {
+    let (translation, rotation, scale) = node.transform().decomposed();
+    let transform = parent_transform.mul_transform(Transform {
+        translation: Vec3::from_array(translation),
+        rotation: Quat::from_array(rotation),
+        scale: Vec3::from_array(scale),
+    });
+    if let Some(extras) = node.extras() {
+        // Skip extras not written by pmetra...
+        if let Ok(CadGltfExtras { mut pmetra }) = serde_json::from_str(extras.get()) {
+            pmetra.transform = transform;
+            entries.push(pmetra);
+            // Descendants belong to the model...
+            return Ok(());
+        }
+    }
+    for child in node.children() {
+        collect_node_entries(&child, transform, entries)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    pub fn test_cad_gltf_builder_dedups_meshes_and_materials() {
+        let mesh = Mesh::from(Cuboid::default());
+        let (mesh_id, material_id) = (AssetId::<Mesh>::default(), AssetId::default());
+        let material = StandardMaterial::from(Color::WHITE);
+
+        let mut builder = CadGltfBuilder::default();
+        let root = builder
+            .add_node("Root", Transform::default(), None, None)
+            .unwrap();
+        for idx in 0..2 {
+            let gltf_mesh = builder
+                .add_mesh(mesh_id, &mesh, material_id, &material)
+                .unwrap();
+            builder
+                .add_node(
+                    &format!("Mesh{}", idx),
+                    Transform::from_xyz(idx as f32, 0., 0.),
+                    Some(root),
+                    Some(gltf_mesh),
+                )
+                .unwrap();
+        }
+        let glb = builder.build_glb().unwrap();
+
+        let gltf = gltf::Gltf::from_slice(&glb).unwrap();
+        assert_eq!(gltf.nodes().count(), 3);
+        assert_eq!(gltf.meshes().count(), 1);
+        assert_eq!(gltf.materials().count(), 1);
+        let primitive = gltf.meshes().next().unwrap().primitives().next().unwrap();
+        assert!(primitive.get(&gltf::Semantic::Normals).is_some());
+        assert!(primitive.get(&gltf::Semantic::TexCoords(0)).is_some());
+        assert_eq!(primitive.indices().unwrap().count(), 36);
+    }
+
+    #[test]
+    pub fn test_cad_gltf_builder_dedups_materials_by_value() {
+        let mesh = Mesh::from(Cuboid::default());
+        let mesh_ids = [AssetId::<Mesh>::invalid(), AssetId::default()];
+        let material_ids = [AssetId::<StandardMaterial>::invalid(), AssetId::default()];
+
+        let mut builder = CadGltfBuilder::default();
+        // Different materials (by id) with the same value...
+        for (mesh_id, material_id) in mesh_ids.into_iter().zip(material_ids) {
+            builder
+                .add_mesh(
+                    mesh_id,
+                    &mesh,
+                    material_id,
+                    &StandardMaterial::from(Color::WHITE),
+                )
+                .unwrap();
+        }
+        // Materials already added by id are reused...
+        builder.add_material(AssetId::default(), &StandardMaterial::from(Color::BLACK));
+        let glb = builder.build_glb().unwrap();
+
+        let gltf = gltf::Gltf::from_slice(&glb).unwrap();
+        assert_eq!(gltf.meshes().count(), 2);
+        assert_eq!(gltf.materials().count(), 1);
+    }
+
+    #[test]
+    pub fn test_cad_gltf_builder_add_model_meshes() {
+        use crate::pmetra_core::builders::{CadElementTag, CadMeshName, CadShellName};
+
+        let model_mesh = |shell: &str, name: &str, pattern: Option<&str>| CadModelMesh {
+            name: CadMeshName(name.into()),
+            shell_name: CadShellName(shell.into()),
+            mesh: Mesh::from(Cuboid::default()),
+            transform: Transform::default(),
+            material: StandardMaterial::from(Color::WHITE),
+            outlines: default(),
+            faces: default(),
+            face_meshes: default(),
+            pattern_name: pattern.map(|pattern| CadMeshName(pattern.into())),
+            lods: vec![],
+        };
+        let mut pattern_meshes = [
+            model_mesh("Cube", "Cube0", Some("Cube")),
+            model_mesh("Cube", "Cube1", Some("Cube")),
+        ];
+        for pattern_mesh in pattern_meshes.iter_mut() {
+            pattern_mesh.face_meshes.insert(
+                CadElementTag::new("Top"),
+                (
+                    Mesh::from(Cuboid::default()),
+                    StandardMaterial::from(Color::BLACK),
+                ),
+            );
+        }
+        let mut model_meshes = pattern_meshes.to_vec();
+        // Different mesh with the same material value...
+        model_meshes.push(model_mesh("Other", "Other", None));
+
+        let mut builder = CadGltfBuilder::default();
+        let root = builder
+            .add_node("Root", Transform::default(), None, None)
+            .unwrap();
+        builder.add_model_meshes(&model_meshes, Some(root)).unwrap();
+        let glb = builder.build_glb().unwrap();
+
+        let gltf = gltf::Gltf::from_slice(&glb).unwrap();
+        // Root + 3 meshes + 2 face meshes...
+        assert_eq!(gltf.nodes().count(), 6);
+        // Pattern instances share the mesh (and face mesh)...
+        assert_eq!(gltf.meshes().count(), 3);
+        assert_eq!(gltf.materials().count(), 2);
+        assert!(gltf
+            .nodes()
+            .any(|node| node.name() == Some("Cube1:Top") && node.mesh().is_some()));
+    }
+
+    #[test]
+    pub fn test_cad_document_from_glb() {
+        let entry = CadDocumentEntry {
+            params_type: "SimpleCube".to_string(),
+            params: serde_json::json!({ "side_length": 0.5, "array_count": 3 }),
+            transform: Transform::default(),
+        };
+        let transform = Transform::from_xyz(1., 2., 3.);
+
+        let mut builder = CadGltfBuilder::default();
+        let group = builder
+            .add_node("Group", Transform::from_xyz(0., 1., 0.), None, None)
+            .unwrap();
+        let root = builder
+            .add_node("Root", transform, Some(group), None)
+            .unwrap();
+        builder
+            .set_node_document_entry(root, entry.clone())
+            .unwrap();
+        let glb = builder.build_glb().unwrap();
+
+        let document = CadDocument::from_glb(&glb).unwrap();
+        assert_eq!(document.entries.len(), 1);
+        assert_eq!(document.entries[0].params, entry.params);
+        assert!(document.entries[0]
+            .transform
+            .translation
+            .abs_diff_eq(Vec3::new(1., 3., 3.), 1e-6));
+        assert!(CadDocument::from_glb(&CadGltfBuilder::default().build_glb().unwrap()).is_err());
+    }
+}
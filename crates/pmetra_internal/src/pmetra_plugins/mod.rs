@@ -1,6 +1,10 @@
 pub mod cleanup_manager;
 pub mod components;
+#[cfg(feature = "serde")]
+pub mod document;
 pub mod events;
+#[cfg(feature = "gltf")]
+pub mod gltf_export;
 pub mod plugins;
 pub mod resources;
 pub mod systems;
@@ -16,32 +16,48 @@ use crate::{
 
 use super::{
     cleanup_manager::CleanupManagerPlugin,
-    events::cad::{GenerateCadModel, SpawnMeshesBuilder},
+    events::cad::{
+        CadFaceClicked, CadFaceHovered, GenerateCadModel, RedoCadModel, SpawnMeshesBuilder,
+        UndoCadModel,
+    },
     resources::{
-        MeshesBuilderFinishedResultsMap, MeshesBuilderQueue, MeshesBuilderQueueInspector,
-        PmetraGlobalSettings,
+        MeshesBuilderCache, MeshesBuilderFinishedResultsMap, MeshesBuilderQueue,
+        MeshesBuilderQueueInspector, PmetraGlobalSettings, ShellsBuilderQueue,
     },
     systems::{
         cad::{
+            faces::{handle_face_clicks, handle_face_hovers},
+            history::{handle_undo_redo_events, send_undo_redo_events_on_keys},
+            lod::update_cad_mesh_lods,
             // mesh::show_mesh_local_debug_axis,
             model::{
-                handle_spawn_meshes_builder_events, mesh_builder_to_bundle,
-                shells_to_mesh_builder_events, shells_to_sliders, spawn_shells_by_name_on_generate,
-                update_shells_by_name_on_params_change,
+                handle_spawn_meshes_builder_events, handle_spawn_shells_builder_events,
+                mesh_builder_to_bundle, shells_to_mesh_builder_events, shells_to_sliders,
+                spawn_shells_by_name_on_generate, update_shells_by_name_on_params_change,
             },
             outlines::render_mesh_outlines,
             params_ui::setup_param_display_ui,
             root::deselect_all_root_if_clicked_outside,
-            settings::show_selected_mesh_outlines,
+            settings::{show_selected_mesh_outlines, undo_redo_keys},
             slider::{
                 draw_slider_gizmo, scale_sliders_based_on_zoom_level, update_params_from_sliders,
-                update_slider_visibility_based_on_root_selection,
+                update_slider_color_from_issues, update_slider_visibility_based_on_root_selection,
             },
         },
         gizmos::{configure_custom_gizmos, PmetraMeshOutlineGizmos, PmetraSliderOutlineGizmos},
         wire_frame::control_wire_frame_display,
     },
 };
+#[cfg(feature = "serde")]
+use super::{
+    document::CadDocumentRegistry, events::cad::LoadCadDocument,
+    systems::cad::document::handle_load_cad_document_events,
+};
+#[cfg(feature = "gltf")]
+use super::{
+    events::cad::{CadModelExported, ExportCadModel},
+    systems::cad::gltf_export::handle_export_cad_model_events,
+};
 
 /// Base [`Plugin`] for *Interactive/Parametric/CAD* modelling.
 ///
@@ -91,6 +107,13 @@ impl Plugin for PmetraBasePlugin {
         app // app
             // resources...
             .init_resource::<PmetraGlobalSettings>()
+            // face picking...
+            .add_message::<CadFaceClicked>()
+            .add_message::<CadFaceHovered>()
+            // undo/redo...
+            .add_message::<UndoCadModel>()
+            .add_message::<RedoCadModel>()
+            .add_systems(Update, send_undo_redo_events_on_keys.run_if(undo_redo_keys))
             // gizmos...
             .init_gizmo_group::<PmetraMeshOutlineGizmos>()
             .init_gizmo_group::<PmetraSliderOutlineGizmos>()
@@ -103,6 +126,7 @@ impl Plugin for PmetraBasePlugin {
                 (
                     render_mesh_outlines.run_if(show_selected_mesh_outlines),
                     deselect_all_root_if_clicked_outside,
+                    update_cad_mesh_lods,
                     // show_mesh_local_debug_axis.run_if(show_selected_mesh_local_debug_axis),
                 ),
             )
@@ -129,6 +153,20 @@ impl Plugin for PmetraBasePlugin {
                 control_wire_frame_display.run_if(move || allow_wire_frames),
             )
             .add_systems(Startup, || info!("PmetraBasePlugin started!"));
+
+        // documents...
+        #[cfg(feature = "serde")]
+        app // app
+            .init_resource::<CadDocumentRegistry>()
+            .add_message::<LoadCadDocument>()
+            .add_systems(Update, handle_load_cad_document_events);
+
+        // glTF export...
+        #[cfg(feature = "gltf")]
+        app // app
+            .add_message::<ExportCadModel>()
+            .add_message::<CadModelExported>()
+            .add_systems(Update, handle_export_cad_model_events);
     }
 }
 
@@ -151,9 +189,11 @@ impl<Params: PmetraModelling + Component + Clone> Plugin for PmetraModellingPlug
         app // App
             .add_message::<GenerateCadModel<Params>>()
             .add_message::<SpawnMeshesBuilder<Params>>()
+            .init_resource::<ShellsBuilderQueue<Params>>()
             .init_resource::<MeshesBuilderQueue<Params>>()
             .init_resource::<MeshesBuilderQueueInspector>()
             .init_resource::<MeshesBuilderFinishedResultsMap<Params>>()
+            .init_resource::<MeshesBuilderCache<Params>>()
             // Generate Model systems...
             .add_systems(
                 Update,
@@ -161,6 +201,7 @@ impl<Params: PmetraModelling + Component + Clone> Plugin for PmetraModellingPlug
                     // Model...
                     spawn_shells_by_name_on_generate::<Params>,
                     update_shells_by_name_on_params_change::<Params>,
+                    handle_spawn_shells_builder_events::<Params>,
                     shells_to_mesh_builder_events::<Params>,
                     handle_spawn_meshes_builder_events::<Params>,
                     mesh_builder_to_bundle::<Params>,
@@ -168,6 +209,12 @@ impl<Params: PmetraModelling + Component + Clone> Plugin for PmetraModellingPlug
                     // chain seems to make the model update run more stable/smooth (less jittery).
                     .chain(),
             )
+            // Undo/redo params edits...
+            .add_systems(
+                Update,
+                handle_undo_redo_events::<Params>
+                    .before(update_shells_by_name_on_params_change::<Params>),
+            )
             // rest...
             .add_systems(Startup, || info!("PmetraModellingPlugin started!"));
     }
@@ -197,11 +244,44 @@ impl<Params: PmetraInteractions + Component<Mutability = Mutable> + Clone> Plugi
                 (
                     shells_to_sliders::<Params>
                         // Need the below for smooth/less jittery behavior...
-                        .after(update_shells_by_name_on_params_change::<Params>)
+                        .after(handle_spawn_shells_builder_events::<Params>)
                         .before(shells_to_mesh_builder_events::<Params>),
                     update_params_from_sliders::<Params>,
+                    update_slider_color_from_issues.after(update_params_from_sliders::<Params>),
+                    // Faces...
+                    handle_face_clicks::<Params>,
+                    handle_face_hovers::<Params>,
                 ),
             )
             .add_systems(Startup, || info!("PmetraInteractionsPlugin started!"));
     }
 }
+
+/// Document [`Plugin`] for Pmetra.
+///
+/// This [`Plugin`] registers the [`Params`] type in the [`CadDocumentRegistry`], allowing its models
+/// to be saved/loaded via [`CadDocument`](super::document::CadDocument).
+///
+/// Prerequisites:
+/// - Enable feature: `serde`.
+/// - Add plugin: [`PmetraModellingPlugin<Params>`].
+#[cfg(feature = "serde")]
+#[derive(Default)]
+pub struct PmetraDocumentPlugin<Params: PmetraModelling + Component> {
+    /// Owns the params type to prevent compiler complains.
+    _params_type: PhantomData<Params>,
+}
+
+#[cfg(feature = "serde")]
+impl<
+        Params: PmetraModelling + Component + TypePath + serde::Serialize + serde::de::DeserializeOwned,
+    > Plugin for PmetraDocumentPlugin<Params>
+{
+    fn build(&self, app: &mut App) {
+        app.world_mut()
+            .get_resource_or_init::<CadDocumentRegistry>()
+            .register::<Params>();
+        app // App
+            .add_systems(Startup, || info!("PmetraDocumentPlugin started!"));
+    }
+}
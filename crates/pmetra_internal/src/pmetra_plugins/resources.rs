@@ -1,10 +1,25 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, marker::PhantomData};
 
 use bevy::{platform::collections::HashMap, prelude::*};
 
-use crate::pmetra_core::builders::{CadShellName, PmetraModelling};
+use crate::{
+    bevy_mesh::CadNormalMode,
+    constants::FAST_TRIANGULATION_TOL_1,
+    pmetra_core::builders::{
+        CadElementTag, CadMeshFaces, CadMeshOutlines, CadMeshesBuildResult, CadShellName,
+        PmetraModelling,
+    },
+};
 
-use super::events::cad::SpawnMeshesBuilder;
+use super::events::cad::{SpawnMeshesBuilder, SpawnShellsBuilder};
+
+/// Queue of [`SpawnShellsBuilder`]s to be built off the main thread.
+///
+/// Holds at most one pending builder per root, as only the latest one is needed.
+#[derive(Default, Clone, Resource, Deref, DerefMut)]
+pub struct ShellsBuilderQueue<Params: PmetraModelling + Component + Clone>(
+    pub VecDeque<SpawnShellsBuilder<Params>>,
+);
 
 #[derive(Debug, Default, Clone, Resource, Reflect, Deref, DerefMut)]
 pub struct MeshesBuilderQueue<Params: PmetraModelling + Component + Clone>(
@@ -13,14 +28,122 @@ pub struct MeshesBuilderQueue<Params: PmetraModelling + Component + Clone>(
 
 #[derive(Debug, Default, Clone, Resource, Reflect, Deref, DerefMut)]
 pub struct MeshesBuilderQueueInspector {
+    #[deref]
     pub meshes_builder_queue_size: usize,
+    pub shells_builder_queue_size: usize,
+    /// Number of meshes builds served from [`MeshesBuilderCache`].
+    pub cache_hits: usize,
+    /// Number of meshes builds not found in [`MeshesBuilderCache`].
+    pub cache_misses: usize,
+}
+
+/// Finished meshes build result with the mesh handles.
+#[derive(Debug, Clone)]
+pub struct MeshesBuilderFinishedResult<Params: PmetraModelling + Component + Clone> {
+    pub mesh_hdl: Handle<Mesh>,
+    pub faces: CadMeshFaces,
+    /// Handles of the meshes of the faces with face materials.
+    pub face_mesh_hdls: HashMap<CadElementTag, Handle<Mesh>>,
+    pub outlines: CadMeshOutlines,
+    /// Meshes of the levels of detail as `(tolerance, mesh, faces)`, sorted by tolerance.
+    pub lod_meshes: Vec<(f64, Handle<Mesh>, CadMeshFaces)>,
+    pub spawn_meshes_builder: SpawnMeshesBuilder<Params>,
 }
 
+/// Latest applied meshes build result per root/shell.
 #[derive(Debug, Default, Clone, Resource, Reflect, Deref, DerefMut)]
 pub struct MeshesBuilderFinishedResultsMap<Params: PmetraModelling + Component + Clone>(
-    HashMap<(Entity, CadShellName), (Mesh, SpawnMeshesBuilder<Params>)>,
+    HashMap<(Entity, CadShellName), MeshesBuilderFinishedResult<Params>>,
 );
 
+/// Key of a [`MeshesBuilderCache`] entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MeshesBuilderCacheKey {
+    pub shell_name: CadShellName,
+    /// Shell key or params key.
+    pub key: u64,
+    /// Bits of the tessellation tolerance.
+    pub tolerance_bits: u64,
+    /// Bits of the (sorted) level of detail tolerances.
+    pub lod_tolerance_bits: Vec<u64>,
+    /// Mode of computing the normals.
+    pub normal_mode: CadNormalMode,
+    /// If tangents are generated (for normal mapped materials).
+    pub tangents: bool,
+    /// If outlines are built.
+    pub outlines: bool,
+    /// Sorted tags of the faces with face materials (meshed separately).
+    pub face_tags: Vec<CadElementTag>,
+}
+
+/// Bounded LRU cache of tessellated [`Mesh`]es (with [`CadMeshOutlines`]), ie: [`CadMeshesBuildResult`]s.
+///
+/// Allows skipping the meshing tasks for geometry that was already built,
+/// eg: when dragging a slider back and forth.
+///
+/// Capacity is set via [`PmetraGlobalSettings::tessellation_cache_capacity`].
+#[derive(Resource)]
+pub struct MeshesBuilderCache<Params: PmetraModelling + Component + Clone> {
+    entries: HashMap<MeshesBuilderCacheKey, (CadMeshesBuildResult, u64)>,
+    /// Incremented on each access, used to find the least recently used entry.
+    tick: u64,
+    _params_type: PhantomData<Params>,
+}
+
+impl<Params: PmetraModelling + Component + Clone> Default for MeshesBuilderCache<Params> {
+    fn default() -> Self {
+        Self {
+            entries: default(),
+            tick: 0,
+            _params_type: PhantomData,
+        }
+    }
+}
+
+impl<Params: PmetraModelling + Component + Clone> MeshesBuilderCache<Params> {
+    /// Get the cached result for key, marking it as recently used.
+    pub fn get(&mut self, key: &MeshesBuilderCacheKey) -> Option<CadMeshesBuildResult> {
+        self.tick += 1;
+        let (build_result, last_used) = self.entries.get_mut(key)?;
+        *last_used = self.tick;
+        Some(build_result.clone())
+    }
+
+    /// Insert result for key, evicting the least recently used entries over capacity.
+    pub fn insert(
+        &mut self,
+        key: MeshesBuilderCacheKey,
+        build_result: CadMeshesBuildResult,
+        capacity: usize,
+    ) {
+        self.tick += 1;
+        self.entries.insert(key, (build_result, self.tick));
+        while self.entries.len() > capacity {
+            let Some(lru_key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&lru_key);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
 /// Global Settings for Pmetra.
 #[derive(Debug, Clone, Resource, Reflect)]
 pub struct PmetraGlobalSettings {
@@ -40,6 +163,22 @@ pub struct PmetraGlobalSettings {
     pub slider_drag_plane_size: f32,
     /// Show slider drag plane for debugging.
     pub slider_drag_plane_debug: bool,
+    /// Max number of tessellated meshes kept in [`MeshesBuilderCache`] (per params type).
+    ///
+    /// Set to `0` to disable caching.
+    pub tessellation_cache_capacity: usize,
+    /// Max number of params edits kept for undo (per [`CadGeneratedRoot`](crate::prelude::CadGeneratedRoot)).
+    ///
+    /// Set to `0` to disable the history.
+    pub params_history_capacity: usize,
+    /// Undo/redo the params edits of selected models via keys:
+    /// `Ctrl+Z` (undo), `Ctrl+Shift+Z`/`Ctrl+Y` (redo).
+    pub undo_redo_keys: bool,
+    /// Tolerance used for tessellating the changed shells while a slider is dragged, for responsiveness.
+    ///
+    /// Meshes are rebuilt with their full quality tolerance once the drag ends.
+    /// Set to `None` to always tessellate with the full quality tolerance.
+    pub slider_drag_preview_tolerance: Option<f64>,
 }
 
 impl Default for PmetraGlobalSettings {
@@ -51,6 +190,91 @@ impl Default for PmetraGlobalSettings {
             slider_outlines_width: 1.25,
             slider_drag_plane_size: 100.,
             slider_drag_plane_debug: false,
+            tessellation_cache_capacity: 64,
+            params_history_capacity: 64,
+            undo_redo_keys: true,
+            slider_drag_preview_tolerance: Some(FAST_TRIANGULATION_TOL_1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::test_utils::MeshPerShell;
+    #[allow(unused_imports)]
+    use bevy::{asset::RenderAssetUsages, render::render_resource::PrimitiveTopology};
+
+    #[allow(dead_code)]
+    fn cache_key(key: u64) -> MeshesBuilderCacheKey {
+        MeshesBuilderCacheKey {
+            shell_name: CadShellName("s1".into()),
+            key,
+            tolerance_bits: FAST_TRIANGULATION_TOL_1.to_bits(),
+            lod_tolerance_bits: vec![],
+            normal_mode: CadNormalMode::default(),
+            tangents: false,
+            outlines: false,
+            face_tags: vec![],
+        }
+    }
+
+    #[allow(dead_code)]
+    fn build_result() -> CadMeshesBuildResult {
+        CadMeshesBuildResult {
+            mesh: Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            ),
+            faces: CadMeshFaces::default(),
+            face_meshes: default(),
+            outlines: CadMeshOutlines::default(),
+            lod_meshes: vec![],
+        }
+    }
+
+    #[test]
+    pub fn test_meshes_builder_cache_capacity() {
+        let mut cache = MeshesBuilderCache::<MeshPerShell>::default();
+        assert!(cache.is_empty());
+        for key in 0..5 {
+            cache.insert(cache_key(key), build_result(), 3);
+            assert!(cache.len() <= 3);
+        }
+        assert_eq!(cache.len(), 3);
+        // Re-inserting an existing key doesn't grow the cache...
+        cache.insert(cache_key(4), build_result(), 3);
+        assert_eq!(cache.len(), 3);
+        // Zero capacity keeps nothing...
+        cache.insert(cache_key(5), build_result(), 0);
+        assert!(cache.is_empty());
+        assert!(cache.get(&cache_key(5)).is_none());
+    }
+
+    #[test]
+    pub fn test_meshes_builder_cache_eviction_order() {
+        let mut cache = MeshesBuilderCache::<MeshPerShell>::default();
+        for key in 0..3 {
+            cache.insert(cache_key(key), build_result(), 3);
         }
+        // Mark the oldest entry as recently used, so `1` becomes the least recently used...
+        assert!(cache.get(&cache_key(0)).is_some());
+        cache.insert(cache_key(3), build_result(), 3);
+        assert!(cache.get(&cache_key(1)).is_none());
+        assert!(cache.get(&cache_key(0)).is_some());
+        assert!(cache.get(&cache_key(2)).is_some());
+        assert!(cache.get(&cache_key(3)).is_some());
+        // Now `0` is the least recently used (before `2` and `3`)...
+        cache.insert(cache_key(4), build_result(), 3);
+        assert!(cache.get(&cache_key(0)).is_none());
+        assert_eq!(cache.len(), 3);
+        // Keys differing only in meshing are distinct entries...
+        let outlines_key = MeshesBuilderCacheKey {
+            outlines: true,
+            ..cache_key(4)
+        };
+        assert!(cache.get(&outlines_key).is_none());
     }
 }
@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+
+use crate::pmetra_plugins::{
+    cleanup_manager::Cleanup, components::cad::CadGeneratedRoot, events::cad::LoadCadDocument,
+};
+
+/// Load the [`CadDocument`](crate::pmetra_plugins::document::CadDocument)s on [`LoadCadDocument`].
+pub fn handle_load_cad_document_events(
+    mut commands: Commands,
+    mut events: MessageReader<LoadCadDocument>,
+    cad_generated: Query<Entity, (With<CadGeneratedRoot>, Without<Cleanup>)>,
+) {
+    for LoadCadDocument {
+        document,
+        remove_existing_models,
+    } in events.read()
+    {
+        if *remove_existing_models {
+            for root_ent in cad_generated.iter() {
+                // Remove root and its descendants...
+                let Ok(mut ent_commands) = commands.get_entity(root_ent) else {
+                    continue;
+                };
+                // Using try_insert to prevent panic...
+                ent_commands.try_insert(Cleanup::Recursive);
+            }
+        }
+        // Needs world access to fire the events of the registered params types...
+        let document = document.clone();
+        commands.queue(move |world: &mut World| {
+            if let Err(e) = document.generate_in_world(world) {
+                error!("{:#}", e);
+            }
+        });
+    }
+}
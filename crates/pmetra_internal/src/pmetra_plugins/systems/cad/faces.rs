@@ -0,0 +1,154 @@
+use bevy::{
+    ecs::component::Mutable, picking::backend::HitData, platform::collections::HashMap, prelude::*,
+};
+use truck_modeling::Surface;
+use truck_topology::FaceID;
+
+use crate::{
+    pmetra_core::builders::{CadFaceHit, PmetraInteractions},
+    pmetra_plugins::{
+        components::cad::{
+            BelongsToCadGeneratedMesh, BelongsToCadGeneratedRoot, CadGeneratedFaceMesh,
+            CadGeneratedMeshFaces, CadGeneratedRoot,
+        },
+        events::cad::{CadFaceClicked, CadFaceHovered},
+    },
+};
+
+/// Offset (along the hit normal) of the ray cast origin used to find the hit triangle.
+const FACE_HIT_RAY_OFFSET: f32 = 1e-3;
+
+pub fn handle_face_clicks<Params: PmetraInteractions + Component<Mutability = Mutable>>(
+    mut pointer_clicks: MessageReader<Pointer<Click>>,
+    cad_meshes: Query<(&CadGeneratedMeshFaces, &BelongsToCadGeneratedRoot)>,
+    cad_face_meshes: Query<(&CadGeneratedFaceMesh, &BelongsToCadGeneratedMesh)>,
+    mut cad_generated: Query<&mut Params, With<CadGeneratedRoot>>,
+    mut mesh_ray_cast: MeshRayCast,
+    mut face_clicked: MessageWriter<CadFaceClicked>,
+) {
+    for Pointer {
+        entity,
+        event: Click { button, hit, .. },
+        ..
+    } in pointer_clicks.read()
+    {
+        if *button != PointerButton::Primary {
+            continue;
+        }
+        let Some((root_ent, face_hit)) = get_face_hit(
+            *entity,
+            hit,
+            &cad_meshes,
+            &cad_face_meshes,
+            &mut mesh_ray_cast,
+        ) else {
+            continue;
+        };
+        let Ok(mut params) = cad_generated.get_mut(root_ent) else {
+            continue;
+        };
+        // Only trigger change detection (regenerating the model) if the handler changed params...
+        if params.bypass_change_detection().on_face_click(&face_hit) {
+            params.set_changed();
+        }
+        face_clicked.write(CadFaceClicked {
+            root: root_ent,
+            mesh: *entity,
+            hit: face_hit,
+        });
+    }
+}
+
+pub fn handle_face_hovers<Params: PmetraInteractions + Component<Mutability = Mutable>>(
+    mut pointer_moves: MessageReader<Pointer<Move>>,
+    mut pointer_outs: MessageReader<Pointer<Out>>,
+    cad_meshes: Query<(&CadGeneratedMeshFaces, &BelongsToCadGeneratedRoot)>,
+    cad_face_meshes: Query<(&CadGeneratedFaceMesh, &BelongsToCadGeneratedMesh)>,
+    mut cad_generated: Query<&mut Params, With<CadGeneratedRoot>>,
+    mut mesh_ray_cast: MeshRayCast,
+    mut face_hovered: MessageWriter<CadFaceHovered>,
+    mut hovered_faces: Local<HashMap<Entity, FaceID<Surface>>>,
+) {
+    for Pointer { entity, .. } in pointer_outs.read() {
+        // Reset hovered face of the mesh...
+        hovered_faces.remove(entity);
+    }
+    for Pointer {
+        entity,
+        event: Move { hit, .. },
+        ..
+    } in pointer_moves.read()
+    {
+        let Some((root_ent, face_hit)) = get_face_hit(
+            *entity,
+            hit,
+            &cad_meshes,
+            &cad_face_meshes,
+            &mut mesh_ray_cast,
+        ) else {
+            continue;
+        };
+        if hovered_faces.get(entity) == Some(&face_hit.face_id) {
+            // Same face still hovered...
+            continue;
+        }
+        hovered_faces.insert(*entity, face_hit.face_id);
+        let Ok(mut params) = cad_generated.get_mut(root_ent) else {
+            continue;
+        };
+        if params.bypass_change_detection().on_face_hover(&face_hit) {
+            params.set_changed();
+        }
+        face_hovered.write(CadFaceHovered {
+            root: root_ent,
+            mesh: *entity,
+            hit: face_hit,
+        });
+    }
+}
+
+/// Get the [`CadFaceHit`] (with root entity) for the pointer hit on mesh entity.
+fn get_face_hit(
+    entity: Entity,
+    hit: &HitData,
+    cad_meshes: &Query<(&CadGeneratedMeshFaces, &BelongsToCadGeneratedRoot)>,
+    cad_face_meshes: &Query<(&CadGeneratedFaceMesh, &BelongsToCadGeneratedMesh)>,
+    mesh_ray_cast: &mut MeshRayCast,
+) -> Option<(Entity, CadFaceHit)> {
+    let position = hit.position?;
+    let normal = hit.normal?;
+    if let Ok((CadGeneratedFaceMesh(tag), BelongsToCadGeneratedMesh(mesh_ent))) =
+        cad_face_meshes.get(entity)
+    {
+        // Face meshes only hold the single tagged face...
+        let (CadGeneratedMeshFaces(faces), BelongsToCadGeneratedRoot(root_ent)) =
+            cad_meshes.get(*mesh_ent).ok()?;
+        let face_hit = CadFaceHit {
+            face_id: faces.face_id_by_tag(tag)?,
+            tag: Some(tag.clone()),
+            position,
+            normal,
+        };
+        return Some((*root_ent, face_hit));
+    }
+    let (CadGeneratedMeshFaces(faces), BelongsToCadGeneratedRoot(root_ent)) =
+        cad_meshes.get(entity).ok()?;
+    // Pointer hits don't include the triangle, so cast a short ray onto the mesh at the hit point...
+    let ray = Ray3d::new(
+        position + normal * FACE_HIT_RAY_OFFSET,
+        Dir3::new(-normal).ok()?,
+    );
+    let filter = |ent: Entity| ent == entity;
+    let settings = MeshRayCastSettings::default()
+        .with_filter(&filter)
+        .with_visibility(RayCastVisibility::Any);
+    let (_, ray_hit) = mesh_ray_cast.cast_ray(ray, &settings).first()?;
+    let face_id = faces.face_at_triangle(ray_hit.triangle_index?)?;
+    let face_hit = CadFaceHit {
+        face_id,
+        tag: faces.tag(face_id).cloned(),
+        position,
+        normal,
+    };
+    Some((*root_ent, face_hit))
+}
@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Result};
+use bevy::prelude::*;
+
+use crate::pmetra_plugins::{
+    cleanup_manager::Cleanup,
+    components::cad::{
+        BelongsToCadGeneratedMesh, BelongsToCadGeneratedRoot, CadGeneratedFaceMesh,
+        CadGeneratedMesh, CadGeneratedMeshLods, CadGeneratedRoot,
+    },
+    document::CadDocumentRegistry,
+    events::cad::{CadModelExported, ExportCadModel},
+    gltf_export::CadGltfBuilder,
+};
+
+/// Export the [`CadGeneratedRoot`]s to binary glTF on [`ExportCadModel`].
+///
+/// Params of the root (if registered in the [`CadDocumentRegistry`]) are written in the `extras` of its node.
+pub fn handle_export_cad_model_events(
+    mut events: MessageReader<ExportCadModel>,
+    mut exported_events: MessageWriter<CadModelExported>,
+    registry: Res<CadDocumentRegistry>,
+    cad_generated: Query<EntityRef, (With<CadGeneratedRoot>, Without<Cleanup>)>,
+    cad_meshes: Query<
+        (
+            Entity,
+            &Name,
+            &Transform,
+            &Mesh3d,
+            Option<&CadGeneratedMeshLods>,
+            &MeshMaterial3d<StandardMaterial>,
+            &BelongsToCadGeneratedRoot,
+        ),
+        (With<CadGeneratedMesh>, Without<Cleanup>),
+    >,
+    cad_face_meshes: Query<
+        (
+            &Name,
+            &Mesh3d,
+            &MeshMaterial3d<StandardMaterial>,
+            &BelongsToCadGeneratedMesh,
+        ),
+        (With<CadGeneratedFaceMesh>, Without<Cleanup>),
+    >,
+    meshes: Res<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+) {
+    for ExportCadModel { root, path } in events.read() {
+        let result = (|| -> Result<Vec<u8>> {
+            let root_ent_ref = cad_generated
+                .get(*root)
+                .map_err(|_| anyhow!("Could not find CadGeneratedRoot: {:?}", root))?;
+            let mut builder = CadGltfBuilder::default();
+            let root_node = builder.add_node(
+                root_ent_ref
+                    .get::<Name>()
+                    .map_or("CadGeneratedRoot", |name| name.as_str()),
+                root_ent_ref.get::<Transform>().copied().unwrap_or_default(),
+                None,
+                None,
+            )?;
+            // Params to re-import as a parametric model...
+            match registry.root_entry(root_ent_ref) {
+                Some(entry) => builder.set_node_document_entry(root_node, entry?)?,
+                None => warn!(
+                    "Params type of {:?} is not registered, exporting without params!",
+                    root
+                ),
+            }
+            // Sort meshes for a stable output...
+            let mut root_meshes = cad_meshes
+                .iter()
+                .filter(|(.., BelongsToCadGeneratedRoot(root_ent))| root_ent == root)
+                .collect::<Vec<_>>();
+            root_meshes.sort_by_key(|(entity, ..)| *entity);
+            for (
+                entity,
+                name,
+                transform,
+                Mesh3d(mesh_hdl),
+                lods,
+                MeshMaterial3d(material_hdl),
+                _,
+            ) in root_meshes
+            {
+                // Export the full detail mesh (not the current level of detail)...
+                let mesh_hdl = lods.map_or(mesh_hdl, |lods| &lods.base.0);
+                let (Some(mesh), Some(material)) =
+                    (meshes.get(mesh_hdl), materials.get(material_hdl))
+                else {
+                    return Err(anyhow!("Mesh/material not loaded for: {}", name));
+                };
+                let gltf_mesh =
+                    builder.add_mesh(mesh_hdl.id(), mesh, material_hdl.id(), material)?;
+                let mesh_node = builder.add_node(
+                    name.as_str(),
+                    *transform,
+                    Some(root_node),
+                    Some(gltf_mesh),
+                )?;
+                // Face meshes (with their own materials) are children of the mesh...
+                for (face_name, Mesh3d(face_mesh_hdl), MeshMaterial3d(face_material_hdl), _) in
+                    cad_face_meshes
+                        .iter()
+                        .filter(|(.., BelongsToCadGeneratedMesh(mesh_ent))| *mesh_ent == entity)
+                {
+                    let (Some(face_mesh), Some(face_material)) =
+                        (meshes.get(face_mesh_hdl), materials.get(face_material_hdl))
+                    else {
+                        return Err(anyhow!("Mesh/material not loaded for: {}", face_name));
+                    };
+                    let gltf_face_mesh = builder.add_mesh(
+                        face_mesh_hdl.id(),
+                        face_mesh,
+                        face_material_hdl.id(),
+                        face_material,
+                    )?;
+                    builder.add_node(
+                        face_name.as_str(),
+                        Transform::default(),
+                        Some(mesh_node),
+                        Some(gltf_face_mesh),
+                    )?;
+                }
+            }
+            builder.build_glb()
+        })();
+        let glb = match result {
+            Ok(glb) => glb,
+            Err(e) => {
+                error!("Could not export cad model: {:#}", e);
+                continue;
+            }
+        };
+        if let Some(path) = path.clone() {
+            write_glb(path, glb.clone());
+        }
+        exported_events.write(CadModelExported { root: *root, glb });
+    }
+}
+
+/// Write the glb to the file in a task, to avoid blocking on the file system.
+#[cfg(not(target_arch = "wasm32"))]
+fn write_glb(path: std::path::PathBuf, glb: Vec<u8>) {
+    bevy::tasks::IoTaskPool::get()
+        .spawn(async move {
+            match std::fs::write(&path, glb) {
+                Ok(_) => info!("Exported glTF: {:?}", path),
+                Err(e) => error!("Could not write glTF {:?}: {:#}", path, e),
+            }
+        })
+        .detach();
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_glb(path: std::path::PathBuf, _glb: Vec<u8>) {
+    error!("Could not write glTF {:?}: no file system in wasm!", path);
+}
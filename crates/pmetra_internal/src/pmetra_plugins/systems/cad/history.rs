@@ -0,0 +1,192 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    pmetra_core::builders::PmetraModelling,
+    pmetra_plugins::{
+        cleanup_manager::Cleanup,
+        components::cad::{CadGeneratedRoot, CadGeneratedRootSelectionState, CadParamsHistory},
+        events::cad::{RedoCadModel, UndoCadModel},
+    },
+};
+
+/// Restore the params snapshots from [`CadParamsHistory`] on [`UndoCadModel`]/[`RedoCadModel`].
+///
+/// Params are re-inserted, which triggers the model update (as for any params change).
+pub fn handle_undo_redo_events<Params: PmetraModelling + Component + Clone>(
+    mut commands: Commands,
+    mut undo_events: MessageReader<UndoCadModel>,
+    mut redo_events: MessageReader<RedoCadModel>,
+    mut generated_roots: Query<
+        (
+            Entity,
+            &Params,
+            &CadGeneratedRootSelectionState,
+            &mut CadParamsHistory<Params>,
+        ),
+        (With<CadGeneratedRoot>, Without<Cleanup>),
+    >,
+) {
+    let events = undo_events
+        .read()
+        .map(|UndoCadModel { root }| (*root, true))
+        .chain(
+            redo_events
+                .read()
+                .map(|RedoCadModel { root }| (*root, false)),
+        )
+        .collect::<Vec<_>>();
+    if events.is_empty() {
+        return;
+    }
+    // Params restored this frame (inserted via commands, so not yet visible on the query)...
+    let mut restored_params: HashMap<Entity, Params> = HashMap::new();
+    for (root, is_undo) in events {
+        for (root_ent, params, selection_state, mut history) in generated_roots.iter_mut() {
+            let is_target = match root {
+                Some(root) => root == root_ent,
+                None => matches!(selection_state, CadGeneratedRootSelectionState::Selected),
+            };
+            if !is_target {
+                continue;
+            }
+            let current = restored_params
+                .get(&root_ent)
+                .cloned()
+                .unwrap_or_else(|| params.clone());
+            let restored = if is_undo {
+                history.undo(current)
+            } else {
+                history.redo(current)
+            };
+            let Some(restored) = restored else {
+                continue;
+            };
+            // Drop the snapshot of any drag in progress, as it is now stale...
+            history.drag_start = None;
+            restored_params.insert(root_ent, restored);
+        }
+    }
+    for (root_ent, params) in restored_params {
+        let Ok(mut ent_commands) = commands.get_entity(root_ent) else {
+            continue;
+        };
+        ent_commands.try_insert(params);
+    }
+}
+
+/// Send [`UndoCadModel`]/[`RedoCadModel`] (for the selected roots) on the keys.
+///
+/// Refer [`PmetraGlobalSettings::undo_redo_keys`](crate::pmetra_plugins::resources::PmetraGlobalSettings::undo_redo_keys).
+pub fn send_undo_redo_events_on_keys(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut undo_events: MessageWriter<UndoCadModel>,
+    mut redo_events: MessageWriter<RedoCadModel>,
+) {
+    // Keyboard input is not available in headless apps...
+    let Some(keys) = keys else {
+        return;
+    };
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let is_shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keys.just_pressed(KeyCode::KeyZ) && !is_shift {
+        undo_events.write(UndoCadModel::default());
+    } else if keys.just_pressed(KeyCode::KeyY) || (keys.just_pressed(KeyCode::KeyZ) && is_shift) {
+        redo_events.write(RedoCadModel::default());
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::{
+        pmetra_plugins::{
+            components::cad::CadGeneratedSliderState, resources::PmetraGlobalSettings,
+            systems::cad::slider::update_params_from_sliders,
+        },
+        test_utils::{drag, spawn_plank_slider, Plank},
+    };
+
+    /// Start/end a drag of the slider (as the slider drag observers do).
+    fn set_dragging(app: &mut App, root: Entity, slider: Entity, is_dragging: bool) {
+        let params = app.world().get::<Plank>(root).unwrap().clone();
+        let mut history = app
+            .world_mut()
+            .get_mut::<CadParamsHistory<Plank>>(root)
+            .unwrap();
+        history.drag_start = is_dragging.then_some(params);
+        app.world_mut().entity_mut(slider).insert(if is_dragging {
+            CadGeneratedSliderState::Dragging
+        } else {
+            CadGeneratedSliderState::Normal
+        });
+    }
+
+    #[test]
+    pub fn test_handle_undo_redo_events() {
+        let mut app = App::new();
+        app.init_resource::<PmetraGlobalSettings>()
+            .add_message::<UndoCadModel>()
+            .add_message::<RedoCadModel>()
+            .add_systems(
+                Update,
+                (
+                    update_params_from_sliders::<Plank>,
+                    handle_undo_redo_events::<Plank>,
+                ),
+            );
+        let root = app
+            .world_mut()
+            .spawn((
+                CadGeneratedRoot,
+                CadGeneratedRootSelectionState::Selected,
+                Plank { width: 1. },
+                CadParamsHistory::<Plank>::default(),
+            ))
+            .id();
+        let slider = spawn_plank_slider(&mut app, root, CadGeneratedSliderState::Normal);
+        let width = |app: &App| app.world().get::<Plank>(root).unwrap().width;
+        let stacks_len = |app: &App| {
+            let history = app.world().get::<CadParamsHistory<Plank>>(root).unwrap();
+            (history.undo_stack.len(), history.redo_stack.len())
+        };
+
+        // A whole drag (of several edits) records a single entry...
+        set_dragging(&mut app, root, slider, true);
+        drag(&mut app, slider, 0., 0.25);
+        drag(&mut app, slider, 0.25, 0.5);
+        drag(&mut app, slider, 0.5, 0.75);
+        set_dragging(&mut app, root, slider, false);
+        assert!((width(&app) - 1.75).abs() < 1e-6);
+        assert_eq!(stacks_len(&app), (1, 0));
+
+        // Undo (of the root) restores the params before the drag...
+        app.world_mut()
+            .write_message(UndoCadModel { root: Some(root) });
+        app.update();
+        assert!((width(&app) - 1.).abs() < 1e-6);
+        assert_eq!(stacks_len(&app), (0, 1));
+
+        // Redo (of the selected roots) restores the params after the drag...
+        app.world_mut().write_message(RedoCadModel::default());
+        app.update();
+        assert!((width(&app) - 1.75).abs() < 1e-6);
+        assert_eq!(stacks_len(&app), (1, 0));
+
+        // A new edit after undo clears the redo stack...
+        app.world_mut().write_message(UndoCadModel::default());
+        app.update();
+        assert_eq!(stacks_len(&app), (0, 1));
+        set_dragging(&mut app, root, slider, true);
+        drag(&mut app, slider, 0., 0.25);
+        set_dragging(&mut app, root, slider, false);
+        assert!((width(&app) - 1.25).abs() < 1e-6);
+        assert_eq!(stacks_len(&app), (1, 0));
+        app.world_mut().write_message(RedoCadModel::default());
+        app.update();
+        assert!((width(&app) - 1.25).abs() < 1e-6);
+    }
+}
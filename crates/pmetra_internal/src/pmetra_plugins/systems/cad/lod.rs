@@ -0,0 +1,59 @@
+use bevy::{camera::primitives::Aabb, prelude::*};
+
+use crate::pmetra_plugins::{
+    cleanup_manager::Cleanup,
+    components::{
+        cad::{CadGeneratedMesh, CadGeneratedMeshFaces, CadGeneratedMeshLods},
+        camera::CadCamera,
+    },
+};
+
+/// Swap the [`Mesh3d`] of the [`CadGeneratedMesh`]es with [`CadGeneratedMeshLods`]
+/// by their distance to the (closest) [`CadCamera`].
+pub fn update_cad_mesh_lods(
+    mut commands: Commands,
+    cameras: Query<&GlobalTransform, With<CadCamera>>,
+    cad_meshes: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            Option<&Aabb>,
+            &Mesh3d,
+            &CadGeneratedMeshLods,
+        ),
+        (With<CadGeneratedMesh>, Without<Cleanup>),
+    >,
+) {
+    let camera_translations = cameras
+        .iter()
+        .map(|transform| transform.translation())
+        .collect::<Vec<_>>();
+    if camera_translations.is_empty() {
+        return;
+    }
+    for (entity, transform, aabb, Mesh3d(mesh_hdl), lods) in cad_meshes.iter() {
+        // Measure from the center of the mesh (if its bounds are computed)...
+        let center = aabb.map_or(transform.translation(), |aabb| {
+            transform.transform_point(aabb.center.into())
+        });
+        let Some(distance) = camera_translations
+            .iter()
+            .map(|translation| translation.distance(center))
+            .reduce(f32::min)
+        else {
+            continue;
+        };
+        let (lod_mesh_hdl, lod_faces) = lods.at_distance(distance);
+        if lod_mesh_hdl == mesh_hdl {
+            continue;
+        }
+        commands
+            .entity(entity)
+            .insert((
+                Mesh3d(lod_mesh_hdl.clone()),
+                CadGeneratedMeshFaces(lod_faces.clone()),
+            ))
+            // Remove AABB for Bevy to recompute...
+            .remove::<Aabb>();
+    }
+}
@@ -1,3 +1,10 @@
+#[cfg(feature = "serde")]
+pub mod document;
+pub mod faces;
+#[cfg(feature = "gltf")]
+pub mod gltf_export;
+pub mod history;
+pub mod lod;
 pub mod mesh;
 pub mod model;
 pub mod outlines;
@@ -1,33 +1,39 @@
 use std::task::Poll;
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use bevy::{
     camera::primitives::Aabb,
     color::palettes::css,
     light::NotShadowCaster,
+    platform::collections::{HashMap, HashSet},
     prelude::*,
 };
 use bevy_async_task::TaskPool;
 
 use crate::{
     pmetra_core::builders::{
-        CadMesh, CadMeshBuilder, CadMeshName, CadShellName, CadShellsByName, CadSlider,
+        CadMesh, CadMeshBuilder, CadMeshFaces, CadMeshLodBuildResult, CadMeshName, CadMeshOutlines,
+        CadMeshesBuildResult, CadMeshesBuilder, CadShellName, CadShellsByName, CadSlider,
         CadSliderName, PmetraInteractions, PmetraModelling,
     },
     pmetra_plugins::{
         cleanup_manager::Cleanup,
         components::{
             cad::{
-                BelongsToCadGeneratedRoot, CadGeneratedMesh, CadGeneratedMeshOutlines,
-                CadGeneratedRoot, CadGeneratedRootSelectionState, CadGeneratedSlider,
-                CadGeneratedSliderConfig, CadGeneratedSliderPreviousTransform,
-                CadGeneratedSliderState,
+                BelongsToCadGeneratedMesh, BelongsToCadGeneratedRoot, CadGeneratedFaceMesh,
+                CadGeneratedMesh, CadGeneratedMeshFaces, CadGeneratedMeshLods,
+                CadGeneratedMeshOutlines, CadGeneratedRoot, CadGeneratedRootPreviewMeshes,
+                CadGeneratedRootSelectionState, CadGeneratedSlider, CadGeneratedSliderConfig,
+                CadGeneratedSliderIssues, CadGeneratedSliderPreviousTransform,
+                CadGeneratedSliderState, CadParamsHistory, CadShellsBuiltWith,
             },
             wire_frame::WireFrameDisplaySettings,
         },
-        events::cad::{GenerateCadModel, SpawnMeshesBuilder},
+        events::cad::{GenerateCadModel, SpawnMeshesBuilder, SpawnShellsBuilder},
         resources::{
+            MeshesBuilderCache, MeshesBuilderCacheKey, MeshesBuilderFinishedResult,
             MeshesBuilderFinishedResultsMap, MeshesBuilderQueue, MeshesBuilderQueueInspector,
+            PmetraGlobalSettings, ShellsBuilderQueue,
         },
     },
 };
@@ -63,78 +69,121 @@ pub fn spawn_shells_by_name_on_generate<Params: PmetraModelling + Component + Cl
         }
 
         // Spawn root...
-        let root_ent = commands
+        // Shells are built async (on params added/changed) and spawned once built.
+        commands
             .spawn((
                 *transform,
                 Visibility::default(),
                 CadGeneratedRoot,
                 CadGeneratedRootSelectionState::default(),
                 params.clone(),
+                CadParamsHistory::<Params>::default(),
             ))
             // picking observers...
             .observe(root_pointer_move)
             .observe(root_pointer_out)
-            .observe(root_on_click)
-            .id();
+            .observe(root_on_click);
+    }
+}
 
+pub fn update_shells_by_name_on_params_change<Params: PmetraModelling + Component + Clone>(
+    cad_generated: Query<
+        (Entity, &Params),
+        (Changed<Params>, With<CadGeneratedRoot>, Without<Cleanup>),
+    >,
+    shells_by_name_entities: Query<
+        (
+            &BelongsToCadGeneratedRoot,
+            &CadShellsByName,
+            &CadShellsBuiltWith<Params>,
+        ),
+        Without<Cleanup>,
+    >,
+    mut builder_queue: ResMut<ShellsBuilderQueue<Params>>,
+    mut builder_creation_index: Local<usize>,
+) {
+    for (root_ent, params) in cad_generated.iter() {
+        // Keep the last model for invalid params (shell builders expect valid ones)...
+        if let Err(issues) = params.validate() {
+            warn!("Skipping shells build for invalid params: {:?}", issues);
+            continue;
+        }
         // Get the shell builders from params...
         let shells_builders = match params.shells_builders() {
             Ok(result) => result,
             Err(e) => {
                 error!("shells_builders failed with error: {:?}", e);
-                return;
+                continue;
             }
         };
-
-        let mut shells_by_name = CadShellsByName::default();
-        // Build Shells from Builders and add to common resource for later use.
-        for (shell_name, shell_builder) in shells_builders.builders.iter() {
-            let cad_shell = match shell_builder.build_cad_shell() {
-                Ok(shell) => shell,
-                Err(e) => {
-                    error!(
-                        "build_cad_shell for shell_name: {:?} failed, error: {:?}",
-                        shell_name, e
-                    );
+        // Get keys of the shells that have them...
+        let shell_keys = shells_builders
+            .builders
+            .iter()
+            .filter_map(|(shell_name, shell_builder)| {
+                Some((shell_name.clone(), shell_builder.key()?))
+            })
+            .collect::<HashMap<_, _>>();
+        // Reuse existing shells with unchanged keys...
+        let mut reused_shells = CadShellsByName::default();
+        if let Some((_, shells_by_name, built_with)) = shells_by_name_entities
+            .iter()
+            .find(|(bel_root, _, _)| bel_root.0 == root_ent)
+        {
+            for (shell_name, shell_key) in shell_keys.iter() {
+                if built_with.shell_keys.get(shell_name) != Some(shell_key) {
                     continue;
                 }
-            };
-            shells_by_name.insert(shell_name.clone(), cad_shell);
+                let Some(cad_shell) = shells_by_name.get(shell_name) else {
+                    continue;
+                };
+                reused_shells.insert(shell_name.clone(), cad_shell.clone());
+            }
         }
-        // Spawn shells by name and add to root...
-        let shells_by_name_ent = commands
-            .spawn((shells_by_name, BelongsToCadGeneratedRoot(root_ent)))
-            .id();
-        commands.entity(root_ent).add_child(shells_by_name_ent);
+        // Only the latest builder per root is needed, drop any pending ones...
+        builder_queue.retain(|builder| builder.belongs_to_root.0 != root_ent);
+        *builder_creation_index += 1;
+        builder_queue.push_back(SpawnShellsBuilder {
+            belongs_to_root: BelongsToCadGeneratedRoot(root_ent),
+            shells_builders,
+            shell_keys,
+            reused_shells,
+            created_at_idx: *builder_creation_index,
+        });
     }
 }
 
-pub fn update_shells_by_name_on_params_change<Params: PmetraModelling + Component + Clone>(
-    cad_generated: Query<
-        (Entity, &Params),
-        (Changed<Params>, With<CadGeneratedRoot>, Without<Cleanup>),
+pub fn handle_spawn_shells_builder_events<Params: PmetraModelling + Component + Clone>(
+    mut commands: Commands,
+    cad_generated: Query<Entity, (With<CadGeneratedRoot>, Without<Cleanup>)>,
+    mut shells_by_name_entities: Query<
+        (
+            &BelongsToCadGeneratedRoot,
+            &mut CadShellsByName,
+            &mut CadShellsBuiltWith<Params>,
+        ),
+        Without<Cleanup>,
     >,
-    mut shells_by_name_entities: Query<(Entity, &BelongsToCadGeneratedRoot, &mut CadShellsByName)>,
+    mut task_pool: TaskPool<(CadShellsByName, SpawnShellsBuilder<Params>)>,
+    mut builder_queue: ResMut<ShellsBuilderQueue<Params>>,
+    mut builder_queue_inspector: ResMut<MeshesBuilderQueueInspector>,
 ) {
-    for (root_ent, params) in cad_generated.iter() {
-        for (_entity, &BelongsToCadGeneratedRoot(cur_root), mut shells_by_name) in
-            shells_by_name_entities.iter_mut()
-        {
-            if cur_root != root_ent {
-                continue;
-            }
-            // Get the shell builders from params...
-            let shells_builders = match params.shells_builders() {
-                Ok(result) => result,
-                Err(e) => {
-                    error!("shells_builders failed with error: {:?}", e);
-                    return;
+    // Update inspector...
+    builder_queue_inspector.shells_builder_queue_size = builder_queue.len();
+
+    // Spawn a set num of tasks per frame from queue...
+    for _ in 0..10 {
+        let Some(spawn_shells_builder) = builder_queue.pop_front() else {
+            break;
+        };
+        task_pool.spawn(async move {
+            let mut shells_by_name = spawn_shells_builder.reused_shells.clone();
+            // Build (non reused) Shells from Builders and add to shells_by_name...
+            for (shell_name, shell_builder) in spawn_shells_builder.shells_builders.builders.iter()
+            {
+                if spawn_shells_builder.reused_shells.contains_key(shell_name) {
+                    continue;
                 }
-            };
-            // Clear existing shells...
-            shells_by_name.clear();
-            // Build Shells from Builders and add to shells_by_name...
-            for (shell_name, shell_builder) in shells_builders.builders.iter() {
                 let cad_shell = match shell_builder.build_cad_shell() {
                     Ok(shell) => shell,
                     Err(e) => {
@@ -147,15 +196,111 @@ pub fn update_shells_by_name_on_params_change<Params: PmetraModelling + Componen
                 };
                 shells_by_name.insert(shell_name.clone(), cad_shell);
             }
+
+            (shells_by_name, spawn_shells_builder)
+        });
+    }
+
+    // Collect finished tasks...
+    let finished_task_results = task_pool
+        .iter_poll()
+        .filter_map(|status| {
+            if let Poll::Ready(result) = status {
+                Some(result)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    // Keep only the latest result per root...
+    let mut latest_results: HashMap<Entity, (CadShellsByName, SpawnShellsBuilder<Params>)> =
+        HashMap::default();
+    for task_result in finished_task_results {
+        let root_ent = task_result.1.belongs_to_root.0;
+        if !cad_generated.contains(root_ent) {
+            // If root is not available, skip...
+            continue;
+        }
+        if let Some(current_result) = latest_results.get(&root_ent) {
+            if current_result.1.created_at_idx > task_result.1.created_at_idx {
+                continue;
+            }
+        }
+        latest_results.insert(root_ent, task_result);
+    }
+
+    for (
+        root_ent,
+        (
+            new_shells_by_name,
+            SpawnShellsBuilder {
+                shells_builders,
+                shell_keys,
+                created_at_idx,
+                ..
+            },
+        ),
+    ) in latest_results
+    {
+        if let Some((_, mut shells_by_name, mut built_with)) = shells_by_name_entities
+            .iter_mut()
+            .find(|(bel_root, _, _)| bel_root.0 == root_ent)
+        {
+            // Ignore results older than the ones already applied.
+            // This will prevent overriding from older tasks and prevent flashing jitter...
+            if built_with.created_at_idx > created_at_idx {
+                continue;
+            }
+            // Shells without keys, with changed keys or newly added are considered changed...
+            let changed_shells = new_shells_by_name
+                .keys()
+                .filter(|shell_name| {
+                    let shell_key = shell_keys.get(*shell_name);
+                    shell_key.is_none()
+                        || built_with.shell_keys.get(*shell_name) != shell_key
+                        || !shells_by_name.contains_key(*shell_name)
+                })
+                .cloned()
+                .collect();
+            *shells_by_name = new_shells_by_name;
+            *built_with = CadShellsBuiltWith {
+                params: shells_builders.params,
+                created_at_idx,
+                shell_keys,
+                changed_shells,
+            };
+        } else {
+            // Spawn shells by name and add to root...
+            let Ok(mut root_ent_commands) = commands.get_entity(root_ent) else {
+                continue;
+            };
+            let changed_shells = new_shells_by_name.keys().cloned().collect();
+            root_ent_commands.with_children(|commands| {
+                commands.spawn((
+                    new_shells_by_name,
+                    CadShellsBuiltWith {
+                        params: shells_builders.params,
+                        created_at_idx,
+                        shell_keys,
+                        changed_shells,
+                    },
+                    BelongsToCadGeneratedRoot(root_ent),
+                ));
+            });
         }
     }
 }
 
 pub fn shells_to_sliders<Params: PmetraInteractions + Component + Clone>(
     mut commands: Commands,
-    cad_generated: Query<&Params, (With<CadGeneratedRoot>, Without<Cleanup>)>,
+    cad_generated: Query<Entity, (With<CadGeneratedRoot>, Without<Cleanup>)>,
     shells_by_name_entities: Query<
-        (Entity, &CadShellsByName, &BelongsToCadGeneratedRoot),
+        (
+            Entity,
+            &CadShellsByName,
+            &CadShellsBuiltWith<Params>,
+            &BelongsToCadGeneratedRoot,
+        ),
         Changed<CadShellsByName>,
     >,
     mut slider_comps: Query<
@@ -172,15 +317,18 @@ pub fn shells_to_sliders<Params: PmetraInteractions + Component + Clone>(
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
 ) {
-    for (_entity, shells_by_name, &BelongsToCadGeneratedRoot(root_ent)) in
-        shells_by_name_entities.iter()
+    for (
+        _entity,
+        shells_by_name,
+        CadShellsBuiltWith { params, .. },
+        &BelongsToCadGeneratedRoot(root_ent),
+    ) in shells_by_name_entities.iter()
     {
-        // Get params from root...
-        let Ok(params) = cad_generated.get(root_ent) else {
-            // warn!("Could not get `CadGeneratedRoot` with associated params!");
+        if !cad_generated.contains(root_ent) {
+            // If root is not available, skip...
             continue;
-        };
-        // Sliders...
+        }
+        // Sliders (using the params the shells were built with)...
         let Ok(sliders) = params.sliders(shells_by_name) else {
             warn!("Could not get sliders!");
             continue;
@@ -246,6 +394,7 @@ pub fn shells_to_sliders<Params: PmetraInteractions + Component + Clone>(
                         },
                         CadGeneratedSliderState::default(),
                         CadGeneratedSliderPreviousTransform(*transform),
+                        CadGeneratedSliderIssues::default(),
                         BelongsToCadGeneratedRoot(root_ent),
                         NotShadowCaster,
                         // picking...
@@ -268,35 +417,161 @@ pub fn shells_to_sliders<Params: PmetraInteractions + Component + Clone>(
 }
 
 pub fn shells_to_mesh_builder_events<Params: PmetraModelling + Component + Clone>(
-    cad_generated: Query<&Params, (With<CadGeneratedRoot>, Without<Cleanup>)>,
-    shells_by_name_entities: Query<
-        (Entity, &CadShellsByName, &BelongsToCadGeneratedRoot),
+    mut commands: Commands,
+    cad_generated: Query<
+        Option<&CadGeneratedRootPreviewMeshes>,
+        (With<CadGeneratedRoot>, Without<Cleanup>),
+    >,
+    preview_roots: Query<
+        Entity,
+        (
+            With<CadGeneratedRoot>,
+            With<CadGeneratedRootPreviewMeshes>,
+            Without<Cleanup>,
+        ),
+    >,
+    changed_shells_by_name_entities: Query<
+        (
+            Entity,
+            &CadShellsByName,
+            &CadShellsBuiltWith<Params>,
+            &BelongsToCadGeneratedRoot,
+        ),
         Changed<CadShellsByName>,
     >,
+    shells_by_name_entities: Query<(
+        Entity,
+        &CadShellsByName,
+        &CadShellsBuiltWith<Params>,
+        &BelongsToCadGeneratedRoot,
+    )>,
+    mesh_builders: Query<
+        (&CadShellName, &BelongsToCadGeneratedRoot),
+        (With<CadMeshBuilder<Params>>, Without<Cleanup>),
+    >,
+    sliders: Query<
+        (&CadGeneratedSliderState, &BelongsToCadGeneratedRoot),
+        With<CadGeneratedSlider>,
+    >,
     mut builder_queue: ResMut<MeshesBuilderQueue<Params>>,
+    global_settings: Res<PmetraGlobalSettings>,
     mut builder_creation_index: Local<usize>,
 ) {
-    for (_entity, shells_by_name, &BelongsToCadGeneratedRoot(root_ent)) in
-        shells_by_name_entities.iter()
+    let is_root_dragging = |root_ent: Entity| {
+        sliders
+            .iter()
+            .any(|(state, BelongsToCadGeneratedRoot(cur_root))| {
+                *cur_root == root_ent && matches!(state, CadGeneratedSliderState::Dragging)
+            })
+    };
+    let mut shells_by_name_to_mesh = changed_shells_by_name_entities.iter().collect::<Vec<_>>();
+    // Roots with preview meshes, whose (unchanged) shells are re-meshed with full quality once the drag ends...
+    let refine_roots = preview_roots
+        .iter()
+        .filter(|root_ent| !is_root_dragging(*root_ent))
+        .collect::<HashSet<_>>();
+    if !refine_roots.is_empty() {
+        shells_by_name_to_mesh.extend(shells_by_name_entities.iter().filter(
+            |(entity, _, _, BelongsToCadGeneratedRoot(root_ent))| {
+                refine_roots.contains(root_ent)
+                    && !changed_shells_by_name_entities.contains(*entity)
+            },
+        ));
+    }
+    for (
+        _entity,
+        shells_by_name,
+        CadShellsBuiltWith {
+            params,
+            shell_keys,
+            changed_shells,
+            ..
+        },
+        &BelongsToCadGeneratedRoot(root_ent),
+    ) in shells_by_name_to_mesh
     {
-        // Get params from root...
-        let Ok(params) = cad_generated.get(root_ent) else {
-            // warn!("Could not get `CadGeneratedRoot` with associated params!");
+        let Ok(preview_meshes) = cad_generated.get(root_ent) else {
+            // If root is not available, skip...
             continue;
         };
+        let is_dragging = is_root_dragging(root_ent);
+        // Rebuild preview meshes with full quality once the drag ends...
+        let refine_preview_meshes = preview_meshes.is_some() && !is_dragging;
+        // Use the params the shells were built with, as root params may be ahead...
         let Ok(meshes_builders_by_shell) = params.meshes_builders_by_shell(shells_by_name) else {
             warn!("Could not get meshes_builders_by_shell!");
             continue;
         };
+        let preview_tolerance = global_settings
+            .slider_drag_preview_tolerance
+            .filter(|_| is_dragging);
+        let params_key = params.params_key();
+        let mut preview_shells = HashSet::new();
         for (shell_name, meshes_builder) in meshes_builders_by_shell.meshes_builders.iter() {
+            let mut meshes_builder = meshes_builder.clone();
+            let shell_changed = changed_shells.contains(shell_name);
+            // Unchanged shells with preview meshes keep the preview tolerance, so their meshes are reused
+            // instead of being re-tessellated with the full quality tolerance mid-drag...
+            let is_preview = shell_changed
+                || preview_meshes.is_some_and(|CadGeneratedRootPreviewMeshes(shell_names)| {
+                    shell_names.contains(shell_name)
+                });
+            if let (Some(preview_tolerance), true) = (preview_tolerance, is_preview) {
+                // Coarse tessellation (without levels of detail) while dragging...
+                meshes_builder.tolerance = Some(preview_tolerance.max(meshes_builder.tolerance()));
+                for mesh_builder in meshes_builder.mesh_builders.values_mut() {
+                    mesh_builder.lods.clear();
+                }
+                preview_shells.insert(shell_name.clone());
+            }
             *builder_creation_index += 1;
             builder_queue.push_back(SpawnMeshesBuilder {
                 shell_name: shell_name.clone(),
-                meshes_builder: meshes_builder.clone(),
+                meshes_builder,
+                belongs_to_root: BelongsToCadGeneratedRoot(root_ent),
+                // Unchanged shells still need their mesh builders updated (eg: transforms),
+                // but can skip tessellation (preview meshes are re-tessellated as their tolerance differs)...
+                shell_changed,
+                cache_key: shell_keys.get(shell_name).copied().or(params_key),
+                created_at_idx: *builder_creation_index,
+            });
+        }
+        // Shells with existing meshes but no meshes builder anymore (eg: shell removed)...
+        let stale_shell_names = mesh_builders
+            .iter()
+            .filter(|(shell_name, bel_root)| {
+                bel_root.0 == root_ent
+                    && !meshes_builders_by_shell
+                        .meshes_builders
+                        .contains_key(*shell_name)
+            })
+            .map(|(shell_name, _)| shell_name.clone())
+            .collect::<HashSet<_>>();
+        for shell_name in stale_shell_names {
+            // Empty meshes builder, cleans up all meshes of the shell...
+            *builder_creation_index += 1;
+            builder_queue.push_back(SpawnMeshesBuilder {
+                shell_name: shell_name.clone(),
+                meshes_builder: CadMeshesBuilder {
+                    params: params.clone(),
+                    shell_name,
+                    ..default()
+                },
                 belongs_to_root: BelongsToCadGeneratedRoot(root_ent),
+                shell_changed: false,
+                cache_key: None,
                 created_at_idx: *builder_creation_index,
             });
         }
+        if !preview_shells.is_empty() {
+            commands
+                .entity(root_ent)
+                .insert(CadGeneratedRootPreviewMeshes(preview_shells));
+        } else if refine_preview_meshes {
+            commands
+                .entity(root_ent)
+                .remove::<CadGeneratedRootPreviewMeshes>();
+        }
     }
 }
 
@@ -314,91 +589,164 @@ pub fn handle_spawn_meshes_builder_events<Params: PmetraModelling + Component +
         Without<Cleanup>,
     >,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut task_pool: TaskPool<Result<(Mesh, SpawnMeshesBuilder<Params>)>>,
+    mut task_pool: TaskPool<(Result<CadMeshesBuildResult>, SpawnMeshesBuilder<Params>)>,
     mut builder_queue: ResMut<MeshesBuilderQueue<Params>>,
     mut builder_queue_inspector: ResMut<MeshesBuilderQueueInspector>,
     mut meshes_builder_task_results_map: ResMut<MeshesBuilderFinishedResultsMap<Params>>,
+    mut meshes_builder_cache: ResMut<MeshesBuilderCache<Params>>,
+    global_settings: Res<PmetraGlobalSettings>,
+    mut pending_tasks_count: Local<HashMap<(Entity, CadShellName), usize>>,
 ) {
     // Update inspector...
     builder_queue_inspector.meshes_builder_queue_size = builder_queue.len();
 
+    // Results (with mesh handles) finished this frame...
+    let mut finished_results = vec![];
+
     // Spawn a set num of tasks per frame from queue...
     for _ in 0..10 {
         let Some(spawn_meshes_builder) = builder_queue.pop_front() else {
             break;
         };
-        let spawn_meshes_builder = spawn_meshes_builder.clone();
-        task_pool.spawn(async move {
-            let SpawnMeshesBuilder {
-                shell_name,
-                meshes_builder,
-                ..
-            } = spawn_meshes_builder.clone();
-            let Ok(bevy_mesh) = meshes_builder.build_bevy_mesh() else {
-                warn!("Could not build bevy_mesh for shell_name: {:?}", shell_name);
-                return Err(anyhow!(
-                    "Could not build bevy_mesh for shell_name: {:?}",
-                    shell_name
+        let key = (
+            spawn_meshes_builder.belongs_to_root.0,
+            spawn_meshes_builder.shell_name.clone(),
+        );
+        if spawn_meshes_builder.meshes_builder.mesh_builders.is_empty() {
+            // Nothing to mesh, just cleanup...
+            finished_results.push(MeshesBuilderFinishedResult {
+                mesh_hdl: Handle::default(),
+                faces: CadMeshFaces::default(),
+                face_mesh_hdls: default(),
+                outlines: CadMeshOutlines::default(),
+                lod_meshes: vec![],
+                spawn_meshes_builder,
+            });
+            continue;
+        }
+        // Reuse existing meshes if shell (and its meshing) is unchanged
+        // and no meshing is pending for it...
+        if !spawn_meshes_builder.shell_changed && !pending_tasks_count.contains_key(&key) {
+            if let Some(current_result) = meshes_builder_task_results_map.get(&key) {
+                if current_result
+                    .spawn_meshes_builder
+                    .meshes_builder
+                    .has_same_meshing(&spawn_meshes_builder.meshes_builder)
+                {
+                    finished_results.push(MeshesBuilderFinishedResult {
+                        spawn_meshes_builder,
+                        ..current_result.clone()
+                    });
+                    continue;
+                }
+            }
+        }
+        // Use cached tessellation if available (and caching is enabled), skipping the task...
+        if let Some(cache_key) = meshes_builder_cache_key(&spawn_meshes_builder)
+            .filter(|_| global_settings.tessellation_cache_capacity > 0)
+        {
+            if let Some(build_result) = meshes_builder_cache.get(&cache_key) {
+                builder_queue_inspector.cache_hits += 1;
+                finished_results.push(add_meshes_build_result(
+                    &mut meshes,
+                    build_result,
+                    spawn_meshes_builder,
                 ));
-            };
+                continue;
+            }
+            builder_queue_inspector.cache_misses += 1;
+        }
+        *pending_tasks_count.entry(key).or_default() += 1;
+        task_pool.spawn(async move {
+            let build_result = spawn_meshes_builder.meshes_builder.build_bevy_mesh();
 
-            Ok((bevy_mesh, spawn_meshes_builder))
+            (build_result, spawn_meshes_builder)
         });
     }
 
     // Collect finished tasks...
-    let finished_task_results = task_pool
-        .iter_poll()
-        .filter_map(|status| {
-            if let Poll::Ready(Ok(result)) = status {
-                Some(result)
-            } else {
-                None
+    for status in task_pool.iter_poll() {
+        let Poll::Ready((build_result, spawn_meshes_builder)) = status else {
+            continue;
+        };
+        let key = (
+            spawn_meshes_builder.belongs_to_root.0,
+            spawn_meshes_builder.shell_name.clone(),
+        );
+        if let Some(count) = pending_tasks_count.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                pending_tasks_count.remove(&key);
             }
-        })
-        .collect::<Vec<_>>();
-    if finished_task_results.is_empty() {
-        // clear map if no tasks...
-        meshes_builder_task_results_map.clear();
+        }
+        match build_result {
+            Ok(build_result) => {
+                if let Some(cache_key) = meshes_builder_cache_key(&spawn_meshes_builder) {
+                    if global_settings.tessellation_cache_capacity > 0 {
+                        meshes_builder_cache.insert(
+                            cache_key,
+                            build_result.clone(),
+                            global_settings.tessellation_cache_capacity,
+                        );
+                    }
+                }
+                finished_results.push(add_meshes_build_result(
+                    &mut meshes,
+                    build_result,
+                    spawn_meshes_builder,
+                ));
+            }
+            Err(e) => warn!(
+                "Could not build bevy_mesh for shell_name: {:?}, error: {:?}",
+                spawn_meshes_builder.shell_name, e
+            ),
+        }
     }
+
+    // Remove results of roots that are not available anymore...
+    meshes_builder_task_results_map.retain(|(root_ent, _), _| cad_generated.contains(*root_ent));
     // Store only the latest result per shell/root in the map.
     // This will prevent overriding from older tasks and prevent flashing jitter...
-    for task_result in finished_task_results.iter() {
-        let SpawnMeshesBuilder {
-            belongs_to_root: BelongsToCadGeneratedRoot(root_ent),
-            created_at_idx,
-            shell_name,
-            ..
-        } = &task_result.1;
-        if !cad_generated.contains(*root_ent) {
+    let mut updated_keys = HashSet::new();
+    for finished_result in finished_results {
+        let key = (
+            finished_result.spawn_meshes_builder.belongs_to_root.0,
+            finished_result.spawn_meshes_builder.shell_name.clone(),
+        );
+        if !cad_generated.contains(key.0) {
             // If root is not available, skip...
             continue;
         }
-        if let Some(current_result) =
-            meshes_builder_task_results_map.get(&(*root_ent, shell_name.clone()))
-        {
-            if current_result.1.created_at_idx < *created_at_idx {
-                meshes_builder_task_results_map
-                    .insert((*root_ent, shell_name.clone()), task_result.clone());
+        if let Some(current_result) = meshes_builder_task_results_map.get(&key) {
+            if current_result.spawn_meshes_builder.created_at_idx
+                >= finished_result.spawn_meshes_builder.created_at_idx
+            {
+                continue;
             }
-        } else {
-            meshes_builder_task_results_map
-                .insert((*root_ent, shell_name.clone()), task_result.clone());
         }
+        meshes_builder_task_results_map.insert(key.clone(), finished_result);
+        updated_keys.insert(key);
     }
 
-    // Spawn builders from task results map...
-    for (
-        bevy_mesh,
-        SpawnMeshesBuilder {
-            belongs_to_root: BelongsToCadGeneratedRoot(root_ent),
-            shell_name,
-            meshes_builder,
-            ..
-        },
-    ) in meshes_builder_task_results_map.values()
-    {
-        let mesh_hdl = meshes.add(bevy_mesh.clone());
+    // Spawn/update builders from updated results...
+    for key in updated_keys {
+        let Some(MeshesBuilderFinishedResult {
+            mesh_hdl,
+            faces,
+            face_mesh_hdls,
+            outlines,
+            lod_meshes,
+            spawn_meshes_builder:
+                SpawnMeshesBuilder {
+                    belongs_to_root: BelongsToCadGeneratedRoot(root_ent),
+                    shell_name,
+                    meshes_builder,
+                    ..
+                },
+        }) = meshes_builder_task_results_map.get(&key)
+        else {
+            continue;
+        };
 
         // cleanup old mesh builders + mesh bundles (that are not being updated/reused anymore)...
         let mesh_builders_to_be_cleaned =
@@ -414,7 +762,16 @@ pub fn handle_spawn_meshes_builder_events<Params: PmetraModelling + Component +
         }
 
         for (mesh_name, mesh_builder) in meshes_builder.mesh_builders.iter() {
-            let Ok(mesh_builder) = mesh_builder.clone().set_mesh_hdl(mesh_hdl.clone()) else {
+            let mut mesh_builder = mesh_builder.clone();
+            if mesh_builder.build_outlines {
+                mesh_builder.outlines = outlines.clone();
+            }
+            mesh_builder.faces = faces.clone();
+            let Ok(mesh_builder) = mesh_builder
+                .set_face_mesh_hdls(face_mesh_hdls.clone())
+                .and_then(|mut mesh_builder| mesh_builder.set_mesh_hdl(mesh_hdl.clone()))
+                .and_then(|mut mesh_builder| mesh_builder.set_lod_meshes(lod_meshes))
+            else {
                 continue;
             };
             if let Some((_, _, _, mut cur_mesh_builder, _)) = mesh_builders.iter_mut().find(
@@ -444,6 +801,68 @@ pub fn handle_spawn_meshes_builder_events<Params: PmetraModelling + Component +
     }
 }
 
+/// Get the [`MeshesBuilderCacheKey`] for the meshes builder, if it can be cached.
+fn meshes_builder_cache_key<Params: PmetraModelling + Component + Clone>(
+    spawn_meshes_builder: &SpawnMeshesBuilder<Params>,
+) -> Option<MeshesBuilderCacheKey> {
+    let mut face_tags = spawn_meshes_builder
+        .meshes_builder
+        .face_material_tags()
+        .into_iter()
+        .collect::<Vec<_>>();
+    face_tags.sort();
+    Some(MeshesBuilderCacheKey {
+        shell_name: spawn_meshes_builder.shell_name.clone(),
+        key: spawn_meshes_builder.cache_key?,
+        tolerance_bits: spawn_meshes_builder.meshes_builder.tolerance().to_bits(),
+        lod_tolerance_bits: spawn_meshes_builder
+            .meshes_builder
+            .lod_tolerances()
+            .into_iter()
+            .map(f64::to_bits)
+            .collect(),
+        normal_mode: spawn_meshes_builder.meshes_builder.normal_mode().ok()?,
+        tangents: spawn_meshes_builder.meshes_builder.needs_tangents(),
+        outlines: spawn_meshes_builder.meshes_builder.needs_outlines(),
+        face_tags,
+    })
+}
+
+/// Add the meshes of the [`CadMeshesBuildResult`] to assets.
+fn add_meshes_build_result<Params: PmetraModelling + Component + Clone>(
+    meshes: &mut Assets<Mesh>,
+    build_result: CadMeshesBuildResult,
+    spawn_meshes_builder: SpawnMeshesBuilder<Params>,
+) -> MeshesBuilderFinishedResult<Params> {
+    let CadMeshesBuildResult {
+        mesh,
+        faces,
+        face_meshes,
+        outlines,
+        lod_meshes,
+    } = build_result;
+    MeshesBuilderFinishedResult {
+        mesh_hdl: meshes.add(mesh),
+        faces,
+        face_mesh_hdls: face_meshes
+            .into_iter()
+            .map(|(tag, face_mesh)| (tag, meshes.add(face_mesh)))
+            .collect(),
+        outlines,
+        lod_meshes: lod_meshes
+            .into_iter()
+            .map(
+                |CadMeshLodBuildResult {
+                     tolerance,
+                     mesh,
+                     faces,
+                 }| { (tolerance, meshes.add(mesh), faces) },
+            )
+            .collect(),
+        spawn_meshes_builder,
+    }
+}
+
 pub fn mesh_builder_to_bundle<Params: PmetraModelling + Component + Clone>(
     mut commands: Commands,
     mut materials: ResMut<Assets<StandardMaterial>>,
@@ -459,7 +878,13 @@ pub fn mesh_builder_to_bundle<Params: PmetraModelling + Component + Clone>(
         ),
         (Changed<CadMeshBuilder<Params>>, Without<Cleanup>),
     >,
+    cad_face_meshes: Query<
+        (Entity, &CadGeneratedFaceMesh, &BelongsToCadGeneratedMesh),
+        Without<Cleanup>,
+    >,
 ) {
+    // Materials shared by the instances of patterns (updated together)...
+    let mut pattern_materials = HashMap::new();
     for (
         entity,
         cad_generated_mesh,
@@ -490,10 +915,44 @@ pub fn mesh_builder_to_bundle<Params: PmetraModelling + Component + Clone>(
             mesh_hdl,
             base_material,
             transform,
+            pattern_name,
             outlines,
+            faces,
+            face_meshes,
+            lods,
         } = cad_mesh;
-        let material_hdl = materials.add(base_material);
+        // Instances of the same pattern share materials (so they can be batched)...
+        let pattern_key = pattern_name.map(|pattern_name| (*root_ent, pattern_name));
+        let material_hdl = match &pattern_key {
+            Some(pattern_key) => pattern_materials
+                .entry((pattern_key.clone(), None))
+                .or_insert_with(|| materials.add(base_material))
+                .clone(),
+            None => materials.add(base_material),
+        };
+        let mut face_meshes = face_meshes
+            .into_iter()
+            .map(|(tag, (face_mesh_hdl, face_material))| {
+                let face_material_hdl = match &pattern_key {
+                    Some(pattern_key) => pattern_materials
+                        .entry((pattern_key.clone(), Some(tag.clone())))
+                        .or_insert_with(|| materials.add(face_material))
+                        .clone(),
+                    None => materials.add(face_material),
+                };
+                (tag, (face_mesh_hdl, face_material_hdl))
+            })
+            .collect::<HashMap<_, _>>();
 
+        // Levels of detail are swapped in by `update_cad_mesh_lods`...
+        if lods.is_empty() {
+            ent_commands.remove::<CadGeneratedMeshLods>();
+        } else {
+            ent_commands.insert(CadGeneratedMeshLods {
+                base: (mesh_hdl.clone(), faces.clone()),
+                lods,
+            });
+        }
         if cad_generated_mesh.is_some() {
             // If mesh already exists, update it...
             ent_commands
@@ -502,6 +961,7 @@ pub fn mesh_builder_to_bundle<Params: PmetraModelling + Component + Clone>(
                     Mesh3d(mesh_hdl),
                     transform,
                     CadGeneratedMeshOutlines(outlines.clone()),
+                    CadGeneratedMeshFaces(faces.clone()),
                 ))
                 // Remove AABB for Bevy to recompute as it wont recompute by itself...
                 // ref: https://github.com/bevyengine/bevy/issues/4294#issuecomment-1606056536)
@@ -514,6 +974,7 @@ pub fn mesh_builder_to_bundle<Params: PmetraModelling + Component + Clone>(
                     Mesh3d(mesh_hdl),
                     transform,
                     CadGeneratedMeshOutlines(outlines.clone()),
+                    CadGeneratedMeshFaces(faces.clone()),
                 ))
                 .insert((
                     Name::new(mesh_name.0.clone()),
@@ -522,5 +983,155 @@ pub fn mesh_builder_to_bundle<Params: PmetraModelling + Component + Clone>(
                     WireFrameDisplaySettings::default(),
                 ));
         }
+
+        // Update existing face meshes and cleanup the ones not needed anymore...
+        for (face_mesh_ent, CadGeneratedFaceMesh(tag), BelongsToCadGeneratedMesh(mesh_ent)) in
+            cad_face_meshes.iter()
+        {
+            if *mesh_ent != entity {
+                continue;
+            }
+            if let Some((face_mesh_hdl, face_material)) = face_meshes.remove(tag) {
+                commands
+                    .entity(face_mesh_ent)
+                    .insert((MeshMaterial3d(face_material), Mesh3d(face_mesh_hdl)))
+                    .remove::<Aabb>();
+            } else {
+                commands.entity(face_mesh_ent).insert(Cleanup::Recursive);
+            }
+        }
+        // Spawn new face meshes as children of the mesh...
+        commands.entity(entity).with_children(|commands| {
+            for (tag, (face_mesh_hdl, face_material)) in face_meshes {
+                commands.spawn((
+                    Name::new(format!("{}:{}", mesh_name.0, tag.0)),
+                    MeshMaterial3d(face_material),
+                    Mesh3d(face_mesh_hdl),
+                    CadGeneratedFaceMesh(tag),
+                    BelongsToCadGeneratedMesh(entity),
+                    BelongsToCadGeneratedRoot(*root_ent),
+                    WireFrameDisplaySettings::default(),
+                ));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::{
+        constants::CUSTOM_TRUCK_TOLERANCE_1,
+        test_utils::{cube_shell, MeshPerShell},
+    };
+    use truck_modeling::Point3;
+
+    /// Shells `A` and `B` (unit cubes) of the `root`, with the `changed_shells` in the last build.
+    fn built_shells(
+        root: Entity,
+        changed_shells: &[&str],
+    ) -> (
+        CadShellsByName,
+        CadShellsBuiltWith<MeshPerShell>,
+        BelongsToCadGeneratedRoot,
+    ) {
+        let mut shells_by_name = CadShellsByName::default();
+        for name in ["A", "B"] {
+            shells_by_name.insert(
+                CadShellName(name.into()),
+                cube_shell(Point3::new(0., 0., 0.)),
+            );
+        }
+        let built_with = CadShellsBuiltWith {
+            params: MeshPerShell,
+            created_at_idx: 0,
+            shell_keys: default(),
+            changed_shells: changed_shells
+                .iter()
+                .map(|name| CadShellName(name.to_string()))
+                .collect(),
+        };
+        (shells_by_name, built_with, BelongsToCadGeneratedRoot(root))
+    }
+
+    #[test]
+    pub fn test_preview_tolerance_while_dragging() {
+        let preview_tolerance = 0.5;
+        let mut app = App::new();
+        app.insert_resource(PmetraGlobalSettings {
+            slider_drag_preview_tolerance: Some(preview_tolerance),
+            ..default()
+        })
+        .init_resource::<MeshesBuilderQueue<MeshPerShell>>()
+        .add_systems(Update, shells_to_mesh_builder_events::<MeshPerShell>);
+        let root = app.world_mut().spawn((CadGeneratedRoot, MeshPerShell)).id();
+        let slider = app
+            .world_mut()
+            .spawn((
+                CadGeneratedSlider,
+                CadGeneratedSliderState::Dragging,
+                BelongsToCadGeneratedRoot(root),
+            ))
+            .id();
+        let shells = app.world_mut().spawn(built_shells(root, &["A"])).id();
+        // Tolerances of the queued meshes builders by shell name...
+        let queued_tolerances = |app: &mut App| {
+            app.world_mut()
+                .resource_mut::<MeshesBuilderQueue<MeshPerShell>>()
+                .drain(..)
+                .map(|spawn_meshes_builder| {
+                    (
+                        spawn_meshes_builder.shell_name.0,
+                        spawn_meshes_builder.meshes_builder.tolerance(),
+                    )
+                })
+                .collect::<std::collections::HashMap<_, _>>()
+        };
+        let preview_shells = |app: &App| {
+            app.world().get::<CadGeneratedRootPreviewMeshes>(root).map(
+                |CadGeneratedRootPreviewMeshes(shell_names)| {
+                    let mut shell_names = shell_names
+                        .iter()
+                        .map(|shell_name| shell_name.0.as_str())
+                        .collect::<Vec<_>>();
+                    shell_names.sort();
+                    shell_names.join(",")
+                },
+            )
+        };
+
+        // Only the changed shell is tessellated with the preview tolerance...
+        app.update();
+        let tolerances = queued_tolerances(&mut app);
+        assert_eq!(tolerances["A"], preview_tolerance);
+        assert_eq!(tolerances["B"], CUSTOM_TRUCK_TOLERANCE_1);
+        assert_eq!(preview_shells(&app).as_deref(), Some("A"));
+
+        // Shell with preview meshes keeps the preview tolerance (to reuse its meshes)
+        // when only the other shell changes...
+        app.world_mut()
+            .entity_mut(shells)
+            .insert(built_shells(root, &["B"]));
+        app.update();
+        let tolerances = queued_tolerances(&mut app);
+        assert_eq!(tolerances["A"], preview_tolerance);
+        assert_eq!(tolerances["B"], preview_tolerance);
+        assert_eq!(preview_shells(&app).as_deref(), Some("A,B"));
+
+        // Full quality tolerance once the drag ends...
+        app.world_mut()
+            .entity_mut(slider)
+            .insert(CadGeneratedSliderState::Normal);
+        app.update();
+        let tolerances = queued_tolerances(&mut app);
+        assert_eq!(tolerances["A"], CUSTOM_TRUCK_TOLERANCE_1);
+        assert_eq!(tolerances["B"], CUSTOM_TRUCK_TOLERANCE_1);
+        assert_eq!(preview_shells(&app), None);
+
+        // Nothing is re-meshed while the shells are unchanged...
+        app.update();
+        assert!(queued_tolerances(&mut app).is_empty());
     }
 }
@@ -1,12 +1,12 @@
-use bevy::prelude::*;
+use bevy::{color::palettes::css, prelude::*};
 
 use crate::{
     constants::PARAMS_UI_BOTTOM_SHIFT_PX,
-    pmetra_core::builders::{CadSliderName, PmetraInteractions},
+    pmetra_core::builders::{CadSliderName, ParamIssue, PmetraInteractions},
     pmetra_plugins::components::{
         cad::{
             BelongsToCadGeneratedRoot, BelongsToCadGeneratedSlider, CadGeneratedRoot,
-            CadGeneratedSlider, CadGeneratedSliderDragPlane,
+            CadGeneratedSlider, CadGeneratedSliderDragPlane, CadGeneratedSliderIssues,
         },
         camera::CadCamera,
         params_ui::ParamDisplayUi,
@@ -46,22 +46,36 @@ pub fn setup_param_display_ui(mut commands: Commands, cameras: Query<Entity, Add
 pub fn show_params_display_ui_on_pointer_over_slider<Params: PmetraInteractions + Component>(
     trigger: On<Pointer<Over>>,
     cameras: Query<(&Camera, &GlobalTransform), With<CadCamera>>,
-    mut ui_nodes: Query<(&mut Text, &mut Node, &mut Visibility), With<ParamDisplayUi>>,
+    mut ui_nodes: Query<
+        (&mut Text, &mut Node, &mut BackgroundColor, &mut Visibility),
+        With<ParamDisplayUi>,
+    >,
     generated_roots: Query<&Params, With<CadGeneratedRoot>>,
     sliders: Query<
-        (&GlobalTransform, &CadSliderName, &BelongsToCadGeneratedRoot),
+        (
+            &GlobalTransform,
+            &CadSliderName,
+            &BelongsToCadGeneratedRoot,
+            &CadGeneratedSliderIssues,
+        ),
         With<CadGeneratedSlider>,
     >,
 ) {
     let Ok((camera, cam_glob_transform)) = cameras.single() else {
         return;
     };
-    let Ok((mut text, mut ui_node_style, mut visibility)) = ui_nodes.single_mut() else {
+    let Ok((mut text, mut ui_node_style, mut background_color, mut visibility)) =
+        ui_nodes.single_mut()
+    else {
         return;
     };
     let slider = trigger.entity;
-    let Ok((slider_glob_transform, slider_name, BelongsToCadGeneratedRoot(cad_root_ent))) =
-        sliders.get(slider)
+    let Ok((
+        slider_glob_transform,
+        slider_name,
+        BelongsToCadGeneratedRoot(cad_root_ent),
+        CadGeneratedSliderIssues(issues),
+    )) = sliders.get(slider)
     else {
         return;
     };
@@ -72,7 +86,7 @@ pub fn show_params_display_ui_on_pointer_over_slider<Params: PmetraInteractions
     let Ok(Some(tooltip)) = params.on_slider_tooltip(slider_name.clone()) else {
         return;
     };
-    text.0 = tooltip;
+    set_params_display_ui_text(&mut text, &mut background_color, tooltip, issues);
     // Get view translation to set the UI pos from world slider pos.
     let Ok(viewport_pos) =
         camera.world_to_viewport(cam_glob_transform, slider_glob_transform.translation())
@@ -99,25 +113,39 @@ pub fn show_params_display_ui_on_pointer_move_drag_plane<Params: PmetraInteracti
     trigger: On<Pointer<Move>>,
     cameras: Query<(&Camera, &GlobalTransform), With<CadCamera>>,
     drag_planes: Query<&BelongsToCadGeneratedSlider, With<CadGeneratedSliderDragPlane>>,
-    mut ui_nodes: Query<(&mut Text, &mut Node, &mut Visibility), With<ParamDisplayUi>>,
+    mut ui_nodes: Query<
+        (&mut Text, &mut Node, &mut BackgroundColor, &mut Visibility),
+        With<ParamDisplayUi>,
+    >,
     generated_roots: Query<&Params, With<CadGeneratedRoot>>,
     sliders: Query<
-        (&GlobalTransform, &CadSliderName, &BelongsToCadGeneratedRoot),
+        (
+            &GlobalTransform,
+            &CadSliderName,
+            &BelongsToCadGeneratedRoot,
+            &CadGeneratedSliderIssues,
+        ),
         With<CadGeneratedSlider>,
     >,
 ) {
     let Ok((camera, cam_glob_transform)) = cameras.single() else {
         return;
     };
-    let Ok((mut text, mut ui_node_style, mut visibility)) = ui_nodes.single_mut() else {
+    let Ok((mut text, mut ui_node_style, mut background_color, mut visibility)) =
+        ui_nodes.single_mut()
+    else {
         return;
     };
     let drag_plane = trigger.entity;
     let Ok(BelongsToCadGeneratedSlider(slider)) = drag_planes.get(drag_plane) else {
         return;
     };
-    let Ok((slider_glob_transform, slider_name, BelongsToCadGeneratedRoot(cad_root_ent))) =
-        sliders.get(*slider)
+    let Ok((
+        slider_glob_transform,
+        slider_name,
+        BelongsToCadGeneratedRoot(cad_root_ent),
+        CadGeneratedSliderIssues(issues),
+    )) = sliders.get(*slider)
     else {
         return;
     };
@@ -128,7 +156,7 @@ pub fn show_params_display_ui_on_pointer_move_drag_plane<Params: PmetraInteracti
     let Ok(Some(tooltip)) = params.on_slider_tooltip(slider_name.clone()) else {
         return;
     };
-    text.0 = tooltip;
+    set_params_display_ui_text(&mut text, &mut background_color, tooltip, issues);
     // Get view translation to set the UI pos from world slider pos.
     let Ok(viewport_pos) =
         camera.world_to_viewport(cam_glob_transform, slider_glob_transform.translation())
@@ -140,3 +168,20 @@ pub fn show_params_display_ui_on_pointer_move_drag_plane<Params: PmetraInteracti
     ui_node_style.left = Val::Px(viewport_pos.x);
     *visibility = Visibility::Visible;
 }
+
+/// Set the tooltip text, appending the [`ParamIssue`]s (if any) of the rejected slider edit.
+fn set_params_display_ui_text(
+    text: &mut Text,
+    background_color: &mut BackgroundColor,
+    tooltip: String,
+    issues: &[ParamIssue],
+) {
+    text.0 = issues
+        .iter()
+        .fold(tooltip, |text, issue| format!("{}\n{}", text, issue));
+    background_color.0 = if issues.is_empty() {
+        Color::BLACK.with_alpha(0.8)
+    } else {
+        Color::from(css::DARK_RED).with_alpha(0.8)
+    };
+}
@@ -11,3 +11,8 @@ pub fn show_selected_mesh_local_debug_axis(global_settings: Res<PmetraGlobalSett
 pub fn show_selected_mesh_outlines(global_settings: Res<PmetraGlobalSettings>) -> bool {
     global_settings.show_selected_mesh_outlines
 }
+
+/// Run condition for undo/redo of the params edits via keys.
+pub fn undo_redo_keys(global_settings: Res<PmetraGlobalSettings>) -> bool {
+    global_settings.undo_redo_keys
+}
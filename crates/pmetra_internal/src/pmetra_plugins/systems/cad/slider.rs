@@ -6,6 +6,7 @@ use bevy::{
 };
 
 use crate::{
+    constants::SLIDER_CLAMP_ITERATIONS,
     math::get_rotation_from_normals,
     pmetra_core::builders::{CadSliderName, CadSliderType, PmetraInteractions},
     pmetra_plugins::{
@@ -14,8 +15,8 @@ use crate::{
             cad::{
                 BelongsToCadGeneratedRoot, BelongsToCadGeneratedSlider, CadGeneratedMesh,
                 CadGeneratedRoot, CadGeneratedRootSelectionState, CadGeneratedSlider,
-                CadGeneratedSliderConfig, CadGeneratedSliderDragPlane,
-                CadGeneratedSliderPreviousTransform, CadGeneratedSliderState,
+                CadGeneratedSliderConfig, CadGeneratedSliderDragPlane, CadGeneratedSliderIssues,
+                CadGeneratedSliderPreviousTransform, CadGeneratedSliderState, CadParamsHistory,
             },
             params_ui::ParamDisplayUi,
         },
@@ -61,6 +62,7 @@ pub fn slider_drag_start<Params: PmetraInteractions + Component>(
         ),
         With<CadGeneratedSlider>,
     >,
+    mut generated_roots: Query<(&Params, &mut CadParamsHistory<Params>), With<CadGeneratedRoot>>,
     global_settings: Res<PmetraGlobalSettings>,
 ) {
     let slider = drag_event.entity;
@@ -82,6 +84,10 @@ pub fn slider_drag_start<Params: PmetraInteractions + Component>(
     } = *global_settings;
     // set state to dragging
     *slider_state = CadGeneratedSliderState::Dragging;
+    // snapshot params for undo (recorded on the first edit of the drag)...
+    if let Ok((params, mut history)) = generated_roots.get_mut(*cad_root) {
+        history.drag_start = Some(params.clone());
+    }
 
     // Get transform from slider normal...
     let rotation = get_rotation_from_normals(Vec3::Y, *drag_plane_normal);
@@ -142,6 +148,7 @@ pub fn slider_drag_end(
             &CadGeneratedSliderPreviousTransform,
             &CadGeneratedSliderConfig,
             &mut CadGeneratedSliderState,
+            &mut CadGeneratedSliderIssues,
             &BelongsToCadGeneratedRoot,
         ),
         With<CadGeneratedSlider>,
@@ -162,6 +169,7 @@ pub fn slider_drag_end(
         prev_transform,
         _config,
         mut slider_state,
+        mut issues,
         BelongsToCadGeneratedRoot(cad_root),
     )) = sliders.get_mut(slider)
     else {
@@ -172,6 +180,10 @@ pub fn slider_drag_end(
     *slider_transform = prev_transform.0;
     // reset state to default
     *slider_state = CadGeneratedSliderState::default();
+    // clear issues of rejected edits
+    if !issues.0.is_empty() {
+        issues.0.clear();
+    }
 
     // Make slider, etc pick-able again...
     commands.entity(slider).insert(Pickable::default());
@@ -247,8 +259,11 @@ pub fn transform_slider_on_pointer_move(
 }
 
 pub fn update_params_from_sliders<Params: PmetraInteractions + Component<Mutability = Mutable>>(
-    mut generated_roots: Query<(Entity, &mut Params), With<CadGeneratedRoot>>,
-    sliders: Query<
+    mut generated_roots: Query<
+        (Entity, &mut Params, Option<&mut CadParamsHistory<Params>>),
+        With<CadGeneratedRoot>,
+    >,
+    mut sliders: Query<
         (
             &CadSliderName,
             &BelongsToCadGeneratedRoot,
@@ -256,9 +271,11 @@ pub fn update_params_from_sliders<Params: PmetraInteractions + Component<Mutabil
             &CadGeneratedSliderPreviousTransform,
             &CadGeneratedSliderConfig,
             &CadGeneratedSliderState,
+            &mut CadGeneratedSliderIssues,
         ),
         With<CadGeneratedSlider>,
     >,
+    global_settings: Res<PmetraGlobalSettings>,
 ) {
     for (
         slider_name,
@@ -267,19 +284,93 @@ pub fn update_params_from_sliders<Params: PmetraInteractions + Component<Mutabil
         previous_transform,
         _config,
         state,
-    ) in sliders.iter()
+        mut issues,
+    ) in sliders.iter_mut()
     {
-        let Ok((_cad_generated_ent, mut params)) = generated_roots.get_mut(*cad_generated_root)
+        let Ok((_cad_generated_ent, mut params, mut history)) =
+            generated_roots.get_mut(*cad_generated_root)
         else {
             continue;
         };
         let is_transforms_equal = transform
             .translation
             .abs_diff_eq(previous_transform.0.translation, 0.01);
-        if !is_transforms_equal && matches!(state, CadGeneratedSliderState::Dragging) {
-            // run event handler on params...
-            params.on_slider_transform(slider_name.clone(), previous_transform.0, *transform);
+        if is_transforms_equal || !matches!(state, CadGeneratedSliderState::Dragging) {
+            continue;
         }
+        // run event handler on a copy of params, only keeping valid ones...
+        let edit_params = |new_transform: Transform| {
+            let mut new_params = (*params).clone();
+            new_params.on_slider_transform(
+                slider_name.clone(),
+                previous_transform.0,
+                new_transform,
+            );
+            new_params.validate().map(|_| new_params)
+        };
+        let new_params = match edit_params(*transform) {
+            Ok(new_params) => {
+                if !issues.0.is_empty() {
+                    issues.0.clear();
+                }
+                Some(new_params)
+            }
+            Err(new_issues) => {
+                // Clamp to the furthest valid edit along the drag (if any)...
+                let (mut valid_params, mut low, mut high) = (None, 0., 1.);
+                for _ in 0..SLIDER_CLAMP_ITERATIONS {
+                    let mid = (low + high) / 2.;
+                    let mid_transform = previous_transform.0.with_translation(
+                        previous_transform
+                            .0
+                            .translation
+                            .lerp(transform.translation, mid),
+                    );
+                    match edit_params(mid_transform) {
+                        Ok(new_params) => {
+                            valid_params = Some(new_params);
+                            low = mid;
+                        }
+                        Err(_) => high = mid,
+                    }
+                }
+                if issues.0 != new_issues {
+                    issues.0 = new_issues;
+                }
+                valid_params
+            }
+        };
+        let Some(new_params) = new_params else {
+            continue;
+        };
+        // Record one history entry per drag (on its first edit)...
+        if let Some(history) = history.as_mut() {
+            if let Some(snapshot) = history.drag_start.take() {
+                history.record(snapshot, global_settings.params_history_capacity);
+            }
+        }
+        *params = new_params;
+    }
+}
+
+/// Tint the sliders red while their edits are rejected (ie: a param constraint is hit).
+pub fn update_slider_color_from_issues(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    sliders: Query<
+        (&MeshMaterial3d<StandardMaterial>, &CadGeneratedSliderIssues),
+        (With<CadGeneratedSlider>, Changed<CadGeneratedSliderIssues>),
+    >,
+) {
+    for (MeshMaterial3d(material_hdl), CadGeneratedSliderIssues(issues)) in sliders.iter() {
+        let Some(material) = materials.get_mut(material_hdl) else {
+            continue;
+        };
+        let color = if issues.is_empty() {
+            css::WHITE
+        } else {
+            css::RED
+        };
+        material.base_color = color.with_alpha(0.4).into();
     }
 }
 
@@ -349,3 +440,62 @@ pub fn scale_sliders_based_on_zoom_level(
         transform.scale = Vec3::ONE * camera_to_slider_dist.clamp(0., 5.) / 5.;
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+    use crate::{
+        pmetra_core::builders::ParamIssue,
+        test_utils::{drag, spawn_plank_slider, Plank},
+    };
+
+    #[test]
+    pub fn test_update_params_from_sliders_clamps_invalid_edits() {
+        let mut app = App::new();
+        app.init_resource::<PmetraGlobalSettings>()
+            .add_systems(Update, update_params_from_sliders::<Plank>);
+        let root = app
+            .world_mut()
+            .spawn((CadGeneratedRoot, Plank { width: 1. }))
+            .id();
+        let slider = spawn_plank_slider(&mut app, root, CadGeneratedSliderState::Dragging);
+        let width = |app: &App| app.world().get::<Plank>(root).unwrap().width;
+        let issues = |app: &App| {
+            app.world()
+                .get::<CadGeneratedSliderIssues>(slider)
+                .unwrap()
+                .0
+                .clone()
+        };
+
+        // Valid edit...
+        drag(&mut app, slider, 0., 0.5);
+        assert!((width(&app) - 1.5).abs() < 1e-6);
+        assert!(issues(&app).is_empty());
+
+        // Invalid edit is clamped to the last valid value along the drag...
+        drag(&mut app, slider, 0.5, 2.5);
+        assert!(width(&app) <= 2.);
+        assert!(width(&app) > 2. - 2. / (1 << SLIDER_CLAMP_ITERATIONS) as f64 - 1e-6);
+        assert_eq!(
+            issues(&app),
+            vec![ParamIssue::new("width", "should be at most 2")]
+        );
+
+        // Next valid edit clears the issues...
+        let clamped_width = width(&app);
+        drag(&mut app, slider, 2.5, 2.);
+        assert!((width(&app) - (clamped_width - 0.5)).abs() < 1e-6);
+        assert!(issues(&app).is_empty());
+
+        // Edits are ignored when not dragging...
+        app.world_mut()
+            .entity_mut(slider)
+            .insert(CadGeneratedSliderState::Normal);
+        let width_before = width(&app);
+        drag(&mut app, slider, 0., 1.);
+        assert_eq!(width(&app), width_before);
+    }
+}
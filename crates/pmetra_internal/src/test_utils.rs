@@ -0,0 +1,148 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use truck_modeling::{builder, Point3, Rad, Vector3};
+
+use crate::{
+    pmetra_core::builders::{
+        CadMeshBuilder, CadMeshesBuildersByCadShell, CadShell, CadShellsBuilders, CadShellsByName,
+        CadSliderName, CadSliderType, CadSliders, CadSolid, CadTaggedElements, ParamIssue,
+        PmetraCad, PmetraInteractions, PmetraModelling,
+    },
+    pmetra_plugins::components::cad::{
+        BelongsToCadGeneratedRoot, CadGeneratedSlider, CadGeneratedSliderConfig,
+        CadGeneratedSliderIssues, CadGeneratedSliderPreviousTransform, CadGeneratedSliderState,
+    },
+};
+
+/// Unit cube shell (without tags) with its min corner at `origin`.
+pub fn cube_shell(origin: Point3) -> CadShell {
+    let v = builder::vertex(origin);
+    let e = builder::tsweep(&v, Vector3::unit_x());
+    let f = builder::tsweep(&e, Vector3::unit_y());
+    let solid = builder::tsweep(&f, Vector3::unit_z());
+    CadShell {
+        shell: solid.into_boundaries().pop().unwrap(),
+        tagged_elements: CadTaggedElements::default(),
+    }
+}
+
+/// Cylinder shell (without tags) of radius 1 along y from the origin, with a 2 segment circle.
+pub fn cylinder_shell() -> CadShell {
+    let v = builder::vertex(Point3::new(1., 0., 0.));
+    let circle = builder::rsweep(&v, Point3::new(0., 0., 0.), Vector3::unit_y(), Rad(7.0), 2);
+    let disk = builder::try_attach_plane(&[circle]).unwrap();
+    let solid = builder::tsweep(&disk, Vector3::unit_y());
+    CadShell {
+        shell: solid.into_boundaries().pop().unwrap(),
+        tagged_elements: CadTaggedElements::default(),
+    }
+}
+
+/// Number of faces of the solid.
+pub fn face_count(cad_solid: &CadSolid) -> usize {
+    cad_solid.solid.face_iter().count()
+}
+
+/// Params without shell builders of its own, with a mesh (named as the shell) for each of the given shells.
+#[derive(Debug, Clone, Default, Component, TypePath)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeshPerShell;
+
+impl PmetraCad for MeshPerShell {
+    fn shells_builders(&self) -> Result<CadShellsBuilders<Self>> {
+        Ok(CadShellsBuilders::default())
+    }
+}
+
+impl PmetraModelling for MeshPerShell {
+    fn meshes_builders_by_shell(
+        &self,
+        shells_by_name: &CadShellsByName,
+    ) -> Result<CadMeshesBuildersByCadShell<Self>> {
+        let mut meshes_builders =
+            CadMeshesBuildersByCadShell::new(self.clone(), shells_by_name.clone())?;
+        for shell_name in shells_by_name.keys() {
+            meshes_builders.add_mesh_builder(
+                shell_name.clone(),
+                shell_name.0.clone(),
+                CadMeshBuilder::new(self.clone(), shell_name.clone())?,
+            )?;
+        }
+        Ok(meshes_builders)
+    }
+}
+
+/// Params with `width` capped by [`PmetraCad::validate`], edited along the slider's x.
+#[derive(Debug, Clone, Default, Component)]
+pub struct Plank {
+    pub width: f64,
+}
+
+impl PmetraCad for Plank {
+    fn shells_builders(&self) -> Result<CadShellsBuilders<Self>> {
+        Ok(CadShellsBuilders::default())
+    }
+
+    fn validate(&self) -> Result<(), Vec<ParamIssue>> {
+        if self.width <= 2. {
+            Ok(())
+        } else {
+            Err(vec![ParamIssue::new("width", "should be at most 2")])
+        }
+    }
+}
+
+impl PmetraModelling for Plank {
+    fn meshes_builders_by_shell(
+        &self,
+        shells_by_name: &CadShellsByName,
+    ) -> Result<CadMeshesBuildersByCadShell<Self>> {
+        CadMeshesBuildersByCadShell::new(self.clone(), shells_by_name.clone())
+    }
+}
+
+impl PmetraInteractions for Plank {
+    fn sliders(&self, _shells_by_name: &CadShellsByName) -> Result<CadSliders> {
+        Ok(CadSliders::default())
+    }
+
+    fn on_slider_transform(
+        &mut self,
+        _name: CadSliderName,
+        prev_transform: Transform,
+        new_transform: Transform,
+    ) {
+        self.width += (new_transform.translation.x - prev_transform.translation.x) as f64;
+    }
+
+    fn on_slider_tooltip(&self, _name: CadSliderName) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Spawn the `Width` slider of the `root` in the `state`.
+pub fn spawn_plank_slider(app: &mut App, root: Entity, state: CadGeneratedSliderState) -> Entity {
+    app.world_mut()
+        .spawn((
+            CadGeneratedSlider,
+            CadSliderName("Width".into()),
+            BelongsToCadGeneratedRoot(root),
+            CadGeneratedSliderConfig {
+                thumb_radius: 0.1,
+                drag_plane_normal: Vec3::Z,
+                slider_type: CadSliderType::default(),
+            },
+            state,
+            CadGeneratedSliderIssues::default(),
+        ))
+        .id()
+}
+
+/// Drag the slider from `from` to `to` (along x) and run the systems.
+pub fn drag(app: &mut App, slider: Entity, from: f32, to: f32) {
+    app.world_mut().entity_mut(slider).insert((
+        CadGeneratedSliderPreviousTransform(Transform::from_xyz(from, 0., 0.)),
+        Transform::from_xyz(to, 0., 0.),
+    ));
+    app.update();
+}
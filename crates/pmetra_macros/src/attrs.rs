@@ -0,0 +1,271 @@
+use syn::{
+    meta::ParseNestedMeta, spanned::Spanned, Attribute, Data, DeriveInput, Error, Expr, Fields,
+    Ident, Path, Result,
+};
+
+/// Name of the helper attribute used by all Pmetra derives.
+const PMETRA_ATTR: &str = "pmetra";
+
+/// All `#[pmetra(...)]` attributes collected from the params struct and its fields.
+#[derive(Default)]
+pub struct PmetraAttrs {
+    pub shells: Vec<ShellAttr>,
+    pub meshes: Vec<MeshAttr>,
+    pub sliders: Vec<SliderAttr>,
+    pub field_sliders: Vec<FieldSliderAttr>,
+    /// `#[pmetra(validate = ..)]` params validation fn: `fn(&Params) -> Result<(), Vec<ParamIssue>>`.
+    pub validate: Option<Path>,
+    /// `#[pmetra(cache_key)]` key the tessellation cache by all the fields (via `ParamsKey`).
+    pub cache_key: bool,
+    /// `#[pmetra(crate = ..)]` path of the crate exporting the Pmetra traits (`::bevy_pmetra` by default).
+    pub crate_path: Option<Path>,
+}
+
+/// `#[pmetra(shell(name = .., builder = .., key = ..))]`
+pub struct ShellAttr {
+    /// Name of the shell. Any expression implementing `ToString`.
+    pub name: Expr,
+    /// Shell builder fn: `fn(&Params) -> Result<CadShell>`.
+    pub builder: Path,
+    /// Shell key fn: `fn(&Params) -> impl Hash`.
+    pub key: Option<Path>,
+}
+
+/// `#[pmetra(mesh(name = .., shell = .., builder = .., outlines))]`
+pub struct MeshAttr {
+    /// Name of the mesh. Any expression implementing `ToString`.
+    pub name: Expr,
+    /// Name of the shell the mesh is built from.
+    pub shell: Expr,
+    /// Mesh builder fn: `fn(&Params, CadShellName) -> Result<CadMeshBuilder<Params>>`.
+    pub builder: Path,
+    /// Build outlines for the mesh.
+    pub outlines: bool,
+}
+
+/// `#[pmetra(slider(name = .., builder = .., on_transform = .., tooltip = ..))]`
+pub struct SliderAttr {
+    /// Name of the slider. Any expression implementing `ToString`.
+    pub name: Expr,
+    /// Slider builder fn: `fn(&Params, &CadShellsByName) -> Result<CadSlider>`.
+    pub builder: Path,
+    /// Custom transform handler: `fn(&mut Params, Transform, Transform)`.
+    pub on_transform: Option<Path>,
+    /// Custom tooltip handler: `fn(&Params) -> Result<Option<String>>`.
+    pub tooltip: Option<Path>,
+}
+
+/// `#[pmetra(slider = .., axis = .., sensitivity = .., min = .., max = ..)]` on a field.
+pub struct FieldSliderAttr {
+    /// Field bound to the slider.
+    pub field: Ident,
+    /// Name of the slider driving the field.
+    pub slider: Expr,
+    /// Axis of the slider translation delta applied to the field.
+    pub axis: SliderAxis,
+    /// Multiplier applied to the slider delta.
+    pub sensitivity: Option<Expr>,
+    /// Min value of the field. Can use `self`.
+    pub min: Option<Expr>,
+    /// Max value of the field. Can use `self`.
+    pub max: Option<Expr>,
+}
+
+/// Axis of the slider translation delta.
+pub enum SliderAxis {
+    X,
+    Y,
+    Z,
+    /// Whole translation delta, for `DVec3` fields.
+    Xyz,
+}
+
+impl PmetraAttrs {
+    /// Parse all `#[pmetra(...)]` attributes from the derive input.
+    pub fn parse(input: &DeriveInput) -> Result<Self> {
+        let mut attrs = Self::default();
+        for attr in pmetra_attrs(&input.attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("shell") {
+                    attrs.shells.push(parse_shell(&meta)?);
+                } else if meta.path.is_ident("mesh") {
+                    attrs.meshes.push(parse_mesh(&meta)?);
+                } else if meta.path.is_ident("slider") {
+                    attrs.sliders.push(parse_slider(&meta)?);
+                } else if meta.path.is_ident("validate") {
+                    attrs.validate = Some(meta.value()?.parse::<Path>()?);
+                } else if meta.path.is_ident("cache_key") {
+                    attrs.cache_key = true;
+                } else if meta.path.is_ident("crate") {
+                    attrs.crate_path = Some(meta.value()?.parse::<Path>()?);
+                } else {
+                    return Err(meta.error(
+                        "expected `shell(..)`, `mesh(..)`, `slider(..)`, `validate = ..`, `cache_key` or `crate = ..`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+
+        let Data::Struct(data) = &input.data else {
+            return Err(Error::new(
+                input.ident.span(),
+                "Pmetra derives are only supported on structs",
+            ));
+        };
+        if let Fields::Named(fields) = &data.fields {
+            for field in fields.named.iter() {
+                for attr in pmetra_attrs(&field.attrs) {
+                    let field_ident = field.ident.clone().expect("named field");
+                    attrs
+                        .field_sliders
+                        .push(parse_field_slider(attr, field_ident)?);
+                }
+            }
+        }
+
+        Ok(attrs)
+    }
+
+    /// Path of the crate exporting the Pmetra traits, ie: `#[pmetra(crate = ..)]` or `::bevy_pmetra`.
+    pub fn crate_path(&self) -> Path {
+        self.crate_path
+            .clone()
+            .unwrap_or_else(|| syn::parse_quote!(::bevy_pmetra))
+    }
+}
+
+fn pmetra_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(PMETRA_ATTR))
+}
+
+fn parse_shell(meta: &ParseNestedMeta) -> Result<ShellAttr> {
+    let mut name = None;
+    let mut builder = None;
+    let mut key = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("name") {
+            name = Some(inner.value()?.parse::<Expr>()?);
+        } else if inner.path.is_ident("builder") {
+            builder = Some(inner.value()?.parse::<Path>()?);
+        } else if inner.path.is_ident("key") {
+            key = Some(inner.value()?.parse::<Path>()?);
+        } else {
+            return Err(inner.error("expected `name`, `builder` or `key`"));
+        }
+        Ok(())
+    })?;
+
+    Ok(ShellAttr {
+        name: name.ok_or_else(|| meta.error("missing `name` for shell"))?,
+        builder: builder.ok_or_else(|| meta.error("missing `builder` for shell"))?,
+        key,
+    })
+}
+
+fn parse_mesh(meta: &ParseNestedMeta) -> Result<MeshAttr> {
+    let mut name = None;
+    let mut shell = None;
+    let mut builder = None;
+    let mut outlines = false;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("name") {
+            name = Some(inner.value()?.parse::<Expr>()?);
+        } else if inner.path.is_ident("shell") {
+            shell = Some(inner.value()?.parse::<Expr>()?);
+        } else if inner.path.is_ident("builder") {
+            builder = Some(inner.value()?.parse::<Path>()?);
+        } else if inner.path.is_ident("outlines") {
+            outlines = true;
+        } else {
+            return Err(inner.error("expected `name`, `shell`, `builder` or `outlines`"));
+        }
+        Ok(())
+    })?;
+
+    Ok(MeshAttr {
+        name: name.ok_or_else(|| meta.error("missing `name` for mesh"))?,
+        shell: shell.ok_or_else(|| meta.error("missing `shell` for mesh"))?,
+        builder: builder.ok_or_else(|| meta.error("missing `builder` for mesh"))?,
+        outlines,
+    })
+}
+
+fn parse_slider(meta: &ParseNestedMeta) -> Result<SliderAttr> {
+    let mut name = None;
+    let mut builder = None;
+    let mut on_transform = None;
+    let mut tooltip = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("name") {
+            name = Some(inner.value()?.parse::<Expr>()?);
+        } else if inner.path.is_ident("builder") {
+            builder = Some(inner.value()?.parse::<Path>()?);
+        } else if inner.path.is_ident("on_transform") {
+            on_transform = Some(inner.value()?.parse::<Path>()?);
+        } else if inner.path.is_ident("tooltip") {
+            tooltip = Some(inner.value()?.parse::<Path>()?);
+        } else {
+            return Err(inner.error("expected `name`, `builder`, `on_transform` or `tooltip`"));
+        }
+        Ok(())
+    })?;
+
+    Ok(SliderAttr {
+        name: name.ok_or_else(|| meta.error("missing `name` for slider"))?,
+        builder: builder.ok_or_else(|| meta.error("missing `builder` for slider"))?,
+        on_transform,
+        tooltip,
+    })
+}
+
+fn parse_field_slider(attr: &Attribute, field: Ident) -> Result<FieldSliderAttr> {
+    let mut slider = None;
+    let mut axis = None;
+    let mut sensitivity = None;
+    let mut min = None;
+    let mut max = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("slider") {
+            slider = Some(meta.value()?.parse::<Expr>()?);
+        } else if meta.path.is_ident("axis") {
+            let ident = meta.value()?.parse::<Ident>()?;
+            axis = Some(match ident.to_string().as_str() {
+                "x" => SliderAxis::X,
+                "y" => SliderAxis::Y,
+                "z" => SliderAxis::Z,
+                "xyz" => SliderAxis::Xyz,
+                _ => return Err(Error::new(ident.span(), "expected `x`, `y`, `z` or `xyz`")),
+            });
+        } else if meta.path.is_ident("sensitivity") {
+            sensitivity = Some(meta.value()?.parse::<Expr>()?);
+        } else if meta.path.is_ident("min") {
+            min = Some(meta.value()?.parse::<Expr>()?);
+        } else if meta.path.is_ident("max") {
+            max = Some(meta.value()?.parse::<Expr>()?);
+        } else {
+            return Err(meta.error("expected `slider`, `axis`, `sensitivity`, `min` or `max`"));
+        }
+        Ok(())
+    })?;
+
+    let span = attr.span();
+    let axis = axis.ok_or_else(|| Error::new(span, "missing `axis` for field slider"))?;
+    if matches!(axis, SliderAxis::Xyz) && (min.is_some() || max.is_some()) {
+        return Err(Error::new(
+            span,
+            "`min`/`max` are not supported with `axis = xyz`",
+        ));
+    }
+
+    Ok(FieldSliderAttr {
+        field,
+        slider: slider.ok_or_else(|| Error::new(span, "missing `slider` for field slider"))?,
+        axis,
+        sensitivity,
+        min,
+        max,
+    })
+}
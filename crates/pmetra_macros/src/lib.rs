@@ -0,0 +1,607 @@
+//! Derive macros for [`bevy_pmetra`](https://github.com/nilaysavant/bevy_pmetra).
+//!
+//! Generates `PmetraCad`, `PmetraModelling` and `PmetraInteractions` impls
+//! from `#[pmetra(...)]` attributes on the params struct and its fields:
+//!
+//! ```ignore
+//! #[derive(Debug, Default, Clone, Component, PmetraCad, PmetraModelling, PmetraInteractions)]
+//! #[pmetra(shell(name = "Cube", builder = build_cube_shell, key = cube_shell_key))]
+//! #[pmetra(mesh(name = "Cube", shell = "Cube", builder = build_cube_mesh, outlines))]
+//! #[pmetra(slider(name = "SideLength", builder = build_side_length_slider))]
+//! pub struct SimpleCube {
+//!     #[pmetra(slider = "SideLength", axis = x, min = 0.1)]
+//!     pub side_length: f64,
+//! }
+//! ```
+//!
+//! Builder fn signatures:
+//! - shell: `fn(&Params) -> Result<CadShell>`
+//! - shell `key` (optional): `fn(&Params) -> impl Hash`, refer `CadShellsBuilders::set_shell_key`
+//! - mesh: `fn(&Params, CadShellName) -> Result<CadMeshBuilder<Params>>`
+//! - slider: `fn(&Params, &CadShellsByName) -> Result<CadSlider>`
+//! - slider `on_transform` (optional): `fn(&mut Params, Transform, Transform)`
+//! - slider `tooltip` (optional): `fn(&Params) -> Result<Option<String>>`
+//! - `validate` (optional, eg: `#[pmetra(validate = validate_params)]`): `fn(&Params) -> Result<(), Vec<ParamIssue>>`
+//!
+//! Add `#[pmetra(cache_key)]` to key the tessellation cache by all the fields of the params,
+//! via `PmetraCad::params_key` (the field types need to implement `ParamsKey`).
+//!
+//! Names can be any expression implementing `ToString` (eg: string literals or strum enum variants).
+//!
+//! The generated code refers to the traits via `::bevy_pmetra`. When depending on `pmetra_internal`
+//! directly, set the crate path via `#[pmetra(crate = ::pmetra_internal)]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Index, Member, Result};
+
+use crate::attrs::{FieldSliderAttr, PmetraAttrs, SliderAttr, SliderAxis};
+
+mod attrs;
+
+/// Derive `PmetraCad` from `#[pmetra(shell(...))]` (and optional `#[pmetra(validate = ...)]`,
+/// `#[pmetra(cache_key)]`) attributes.
+#[proc_macro_derive(PmetraCad, attributes(pmetra))]
+pub fn derive_pmetra_cad(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_pmetra_cad(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derive `PmetraModelling` from `#[pmetra(mesh(...))]` attributes.
+#[proc_macro_derive(PmetraModelling, attributes(pmetra))]
+pub fn derive_pmetra_modelling(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_pmetra_modelling(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derive `PmetraInteractions` from `#[pmetra(slider(...))]` attributes
+/// on the struct and `#[pmetra(slider = ...)]` attributes on its fields.
+#[proc_macro_derive(PmetraInteractions, attributes(pmetra))]
+pub fn derive_pmetra_interactions(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_pmetra_interactions(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_pmetra_cad(input: &DeriveInput) -> Result<TokenStream2> {
+    let attrs = PmetraAttrs::parse(input)?;
+    let krate = attrs.crate_path();
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let add_shell_builders = attrs.shells.iter().map(|shell| {
+        let name = &shell.name;
+        let builder = &shell.builder;
+        let set_shell_key = shell.key.as_ref().map(|key| {
+            quote! {
+                .set_shell_key(
+                    #krate::prelude::CadShellName((#name).to_string()),
+                    #key,
+                )?
+            }
+        });
+        quote! {
+            .add_shell_builder(
+                #krate::prelude::CadShellName((#name).to_string()),
+                #builder,
+            )?
+            #set_shell_key
+        }
+    });
+
+    let validate = attrs.validate.as_ref().map(|validate| {
+        quote! {
+            fn validate(
+                &self,
+            ) -> ::core::result::Result<(), ::std::vec::Vec<#krate::prelude::ParamIssue>> {
+                #validate(self)
+            }
+        }
+    });
+
+    let params_key = attrs.cache_key.then(|| {
+        let members = struct_members(input);
+        quote! {
+            fn params_key(&self) -> ::core::option::Option<u64> {
+                let mut hasher = ::std::hash::DefaultHasher::new();
+                #(#krate::prelude::ParamsKey::hash_key(&self.#members, &mut hasher);)*
+                ::core::option::Option::Some(::std::hash::Hasher::finish(&hasher))
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #krate::prelude::PmetraCad for #ident #ty_generics #where_clause {
+            fn shells_builders(
+                &self,
+            ) -> #krate::re_exports::anyhow::Result<#krate::prelude::CadShellsBuilders<Self>> {
+                let builders = #krate::prelude::CadShellsBuilders::new(
+                    ::core::clone::Clone::clone(self),
+                )?
+                #(#add_shell_builders)*;
+
+                ::core::result::Result::Ok(builders)
+            }
+
+            #params_key
+
+            #validate
+        }
+    })
+}
+
+fn expand_pmetra_modelling(input: &DeriveInput) -> Result<TokenStream2> {
+    let attrs = PmetraAttrs::parse(input)?;
+    let krate = attrs.crate_path();
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let add_mesh_builders = attrs.meshes.iter().map(|mesh| {
+        let name = &mesh.name;
+        let shell = &mesh.shell;
+        let builder = &mesh.builder;
+        let add_fn = if mesh.outlines {
+            format_ident!("add_mesh_builder_with_outlines")
+        } else {
+            format_ident!("add_mesh_builder")
+        };
+        quote! {
+            .#add_fn(
+                #krate::prelude::CadShellName((#shell).to_string()),
+                (#name).to_string(),
+                #builder(self, #krate::prelude::CadShellName((#shell).to_string()))?,
+            )?
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #krate::prelude::PmetraModelling for #ident #ty_generics #where_clause {
+            fn meshes_builders_by_shell(
+                &self,
+                shells_by_name: &#krate::prelude::CadShellsByName,
+            ) -> #krate::re_exports::anyhow::Result<
+                #krate::prelude::CadMeshesBuildersByCadShell<Self>,
+            > {
+                let cad_meshes_builders_by_cad_shell =
+                    #krate::prelude::CadMeshesBuildersByCadShell::new(
+                        ::core::clone::Clone::clone(self),
+                        ::core::clone::Clone::clone(shells_by_name),
+                    )?
+                    #(#add_mesh_builders)*;
+
+                ::core::result::Result::Ok(cad_meshes_builders_by_cad_shell)
+            }
+        }
+    })
+}
+
+fn expand_pmetra_interactions(input: &DeriveInput) -> Result<TokenStream2> {
+    let attrs = PmetraAttrs::parse(input)?;
+    let krate = attrs.crate_path();
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut add_sliders = vec![];
+    let mut transform_arms = vec![];
+    let mut tooltip_arms = vec![];
+    for slider in attrs.sliders.iter() {
+        let name = &slider.name;
+        let builder = &slider.builder;
+        add_sliders.push(quote! {
+            .add_slider(
+                #krate::prelude::CadSliderName((#name).to_string()),
+                #builder(self, shells_by_name)?,
+            )?
+        });
+
+        let field_slider = find_field_slider(slider, &attrs.field_sliders)?;
+        let (transform_body, tooltip_body) = match (&slider.on_transform, field_slider) {
+            (Some(on_transform), None) => {
+                let tooltip_body = match &slider.tooltip {
+                    Some(tooltip) => quote! { #tooltip(self) },
+                    None => quote! { ::core::result::Result::Ok(::core::option::Option::None) },
+                };
+                (
+                    quote! { #on_transform(self, prev_transform, new_transform); },
+                    tooltip_body,
+                )
+            }
+            (None, Some(field_slider)) => {
+                let tooltip_body = match &slider.tooltip {
+                    Some(tooltip) => quote! { #tooltip(self) },
+                    None => field_slider_tooltip(field_slider),
+                };
+                (field_slider_transform(field_slider), tooltip_body)
+            }
+            (Some(_), Some(_)) => {
+                return Err(Error::new_spanned(
+                    name,
+                    "slider has both `on_transform` and a field binding",
+                ))
+            }
+            (None, None) => {
+                return Err(Error::new_spanned(
+                    name,
+                    "slider needs `on_transform` or a bound field",
+                ))
+            }
+        };
+        transform_arms.push(quote! {
+            let slider_name = (#name).to_string();
+            if name.0 == slider_name {
+                #transform_body
+                return;
+            }
+        });
+        tooltip_arms.push(quote! {
+            let slider_name = (#name).to_string();
+            if name.0 == slider_name {
+                return #tooltip_body;
+            }
+        });
+    }
+
+    // Every field binding must point to a declared slider.
+    for field_slider in attrs.field_sliders.iter() {
+        let slider_name = &field_slider.slider;
+        let declared = attrs
+            .sliders
+            .iter()
+            .any(|slider| expr_eq(&slider.name, slider_name));
+        if !declared {
+            return Err(Error::new_spanned(
+                slider_name,
+                "no `#[pmetra(slider(name = ...))]` declared on the struct for this slider",
+            ));
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics #krate::prelude::PmetraInteractions for #ident #ty_generics #where_clause {
+            fn sliders(
+                &self,
+                shells_by_name: &#krate::prelude::CadShellsByName,
+            ) -> #krate::re_exports::anyhow::Result<#krate::prelude::CadSliders> {
+                let sliders = <#krate::prelude::CadSliders as ::core::default::Default>::default()
+                    #(#add_sliders)*;
+
+                ::core::result::Result::Ok(sliders)
+            }
+
+            fn on_slider_transform(
+                &mut self,
+                name: #krate::prelude::CadSliderName,
+                prev_transform: #krate::re_exports::bevy::prelude::Transform,
+                new_transform: #krate::re_exports::bevy::prelude::Transform,
+            ) {
+                #(#transform_arms)*
+                let _ = (name, prev_transform, new_transform);
+            }
+
+            fn on_slider_tooltip(
+                &self,
+                name: #krate::prelude::CadSliderName,
+            ) -> #krate::re_exports::anyhow::Result<::core::option::Option<::std::string::String>> {
+                #(#tooltip_arms)*
+                let _ = name;
+                ::core::result::Result::Ok(::core::option::Option::None)
+            }
+        }
+    })
+}
+
+/// Members (named or indexed fields) of the struct.
+fn struct_members(input: &DeriveInput) -> Vec<Member> {
+    let Data::Struct(data) = &input.data else {
+        return vec![];
+    };
+    data.fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(idx)),
+        })
+        .collect()
+}
+
+/// Find the field bound to the slider, if any. Errors if multiple fields are bound.
+fn find_field_slider<'a>(
+    slider: &SliderAttr,
+    field_sliders: &'a [FieldSliderAttr],
+) -> Result<Option<&'a FieldSliderAttr>> {
+    let mut found = field_sliders
+        .iter()
+        .filter(|field_slider| expr_eq(&field_slider.slider, &slider.name));
+    let first = found.next();
+    if let Some(second) = found.next() {
+        return Err(Error::new_spanned(
+            &second.field,
+            "slider is already bound to another field",
+        ));
+    }
+    Ok(first)
+}
+
+/// Generate the param update from the slider translation delta.
+fn field_slider_transform(field_slider: &FieldSliderAttr) -> TokenStream2 {
+    let field = &field_slider.field;
+    let sensitivity = field_slider
+        .sensitivity
+        .as_ref()
+        .map(|sensitivity| quote! { #sensitivity })
+        .unwrap_or_else(|| quote! { 1.0 });
+    let update = match field_slider.axis {
+        SliderAxis::Xyz => quote! {
+            self.#field += delta.as_dvec3() * sensitivity;
+        },
+        SliderAxis::X | SliderAxis::Y | SliderAxis::Z => {
+            let axis = match field_slider.axis {
+                SliderAxis::X => format_ident!("x"),
+                SliderAxis::Y => format_ident!("y"),
+                _ => format_ident!("z"),
+            };
+            let bounded = match (&field_slider.min, &field_slider.max) {
+                (Some(min), Some(max)) => quote! { new_value.clamp(#min, #max) },
+                (Some(min), None) => quote! { new_value.max(#min) },
+                (None, Some(max)) => quote! { new_value.min(#max) },
+                (None, None) => quote! { new_value },
+            };
+            quote! {
+                let new_value = self.#field + delta.#axis as f64 * sensitivity;
+                self.#field = #bounded;
+            }
+        }
+    };
+
+    quote! {
+        let delta = new_transform.translation - prev_transform.translation;
+        if delta.length() > 0. {
+            let sensitivity = #sensitivity;
+            #update
+        }
+    }
+}
+
+/// Generate the default tooltip showing the bound field value.
+fn field_slider_tooltip(field_slider: &FieldSliderAttr) -> TokenStream2 {
+    let field = &field_slider.field;
+    match field_slider.axis {
+        SliderAxis::Xyz => {
+            let format = format!("{} : [{{:.3}}, {{:.3}}, {{:.3}}]", field);
+            quote! {
+                ::core::result::Result::Ok(::core::option::Option::Some(::std::format!(
+                    #format,
+                    self.#field.x,
+                    self.#field.y,
+                    self.#field.z
+                )))
+            }
+        }
+        _ => {
+            let format = format!("{} : {{:.3}}", field);
+            quote! {
+                ::core::result::Result::Ok(::core::option::Option::Some(::std::format!(
+                    #format,
+                    self.#field
+                )))
+            }
+        }
+    }
+}
+
+/// Compare slider name expressions by their tokens.
+fn expr_eq(a: &syn::Expr, b: &syn::Expr) -> bool {
+    quote!(#a).to_string() == quote!(#b).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use syn::parse_quote;
+
+    /// Expanded tokens as a string with whitespace removed, for matching.
+    #[allow(dead_code)]
+    fn compact(tokens: TokenStream2) -> String {
+        tokens.to_string().split_whitespace().collect()
+    }
+
+    #[allow(dead_code)]
+    fn cube_input() -> DeriveInput {
+        parse_quote! {
+            #[pmetra(shell(name = "Cube", builder = build_cube_shell, key = cube_shell_key))]
+            #[pmetra(mesh(name = "Cube", shell = "Cube", builder = build_cube_mesh, outlines))]
+            #[pmetra(mesh(name = "CubeNoOutlines", shell = "Cube", builder = build_cube_mesh))]
+            #[pmetra(slider(name = "SideLength", builder = build_side_length_slider))]
+            #[pmetra(slider(name = "Offset", builder = build_offset_slider))]
+            #[pmetra(slider(name = "Custom", builder = build_custom_slider, on_transform = on_custom, tooltip = custom_tooltip))]
+            #[pmetra(validate = validate_cube, cache_key)]
+            pub struct Cube {
+                #[pmetra(slider = "SideLength", axis = x, sensitivity = 2.0, min = 0.1, max = 10.)]
+                pub side_length: f64,
+                #[pmetra(slider = "Offset", axis = xyz)]
+                pub offset: DVec3,
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_expand_pmetra_cad() {
+        let expanded = compact(expand_pmetra_cad(&cube_input()).unwrap());
+        assert!(expanded.contains("impl::bevy_pmetra::prelude::PmetraCadforCube"));
+        assert!(expanded.contains(".add_shell_builder(::bevy_pmetra::prelude::CadShellName((\"Cube\").to_string()),build_cube_shell,)?"));
+        assert!(expanded.contains(".set_shell_key(::bevy_pmetra::prelude::CadShellName((\"Cube\").to_string()),cube_shell_key,)?"));
+        assert!(expanded.contains("validate_cube(self)"));
+        for field in ["side_length", "offset"] {
+            assert!(expanded.contains(&format!(
+                "::bevy_pmetra::prelude::ParamsKey::hash_key(&self.{},&muthasher);",
+                field
+            )));
+        }
+        // Caching by params is opt-in...
+        let mut input = cube_input();
+        input
+            .attrs
+            .retain(|attr| !quote!(#attr).to_string().contains("cache_key"));
+        assert!(!compact(expand_pmetra_cad(&input).unwrap()).contains("fnparams_key"));
+    }
+
+    #[test]
+    pub fn test_expand_pmetra_modelling() {
+        let expanded = compact(expand_pmetra_modelling(&cube_input()).unwrap());
+        assert!(expanded.contains("impl::bevy_pmetra::prelude::PmetraModellingforCube"));
+        assert!(expanded.contains(".add_mesh_builder_with_outlines(::bevy_pmetra::prelude::CadShellName((\"Cube\").to_string()),(\"Cube\").to_string(),build_cube_mesh(self,"));
+        assert!(expanded.contains(".add_mesh_builder(::bevy_pmetra::prelude::CadShellName((\"Cube\").to_string()),(\"CubeNoOutlines\").to_string(),build_cube_mesh(self,"));
+    }
+
+    #[test]
+    pub fn test_expand_pmetra_interactions() {
+        let expanded = compact(expand_pmetra_interactions(&cube_input()).unwrap());
+        assert!(expanded.contains("impl::bevy_pmetra::prelude::PmetraInteractionsforCube"));
+        for builder in [
+            "build_side_length_slider",
+            "build_offset_slider",
+            "build_custom_slider",
+        ] {
+            assert!(expanded.contains(&format!("{}(self,shells_by_name)?", builder)));
+        }
+        // Field slider on an axis (with sensitivity and bounds)...
+        assert!(expanded.contains("letsensitivity=2.0;"));
+        assert!(expanded
+            .contains("letnew_value=self.side_length+delta.xasf64*sensitivity;self.side_length=new_value.clamp(0.1,10.);"));
+        assert!(expanded.contains("format!(\"side_length:{:.3}\",self.side_length)"));
+        // Field slider on all axes...
+        assert!(expanded.contains("self.offset+=delta.as_dvec3()*sensitivity;"));
+        // Custom handlers...
+        assert!(expanded.contains("on_custom(self,prev_transform,new_transform);"));
+        assert!(expanded.contains("returncustom_tooltip(self);"));
+    }
+
+    #[test]
+    pub fn test_expand_crate_path() {
+        let mut input = cube_input();
+        input
+            .attrs
+            .push(parse_quote!(#[pmetra(crate = ::pmetra_internal)]));
+        for expanded in [
+            expand_pmetra_cad(&input).unwrap(),
+            expand_pmetra_modelling(&input).unwrap(),
+            expand_pmetra_interactions(&input).unwrap(),
+        ] {
+            let expanded = compact(expanded);
+            assert!(expanded.contains("::pmetra_internal::prelude::"));
+            assert!(expanded.contains("::pmetra_internal::re_exports::anyhow::Result"));
+            assert!(!expanded.contains("bevy_pmetra"));
+            // Results are fully qualified (not relying on the prelude)...
+            assert!(expanded.contains("::core::result::Result::Ok("));
+            assert!(!expanded.replace("Result::Ok(", "").contains("Ok("));
+        }
+    }
+
+    #[test]
+    pub fn test_expand_errors() {
+        let error = |input: DeriveInput| {
+            expand_pmetra_cad(&input)
+                .and_then(|_| expand_pmetra_modelling(&input))
+                .and_then(|_| expand_pmetra_interactions(&input))
+                .err()
+                .map(|e| e.to_string())
+        };
+        assert_eq!(
+            error(parse_quote! {
+                #[pmetra(shell(name = "Cube"))]
+                struct Cube;
+            }),
+            Some("missing `builder` for shell".into())
+        );
+        assert_eq!(
+            error(parse_quote! {
+                #[pmetra(mesh(name = "Cube", builder = build_cube_mesh))]
+                struct Cube;
+            }),
+            Some("missing `shell` for mesh".into())
+        );
+        assert_eq!(
+            error(parse_quote! {
+                #[pmetra(shape(name = "Cube"))]
+                struct Cube;
+            }),
+            Some(
+                "expected `shell(..)`, `mesh(..)`, `slider(..)`, `validate = ..`, `cache_key` or `crate = ..`"
+                    .into()
+            )
+        );
+        assert_eq!(
+            error(parse_quote! {
+                enum Cube {}
+            }),
+            Some("Pmetra derives are only supported on structs".into())
+        );
+        assert_eq!(
+            error(parse_quote! {
+                #[pmetra(slider(name = "Side", builder = build_slider))]
+                struct Cube;
+            }),
+            Some("slider needs `on_transform` or a bound field".into())
+        );
+        assert_eq!(
+            error(parse_quote! {
+                #[pmetra(slider(name = "Side", builder = build_slider, on_transform = on_side))]
+                struct Cube {
+                    #[pmetra(slider = "Side", axis = x)]
+                    side: f64,
+                }
+            }),
+            Some("slider has both `on_transform` and a field binding".into())
+        );
+        assert_eq!(
+            error(parse_quote! {
+                #[pmetra(slider(name = "Side", builder = build_slider))]
+                struct Cube {
+                    #[pmetra(slider = "Side", axis = x)]
+                    side: f64,
+                    #[pmetra(slider = "Side", axis = y)]
+                    other_side: f64,
+                }
+            }),
+            Some("slider is already bound to another field".into())
+        );
+        assert_eq!(
+            error(parse_quote! {
+                struct Cube {
+                    #[pmetra(slider = "Side", axis = x)]
+                    side: f64,
+                }
+            }),
+            Some(
+                "no `#[pmetra(slider(name = ...))]` declared on the struct for this slider".into()
+            )
+        );
+        assert_eq!(
+            error(parse_quote! {
+                struct Cube {
+                    #[pmetra(slider = "Side", axis = w)]
+                    side: f64,
+                }
+            }),
+            Some("expected `x`, `y`, `z` or `xyz`".into())
+        );
+        assert_eq!(
+            error(parse_quote! {
+                struct Cube {
+                    #[pmetra(slider = "Side", axis = xyz, min = 0.)]
+                    side: DVec3,
+                }
+            }),
+            Some("`min`/`max` are not supported with `axis = xyz`".into())
+        );
+    }
+}
@@ -127,21 +127,20 @@ impl PmetraModelling for SimpleCube {
         &self,
         shells_by_name: &CadShellsByName,
     ) -> Result<CadMeshesBuildersByCadShell<Self>> {
-        let mut meshes_builders_by_shell =
-            CadMeshesBuildersByCadShell::new(self.clone(), shells_by_name.clone())?;
         let shell_name = CadShellName("SimpleCube".into());
-        for i in 0..self.array_count {
-            meshes_builders_by_shell.add_mesh_builder_with_outlines(
+        // All the cubes share the same mesh (tessellated once)...
+        CadMeshesBuildersByCadShell::new(self.clone(), shells_by_name.clone())?
+            .add_mesh_builder_pattern_with_outlines(
                 shell_name.clone(),
-                "SimpleCube".to_string() + &i.to_string(),
-                CadMeshBuilder::new(self.clone(), shell_name.clone())? // builder
-                    .set_transform(Transform::from_translation(
-                        Vec3::X * (i as f32 * (self.side_length as f32 * 1.5)),
-                    ))?
+                "SimpleCube".to_string(),
+                CadMeshBuilder::new(self.clone(), shell_name)? // builder
                     .set_base_material(Color::from(css::RED).into())?,
-            )?;
-        }
-        Ok(meshes_builders_by_shell)
+                &CadMeshPattern::linear(
+                    Vec3::X,
+                    self.side_length as f32 * 1.5,
+                    self.array_count as usize,
+                ),
+            )
     }
 }
 
@@ -189,6 +188,13 @@ impl PmetraInteractions for SimpleCube {
             Ok(None)
         }
     }
+
+    fn on_face_click(&mut self, hit: &CadFaceHit) -> bool {
+        if let Some(tag) = &hit.tag {
+            info!("Clicked face: {} at {}", tag.0, hit.position);
+        }
+        false
+    }
 }
 
 fn build_side_length_slider(
@@ -197,7 +203,7 @@ fn build_side_length_slider(
 ) -> Result<CadSlider> {
     let SimpleCube { side_length, .. } = &params;
     let cad_shell = shells_by_name
-        .get(&CadShellName("SimpleCube".to_string()))
+        .get(&CadShellName("SimpleCube".into()))
         .ok_or_else(|| anyhow!("Could not get cube shell!"))?;
     let Some(CadElement::Face(face)) =
         cad_shell.get_element_by_tag(CadElementTag::new("ProfileFace"))
@@ -232,7 +238,7 @@ fn build_array_count_slider(
         array_count,
     } = &params;
     let cad_shell = shells_by_name
-        .get(&CadShellName("SimpleCube".to_string()))
+        .get(&CadShellName("SimpleCube".into()))
         .ok_or_else(|| anyhow!("Could not get cube shell!"))?;
     let Some(CadElement::Face(face)) =
         cad_shell.get_element_by_tag(CadElementTag::new("ProfileFace"))
@@ -0,0 +1,522 @@
+//! Build the demo models headlessly from params files, writing glb/STL/STEP outputs.
+//!
+//! Run `cargo run -p pmetra_demo --bin pmetra-cli -- --help` for usage.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Instant,
+};
+
+use bevy::{
+    mesh::{Indices, VertexAttributeValues},
+    prelude::*,
+};
+use bevy_pmetra::{
+    pmetra_plugins::document::params_type_name,
+    prelude::*,
+    re_exports::{
+        anyhow::{anyhow, Context, Result},
+        serde::{de::DeserializeOwned, Serialize},
+    },
+};
+use pmetra_demo::utils::cad_models::{
+    simple_primitives::{exp_nurbs::ExpNurbs, simple_cube_at_cylinder::SimpleCubeAtCylinder},
+    space_station::{round_cabin_segment::RoundCabinSegment, tower_extension::TowerExtension},
+};
+
+const USAGE: &str = "\
+Build the demo models headlessly from params files.
+
+Usage: pmetra-cli [OPTIONS] <INPUT>...
+
+Arguments:
+  <INPUT>...  Params file(s) (.ron/.json) of the --model type.
+              Or document file(s) (as saved by the demo) if --model is not set.
+
+Options:
+  -m, --model <TYPE>     Model (params) type of the params files, eg: TowerExtension.
+  -f, --format <FORMAT>  Output format: glb, stl or step. Can be repeated. [default: glb]
+  -o, --out-dir <DIR>    Output directory. [default: exports/cli]
+  -h, --help             Print help.";
+
+/// Output formats of the built models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Glb,
+    Stl,
+    Step,
+}
+
+impl OutputFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Glb => "glb",
+            Self::Stl => "stl",
+            Self::Step => "step",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Args {
+    model: Option<String>,
+    formats: Vec<OutputFormat>,
+    out_dir: PathBuf,
+    inputs: Vec<PathBuf>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>> {
+        let mut parsed = Self {
+            model: None,
+            formats: vec![],
+            out_dir: PathBuf::from("exports/cli"),
+            inputs: vec![],
+        };
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| anyhow!("Missing value for {}!", name))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "-m" | "--model" => parsed.model = Some(value(&arg)?),
+                "-o" | "--out-dir" => parsed.out_dir = PathBuf::from(value(&arg)?),
+                "-f" | "--format" => parsed.formats.push(match value(&arg)?.as_str() {
+                    "glb" => OutputFormat::Glb,
+                    "stl" => OutputFormat::Stl,
+                    "step" => OutputFormat::Step,
+                    format => return Err(anyhow!("Unknown format: {}", format)),
+                }),
+                arg if arg.starts_with('-') => return Err(anyhow!("Unknown option: {}", arg)),
+                _ => parsed.inputs.push(PathBuf::from(&arg)),
+            }
+        }
+        if parsed.inputs.is_empty() {
+            return Err(anyhow!("No input files!"));
+        }
+        if parsed.formats.is_empty() {
+            parsed.formats.push(OutputFormat::Glb);
+        }
+        Ok(Some(parsed))
+    }
+}
+
+/// Functions to parse/build the models of a params type.
+#[derive(Clone, Copy)]
+struct ModelType {
+    /// Full type path, as saved in the documents. Refer [`params_type_name`].
+    name: &'static str,
+    /// Type name without the module path, for `--model`.
+    short_name: &'static str,
+    parse_params: fn(&str, CadDocumentFormat) -> Result<CadDocumentEntry>,
+    build: fn(&CadDocumentEntry) -> Result<CadModel>,
+}
+
+impl ModelType {
+    fn new<Params: PmetraModelling + TypePath + Serialize + DeserializeOwned>() -> Self {
+        Self {
+            name: params_type_name::<Params>(),
+            short_name: Params::short_type_path(),
+            parse_params: parse_params::<Params>,
+            build: build_entry::<Params>,
+        }
+    }
+}
+
+/// Model types that can be built by the cli.
+fn model_types() -> [ModelType; 4] {
+    [
+        ModelType::new::<SimpleCubeAtCylinder>(),
+        ModelType::new::<TowerExtension>(),
+        ModelType::new::<RoundCabinSegment>(),
+        ModelType::new::<ExpNurbs>(),
+    ]
+}
+
+/// Get the model type by its full or short name.
+fn model_type(name: &str) -> Result<ModelType> {
+    model_types()
+        .into_iter()
+        .find(|model_type| model_type.name == name || model_type.short_name == name)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown model type: {} (available: {})",
+                name,
+                model_types()
+                    .map(|model_type| model_type.short_name)
+                    .join(", ")
+            )
+        })
+}
+
+fn parse_params<Params: TypePath + Serialize + DeserializeOwned>(
+    text: &str,
+    format: CadDocumentFormat,
+) -> Result<CadDocumentEntry> {
+    let params: Params = match format {
+        CadDocumentFormat::Ron => bevy_pmetra::re_exports::ron::from_str(text)?,
+        CadDocumentFormat::Json => bevy_pmetra::re_exports::serde_json::from_str(text)?,
+    };
+    let document = CadDocument::default().add_params(&params, Transform::default())?;
+    document
+        .entries
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Could not create document entry!"))
+}
+
+fn build_entry<Params: PmetraModelling + DeserializeOwned>(
+    entry: &CadDocumentEntry,
+) -> Result<CadModel> {
+    let params: Params = bevy_pmetra::re_exports::serde_json::from_value(entry.params.clone())
+        .with_context(|| "Could not deserialize params!")?;
+    build_model(&params)
+}
+
+/// Load the entries of the input file.
+fn load_entries(input: &Path, model: Option<&str>) -> Result<Vec<CadDocumentEntry>> {
+    match model {
+        Some(model) => {
+            let text = fs::read_to_string(input)
+                .with_context(|| format!("Could not read params: {:?}", input))?;
+            let entry =
+                (model_type(model)?.parse_params)(&text, CadDocumentFormat::from_path(input)?)?;
+            Ok(vec![entry])
+        }
+        None => Ok(CadDocument::load(input)?.entries),
+    }
+}
+
+/// Build the entry and write the outputs to `{out_dir}/{stem}.{ext}`.
+fn build_and_write(
+    entry: &CadDocumentEntry,
+    out_dir: &Path,
+    stem: &str,
+    formats: &[OutputFormat],
+) -> Result<()> {
+    let start = Instant::now();
+    let CadModel {
+        shells_by_name,
+        meshes,
+    } = (model_type(&entry.params_type)?.build)(entry)?;
+    print_stats(&entry.params_type, &meshes, start.elapsed().as_millis());
+
+    for format in formats {
+        let path = out_dir.join(format!("{}.{}", stem, format.extension()));
+        match format {
+            OutputFormat::Glb => {
+                let mut builder = CadGltfBuilder::default();
+                let root = builder.add_node(&entry.params_type, entry.transform, None, None)?;
+                builder.set_node_document_entry(root, entry.clone())?;
+                builder.add_model_meshes(&meshes, Some(root))?;
+                fs::write(&path, builder.build_glb()?)?;
+            }
+            OutputFormat::Stl => fs::write(&path, build_stl(&meshes, entry.transform)?)?,
+            OutputFormat::Step => fs::write(
+                &path,
+                build_step(&shells_by_name, &meshes, entry.transform)?,
+            )?,
+        }
+        println!("  -> {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn print_stats(params_type: &str, meshes: &[CadModelMesh], build_millis: u128) {
+    println!("{}:", params_type);
+    let (mut total_vertices, mut total_triangles) = (0, 0);
+    for model_mesh in meshes {
+        let (vertices, triangles) = mesh_stats(&model_mesh.mesh);
+        let (face_vertices, face_triangles) = model_mesh
+            .face_meshes
+            .values()
+            .map(|(face_mesh, _)| mesh_stats(face_mesh))
+            .fold((0, 0), |(v, t), (fv, ft)| (v + fv, t + ft));
+        println!(
+            "  {}: {} vertices, {} triangles ({} face meshes)",
+            model_mesh.name.0,
+            vertices + face_vertices,
+            triangles + face_triangles,
+            model_mesh.face_meshes.len()
+        );
+        total_vertices += vertices + face_vertices;
+        total_triangles += triangles + face_triangles;
+    }
+    println!(
+        "  total: {} meshes, {} vertices, {} triangles (built in {} ms)",
+        meshes.len(),
+        total_vertices,
+        total_triangles,
+        build_millis
+    );
+}
+
+/// Vertices and triangles count of the mesh.
+fn mesh_stats(mesh: &Mesh) -> (usize, usize) {
+    let vertices = mesh.count_vertices();
+    let triangles = mesh.indices().map_or(vertices, |indices| indices.len()) / 3;
+    (vertices, triangles)
+}
+
+/// Build the STEP file content of the shells as placed by the meshes (like the glb and stl outputs).
+fn build_step(
+    shells_by_name: &CadShellsByName,
+    meshes: &[CadModelMesh],
+    transform: Transform,
+) -> Result<String> {
+    let instances = meshes.iter().map(|model_mesh| {
+        (
+            model_mesh.name.0.as_str(),
+            &model_mesh.shell_name,
+            model_mesh.transform,
+        )
+    });
+    shells_by_name.to_step_instances(instances, transform)
+}
+
+/// Build binary STL of the meshes (incl face meshes), with the (root) `transform` applied.
+fn build_stl(meshes: &[CadModelMesh], transform: Transform) -> Result<Vec<u8>> {
+    let mut triangles = vec![];
+    for model_mesh in meshes {
+        let global_transform = transform * model_mesh.transform;
+        let face_meshes = model_mesh.face_meshes.values().map(|(mesh, _)| mesh);
+        for mesh in std::iter::once(&model_mesh.mesh).chain(face_meshes) {
+            let Some(VertexAttributeValues::Float32x3(positions)) =
+                mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                return Err(anyhow!("Could not get Float32x3 for ATTRIBUTE_POSITION!"));
+            };
+            let indices: Vec<usize> = match mesh.indices() {
+                Some(Indices::U16(indices)) => indices.iter().map(|i| *i as usize).collect(),
+                Some(Indices::U32(indices)) => indices.iter().map(|i| *i as usize).collect(),
+                None => (0..positions.len()).collect(),
+            };
+            for triangle in indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]]
+                    .map(|idx| global_transform.transform_point(Vec3::from_array(positions[idx])));
+                triangles.push([(b - a).cross(c - a).normalize_or_zero(), a, b, c]);
+            }
+        }
+    }
+
+    let mut stl = vec![0u8; 80];
+    stl.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+    for triangle in triangles {
+        for vector in triangle {
+            for component in vector.to_array() {
+                stl.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        stl.extend_from_slice(&0u16.to_le_bytes());
+    }
+    Ok(stl)
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {:#}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = fs::create_dir_all(&args.out_dir) {
+        eprintln!("error: Could not create output dir: {:#}", e);
+        return ExitCode::FAILURE;
+    }
+
+    // Build all the inputs, reporting the errors at the end...
+    let mut errors = vec![];
+    for input in args.inputs.iter() {
+        let input_stem = input
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "model".to_string());
+        let entries = match load_entries(input, args.model.as_deref()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(format!("{}: {:#}", input.display(), e));
+                continue;
+            }
+        };
+        for (idx, entry) in entries.iter().enumerate() {
+            let stem = if entries.len() == 1 {
+                input_stem.clone()
+            } else {
+                format!("{}_{}", input_stem, idx)
+            };
+            if let Err(e) = build_and_write(entry, &args.out_dir, &stem, &args.formats) {
+                errors.push(format!("{} ({}): {:#}", input.display(), stem, e));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+    eprintln!("\n{} build error(s):", errors.len());
+    for error in errors {
+        eprintln!("  {}", error);
+    }
+    ExitCode::FAILURE
+}
+
+#[cfg(test)]
+mod test {
+
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn parse(args: &[&str]) -> Result<Option<Args>> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    pub fn test_args_parse() {
+        let args = parse(&[
+            "-m",
+            "TowerExtension",
+            "-f",
+            "stl",
+            "--format",
+            "step",
+            "--out-dir",
+            "out",
+            "a.ron",
+            "b.json",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(args.model.as_deref(), Some("TowerExtension"));
+        assert_eq!(args.formats, vec![OutputFormat::Stl, OutputFormat::Step]);
+        assert_eq!(args.out_dir, PathBuf::from("out"));
+        assert_eq!(
+            args.inputs,
+            vec![PathBuf::from("a.ron"), PathBuf::from("b.json")]
+        );
+
+        // Defaults...
+        let args = parse(&["doc.ron"]).unwrap().unwrap();
+        assert_eq!(args.model, None);
+        assert_eq!(args.formats, vec![OutputFormat::Glb]);
+        assert_eq!(args.out_dir, PathBuf::from("exports/cli"));
+
+        assert!(parse(&["a.ron", "--help"]).unwrap().is_none());
+
+        for (args, error) in [
+            (&[][..], "No input files!"),
+            (&["-f", "obj", "a.ron"][..], "Unknown format: obj"),
+            (&["--verbose", "a.ron"][..], "Unknown option: --verbose"),
+            (&["a.ron", "-m"][..], "Missing value for -m!"),
+        ] {
+            assert_eq!(parse(args).unwrap_err().to_string(), error);
+        }
+    }
+
+    #[test]
+    pub fn test_model_type() {
+        // By the full or short name...
+        let model = model_type("TowerExtension").unwrap();
+        assert_eq!(model.name, params_type_name::<TowerExtension>());
+        assert_eq!(model_type(model.name).unwrap().short_name, "TowerExtension");
+        assert!(model_type("Tower").is_err());
+    }
+
+    #[test]
+    pub fn test_build_stl() {
+        let mut model_mesh = CadModelMesh {
+            name: CadMeshName("Cube".into()),
+            shell_name: CadShellName("Cube".into()),
+            mesh: Mesh::from(Cuboid::default()),
+            transform: Transform::from_xyz(1., 0., 0.),
+            material: StandardMaterial::default(),
+            outlines: default(),
+            faces: default(),
+            face_meshes: default(),
+            pattern_name: None,
+            lods: vec![],
+        };
+        model_mesh.face_meshes.insert(
+            CadElementTag::new("Face"),
+            (Mesh::from(Cuboid::default()), StandardMaterial::default()),
+        );
+        let stl = build_stl(&[model_mesh], Transform::from_xyz(0., 2., 0.)).unwrap();
+
+        // 80 bytes header, triangles count, then 50 bytes per triangle...
+        let triangles_count = u32::from_le_bytes(stl[80..84].try_into().unwrap()) as usize;
+        assert_eq!(triangles_count, 24);
+        assert_eq!(stl.len(), 84 + 50 * triangles_count);
+        let read_vec3 = |offset: usize| {
+            Vec3::from_array(std::array::from_fn(|idx| {
+                let start = offset + idx * 4;
+                f32::from_le_bytes(stl[start..start + 4].try_into().unwrap())
+            }))
+        };
+        for triangle in 0..triangles_count {
+            let offset = 84 + 50 * triangle;
+            let normal = read_vec3(offset);
+            assert!((normal.length() - 1.).abs() < 1e-5);
+            let [a, b, c] = [1, 2, 3].map(|idx| read_vec3(offset + 12 * idx));
+            // Normal matches the winding...
+            assert!(normal.dot((b - a).cross(c - a)) > 0.);
+            // Root and mesh transforms are applied (unit cube centered at (1, 2, 0))...
+            for vertex in [a, b, c] {
+                assert!((vertex - Vec3::new(1., 2., 0.)).abs().max_element() <= 0.5 + 1e-5);
+            }
+            // Attribute byte count...
+            assert_eq!(&stl[offset + 48..offset + 50], &[0, 0]);
+        }
+    }
+
+    #[test]
+    pub fn test_build_step() {
+        let CadModel {
+            shells_by_name,
+            meshes,
+        } = build_model(&TowerExtension::default()).unwrap();
+        let transform = Transform::from_xyz(0., 2., 0.);
+        let step = build_step(&shells_by_name, &meshes, transform).unwrap();
+        let shells = CadShell::from_step(&step, 1e-3).unwrap();
+        assert_eq!(shells.len(), meshes.len());
+
+        let min_vertex = |cad_shell: &CadShell, transform: Transform| {
+            cad_shell
+                .shell
+                .vertex_iter()
+                .map(|vertex| {
+                    let point = vertex.point();
+                    transform.transform_point(Vec3::new(
+                        point.x as f32,
+                        point.y as f32,
+                        point.z as f32,
+                    ))
+                })
+                .reduce(Vec3::min)
+                .unwrap()
+        };
+        // Root and mesh transforms are applied (eg: beams are not all at the origin)...
+        for (cad_shell, model_mesh) in shells.iter().zip(meshes.iter()) {
+            let expected = min_vertex(
+                shells_by_name.get(&model_mesh.shell_name).unwrap(),
+                transform * model_mesh.transform,
+            );
+            let min = min_vertex(cad_shell, Transform::IDENTITY);
+            assert!(
+                (min - expected).abs().max_element() < 1e-3,
+                "{}: {} != {}",
+                model_mesh.name.0,
+                min,
+                expected
+            );
+        }
+    }
+}
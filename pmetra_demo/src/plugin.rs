@@ -6,10 +6,13 @@ use bevy_rapier3d::prelude::*;
 use bevy_pmetra::{pmetra_plugins::resources::MeshesBuilderQueueInspector, prelude::*};
 use smooth_bevy_cameras::{controllers::orbit::OrbitCameraPlugin, LookTransformPlugin};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::systems::cad::{
+    export_cad_model_gltf, export_cad_model_step, import_cad_model_gltf, save_load_cad_document,
+};
 use crate::{
     plugins::{
         fps_display::FpsDisplayPlugin,
-        gltf_exporter::plugin::GltfExporterPlugin,
         my_bevy_defaults::{MyBevyCustomLogPlugin, MyBevyDefaultPluginsConfig},
         origin_axis_gizmo::OriginAxisGizmoPlugin,
     },
@@ -104,6 +107,13 @@ impl Plugin for PmetraDemoPlugin {
                 PmetraModellingPlugin::<ExpNurbs>::default(),
                 PmetraInteractionsPlugin::<ExpNurbs>::default(),
             ))
+            // save/load documents...
+            .add_plugins((
+                PmetraDocumentPlugin::<SimpleCubeAtCylinder>::default(),
+                PmetraDocumentPlugin::<TowerExtension>::default(),
+                PmetraDocumentPlugin::<RoundCabinSegment>::default(),
+                PmetraDocumentPlugin::<ExpNurbs>::default(),
+            ))
             .init_resource::<CadGeneratedModelSpawner>()
             .register_type::<CadGeneratedModelSpawner>()
             .add_plugins(ResourceInspectorPlugin::<CadGeneratedModelSpawner>::default())
@@ -113,8 +123,6 @@ impl Plugin for PmetraDemoPlugin {
             .add_systems(Startup, scene_setup)
             // info...
             .add_systems(Update, (setup_info_ui, update_info_ui))
-            // exporter...
-            .add_plugins(GltfExporterPlugin)
             // inspectors...
             .register_type::<ExpNurbs>()
             .register_type::<SimpleCubeAtCylinder>()
@@ -136,5 +144,17 @@ impl Plugin for PmetraDemoPlugin {
             .add_plugins(ResourceInspectorPlugin::<PmetraGlobalSettings>::default())
             // rest...
             .add_systems(Startup, || info!("TruckIntegrationTestPlugin started!"));
+
+        // Save/Load documents and STEP/glTF export only in native (needs file system)...
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(
+            Update,
+            (
+                save_load_cad_document,
+                export_cad_model_step,
+                export_cad_model_gltf,
+                import_cad_model_gltf,
+            ),
+        );
     }
 }
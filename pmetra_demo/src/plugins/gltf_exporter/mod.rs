@@ -1,2 +0,0 @@
-pub mod gltf;
-pub mod plugin;
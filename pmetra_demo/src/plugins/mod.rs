@@ -1,4 +1,3 @@
 pub mod fps_display;
-pub mod gltf_exporter;
 pub mod my_bevy_defaults;
 pub mod origin_axis_gizmo;
@@ -1,4 +1,11 @@
 use bevy::prelude::*;
+use bevy_pmetra::{prelude::*, re_exports::anyhow::Result};
+
+use crate::utils::cad_models::{
+    simple_primitives::exp_nurbs::ExpNurbs,
+    simple_primitives::simple_cube_at_cylinder::SimpleCubeAtCylinder,
+    space_station::{round_cabin_segment::RoundCabinSegment, tower_extension::TowerExtension},
+};
 
 #[derive(Debug, Resource, Reflect, Default)]
 pub struct CadGeneratedModelSpawner {
@@ -15,3 +22,31 @@ pub enum CadGeneratedModelParamsId {
     MultiModels2TowerExtensions,
     ExpNurbsSolid,
 }
+
+impl CadGeneratedModelParamsId {
+    /// Document with the models to spawn.
+    pub fn document(&self) -> Result<CadDocument> {
+        let mut document = CadDocument::default();
+        match self {
+            Self::ExpNurbsSolid => document.add_params(&ExpNurbs::default(), default()),
+            Self::SimplCubeAtCylinder => {
+                document.add_params(&SimpleCubeAtCylinder::default(), default())
+            }
+            Self::TowerExtension => document.add_params(&TowerExtension::default(), default()),
+            Self::RoundCabinSegment => {
+                document.add_params(&RoundCabinSegment::default(), default())
+            }
+            Self::MultiModelsSimplCubeAtCylinderAndTowerExtension => {
+                document.add_params(&SimpleCubeAtCylinder::default(), default())?;
+                document.add_params(&TowerExtension::default(), default())
+            }
+            Self::MultiModels2TowerExtensions => {
+                document.add_params(&TowerExtension::default(), default())?;
+                document.add_params(
+                    &TowerExtension::default(),
+                    Transform::from_translation(Vec3::X * 1.),
+                )
+            }
+        }
+    }
+}
@@ -1,65 +1,184 @@
 use bevy::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::tasks::IoTaskPool;
 use bevy_pmetra::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy_pmetra::re_exports::anyhow;
 use bevy_rapier3d::prelude::*;
 
-use crate::{
-    resources::{CadGeneratedModelParamsId, CadGeneratedModelSpawner},
-    utils::cad_models::{
-        simple_primitives::exp_nurbs::ExpNurbs,
-        simple_primitives::simple_cube_at_cylinder::SimpleCubeAtCylinder,
-        space_station::{round_cabin_segment::RoundCabinSegment, tower_extension::TowerExtension},
-    },
-};
+use crate::resources::CadGeneratedModelSpawner;
+
+#[cfg(not(target_arch = "wasm32"))]
+const EXPORTS_DIR: &str = "exports/temp";
+#[cfg(not(target_arch = "wasm32"))]
+const CAD_DOCUMENT_FILE: &str = "cad_document.ron";
 
 pub fn spawn_cad_model(
-    mut commands: Commands,
     cad_model_spawner: Res<CadGeneratedModelSpawner>,
-    cad_models: Query<Entity, With<CadGeneratedRoot>>,
-    mut spawn_exp_nurbs_solid: MessageWriter<GenerateCadModel<ExpNurbs>>,
-    mut spawn_simple_cube_at_cylinder: MessageWriter<GenerateCadModel<SimpleCubeAtCylinder>>,
-    mut tower_extension: MessageWriter<GenerateCadModel<TowerExtension>>,
-    mut round_cabin_segment: MessageWriter<GenerateCadModel<RoundCabinSegment>>,
+    mut load_document: MessageWriter<LoadCadDocument>,
 ) {
     if !cad_model_spawner.is_changed() {
         return;
     }
-    // delete all existing models...
-    for entity in cad_models.iter() {
-        let Ok(mut ent_commands) = commands.get_entity(entity) else {
-            continue;
-        };
-        ent_commands.despawn();
-    }
-    // fire event to spawn new model...
-    match cad_model_spawner.selected_params {
-        CadGeneratedModelParamsId::ExpNurbsSolid => {
-            spawn_exp_nurbs_solid.write(GenerateCadModel::default());
+    let document = match cad_model_spawner.selected_params.document() {
+        Ok(document) => document,
+        Err(e) => {
+            error!("Could not create document: {:#}", e);
+            return;
         }
-        CadGeneratedModelParamsId::SimplCubeAtCylinder => {
-            spawn_simple_cube_at_cylinder.write(GenerateCadModel::default());
-        }
-        CadGeneratedModelParamsId::TowerExtension => {
-            tower_extension.write(GenerateCadModel::default());
-        }
-        CadGeneratedModelParamsId::RoundCabinSegment => {
-            round_cabin_segment.write(GenerateCadModel::default());
+    };
+    // fire event to replace existing models with the document's...
+    load_document.write(LoadCadDocument {
+        document,
+        remove_existing_models: true,
+    });
+}
+
+/// Save (`F5`) / Load (`F9`) the models to/from the document file.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_load_cad_document(world: &mut World) {
+    let Some(key_input) = world.get_resource::<ButtonInput<KeyCode>>() else {
+        return;
+    };
+    let (is_save, is_load) = (
+        key_input.just_pressed(KeyCode::F5),
+        key_input.just_pressed(KeyCode::F9),
+    );
+    if is_save {
+        let result = CadDocument::from_world(world).and_then(|document| {
+            std::fs::create_dir_all(EXPORTS_DIR)?;
+            document.save(format!("{}/{}", EXPORTS_DIR, CAD_DOCUMENT_FILE))
+        });
+        match result {
+            Ok(_) => info!("Saved document: {}", CAD_DOCUMENT_FILE),
+            Err(e) => error!("Could not save document: {:#}", e),
         }
-        CadGeneratedModelParamsId::MultiModelsSimplCubeAtCylinderAndTowerExtension => {
-            spawn_simple_cube_at_cylinder.write(GenerateCadModel::default());
-            tower_extension.write(GenerateCadModel {
-                remove_existing_models: false,
-                ..Default::default()
-            });
+    } else if is_load {
+        match CadDocument::load(format!("{}/{}", EXPORTS_DIR, CAD_DOCUMENT_FILE)) {
+            Ok(document) => {
+                world.write_message(LoadCadDocument {
+                    document,
+                    remove_existing_models: true,
+                });
+            }
+            Err(e) => error!("Could not load document: {:#}", e),
         }
-        CadGeneratedModelParamsId::MultiModels2TowerExtensions => {
-            tower_extension.write(GenerateCadModel::default());
-            tower_extension.write(GenerateCadModel {
-                transform: Transform::from_translation(Vec3::X * 1.),
-                remove_existing_models: false,
-                ..Default::default()
+    }
+}
+
+/// Export the shells of each model (as placed by its meshes) to a STEP file (`F6`).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_cad_model_step(
+    key_input: Res<ButtonInput<KeyCode>>,
+    cad_generated: Query<&Transform, With<CadGeneratedRoot>>,
+    shells_by_name_entities: Query<(&BelongsToCadGeneratedRoot, &CadShellsByName)>,
+    cad_meshes: Query<
+        (
+            &CadShellName,
+            &CadMeshName,
+            &Transform,
+            &BelongsToCadGeneratedRoot,
+        ),
+        With<CadGeneratedMesh>,
+    >,
+) {
+    if !key_input.just_pressed(KeyCode::F6) {
+        return;
+    }
+    for (idx, (BelongsToCadGeneratedRoot(root_ent), shells_by_name)) in
+        shells_by_name_entities.iter().enumerate()
+    {
+        let Ok(transform) = cad_generated.get(*root_ent) else {
+            continue;
+        };
+        let mut instances = cad_meshes
+            .iter()
+            .filter(|(.., BelongsToCadGeneratedRoot(mesh_root_ent))| mesh_root_ent == root_ent)
+            .map(|(shell_name, CadMeshName(name), mesh_transform, _)| {
+                (name.clone(), shell_name.clone(), *mesh_transform)
+            })
+            .collect::<Vec<_>>();
+        // Sort by the shell name and then the mesh name for a stable output...
+        instances.sort_by(|(name_a, shell_a, _), (name_b, shell_b, _)| {
+            (&shell_a.0, name_a).cmp(&(&shell_b.0, name_b))
+        });
+        let (shells_by_name, transform) = (shells_by_name.clone(), *transform);
+        let path = format!("{}/cad_model_{}.step", EXPORTS_DIR, idx);
+        // Export in a task to avoid blocking on the STEP conversion and file system...
+        IoTaskPool::get()
+            .spawn(async move {
+                let instances = instances.iter().map(|(name, shell_name, mesh_transform)| {
+                    (name.as_str(), shell_name, *mesh_transform)
+                });
+                let result = std::fs::create_dir_all(EXPORTS_DIR)
+                    .map_err(Into::into)
+                    .and_then(|_| shells_by_name.save_step_instances(&path, instances, transform));
+                match result {
+                    Ok(_) => info!("Exported STEP: {}", path),
+                    Err(e) => error!("Could not export STEP: {:#}", e),
+                }
+            })
+            .detach();
+    }
+}
+
+/// Export each model to a binary glTF file (`Ctrl+S`).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_cad_model_gltf(
+    key_input: Res<ButtonInput<KeyCode>>,
+    cad_generated: Query<Entity, With<CadGeneratedRoot>>,
+    mut export_events: MessageWriter<ExportCadModel>,
+) {
+    if !(key_input.pressed(KeyCode::ControlLeft) && key_input.just_pressed(KeyCode::KeyS)) {
+        return;
+    }
+    if let Err(e) = std::fs::create_dir_all(EXPORTS_DIR) {
+        error!("Could not create exports dir: {:#}", e);
+        return;
+    }
+    for (idx, root_ent) in cad_generated.iter().enumerate() {
+        export_events.write(ExportCadModel {
+            root: root_ent,
+            path: Some(format!("{}/cad_model_{}.glb", EXPORTS_DIR, idx).into()),
+        });
+    }
+}
+
+/// Import the models (as parametric models) from the exported binary glTF files (`Ctrl+O`).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn import_cad_model_gltf(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut load_document: MessageWriter<LoadCadDocument>,
+) {
+    if !(key_input.pressed(KeyCode::ControlLeft) && key_input.just_pressed(KeyCode::KeyO)) {
+        return;
+    }
+    match load_exported_glb_document() {
+        Ok(document) => {
+            load_document.write(LoadCadDocument {
+                document,
+                remove_existing_models: true,
             });
         }
+        Err(e) => error!("Could not import glTF: {:#}", e),
+    }
+}
+
+/// Document of the models of all the binary glTF files in the exports dir.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_exported_glb_document() -> anyhow::Result<CadDocument> {
+    let mut paths = std::fs::read_dir(EXPORTS_DIR)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "glb"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut document = CadDocument::default();
+    for path in paths {
+        document
+            .entries
+            .extend(CadDocument::load_glb(path)?.entries);
     }
+    Ok(document)
 }
 
 pub fn add_collider_to_generated_cad_model(
@@ -1,18 +1,26 @@
-use bevy_pmetra::re_exports::{
-    truck_modeling::{builder, Curve, EuclideanSpace, Point3, Rad, Solid, Vector3},
-    truck_shapeops,
+use bevy_pmetra::{
+    prelude::*,
+    re_exports::{
+        anyhow::Result,
+        truck_modeling::{builder, Curve, EuclideanSpace, Point3, Rad, Vector3},
+    },
 };
 
 /// `Cube` minus `Cylinder`.
 ///
-/// Truck shape-ops example.
+/// Truck shape-ops example. Uses [`CadSolid`] to keep the tags after the boolean op.
 ///
 /// Ref: https://github.com/ricosjp/truck/blob/db958f90adf39bfaf8a7d758672f57f99948b2a3/truck-shapeops/examples/punched-cube-shapeops.rs
-pub fn basic_shape_ops_eg() -> Solid {
+pub fn basic_shape_ops_eg() -> Result<CadSolid> {
     let v = builder::vertex(Point3::origin());
     let e = builder::tsweep(&v, Vector3::unit_x());
     let f = builder::tsweep(&e, Vector3::unit_y());
-    let cube = builder::tsweep(&f, Vector3::unit_z());
+    let cube = CadSolid::new(builder::tsweep(&f, Vector3::unit_z()))
+        // Tag the bottom face, it gets punched by the cylinder...
+        .set_tag(
+            CadElementTag::new("BottomFace"),
+            CadElement::Face(f.clone()),
+        )?;
 
     let v = builder::vertex(Point3::new(0.5, 0.25, -0.5));
     let w = builder::rsweep(
@@ -23,10 +31,9 @@ pub fn basic_shape_ops_eg() -> Solid {
         2,
     );
     let f = builder::try_attach_plane(&[w]).unwrap();
-    let mut cylinder = builder::tsweep(&f, Vector3::unit_z() * 2.0);
-    cylinder.not();
-    let and = truck_shapeops::and(&cube, &cylinder, 0.05).unwrap();
-    and.edge_iter().for_each(|edge| {
+    let cylinder = CadSolid::new(builder::tsweep(&f, Vector3::unit_z() * 2.0));
+    let punched_cube = cube.difference(&cylinder)?;
+    punched_cube.solid.edge_iter().for_each(|edge| {
         let mut curve = edge.curve();
         if let Curve::IntersectionCurve(inter) = &curve {
             // if matches! { inter.leader(), Leader::Polyline(_) } {
@@ -37,5 +44,5 @@ pub fn basic_shape_ops_eg() -> Solid {
         edge.set_curve(curve);
     });
 
-    and
+    Ok(punched_cube)
 }
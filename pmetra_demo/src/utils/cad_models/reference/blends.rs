@@ -0,0 +1,30 @@
+use bevy::math::DVec2;
+use bevy_pmetra::{prelude::*, re_exports::anyhow::Result};
+
+/// Box with filleted and chamfered side edges.
+///
+/// Uses [`CadSolid::fillet_edges`] and [`CadSolid::chamfer_edges`] on the side edges tagged by
+/// [`SketchProfile::extrude`]. The blend faces are tagged as `{edge_tag}Fillet`/`{edge_tag}Chamfer`.
+pub fn basic_blends_eg() -> Result<CadSolid> {
+    let cad_shell = Sketch::new("Box", SketchPlane::xy())
+        .add_rect(DVec2::ZERO, DVec2::new(1., 0.6))?
+        .build()?
+        .extrude(0.4)?;
+    let cad_solid = CadSolid::try_from(cad_shell)?
+        .fillet_edges(
+            &[
+                CadElementTag::new("BoxSideEdge0"),
+                CadElementTag::new("BoxSideEdge2"),
+            ],
+            0.1,
+        )?
+        .chamfer_edges(
+            &[
+                CadElementTag::new("BoxSideEdge1"),
+                CadElementTag::new("BoxSideEdge3"),
+            ],
+            0.05,
+        )?;
+
+    Ok(cad_solid)
+}
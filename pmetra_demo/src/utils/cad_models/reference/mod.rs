@@ -1,2 +1,4 @@
 /// Basic shape ops from truck examples.
 pub mod basic_shape_ops;
+/// Fillet/Chamfer of tagged edges.
+pub mod blends;
@@ -3,6 +3,7 @@ use std::str::FromStr;
 use bevy::{math::DVec3, prelude::*};
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
 use bevy_pmetra::{prelude::*, re_exports::anyhow::Result};
+use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 
 use self::nurbs_surface::{
@@ -13,7 +14,7 @@ use self::nurbs_surface::{
 pub mod nurbs_surface;
 
 /// Experimental NURBS Surface Solid.
-#[derive(Debug, Reflect, Component, Clone, InspectorOptions)]
+#[derive(Debug, Reflect, Component, Clone, InspectorOptions, Serialize, Deserialize)]
 #[reflect(InspectorOptions)]
 pub struct ExpNurbs {
     #[inspector(min = 0.1)]
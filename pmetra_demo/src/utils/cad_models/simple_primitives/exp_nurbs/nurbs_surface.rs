@@ -84,7 +84,11 @@ pub fn nurbs_surface_mesh_builder(
 
     let mesh_builder = CadMeshBuilder::new(params.clone(), shell_name.clone())?
         .set_transform(transform)?
-        .set_base_material(Color::from(css::ORANGE).into())?;
+        .set_base_material(Color::from(css::ORANGE).into())?
+        // Smooth shading across the tangent faces of the surface...
+        .set_normal_mode(CadNormalMode::Smooth {
+            crease_angle: 30_f32.to_radians(),
+        })?;
 
     Ok(mesh_builder)
 }
@@ -3,6 +3,7 @@ use std::{f64::consts::PI, str::FromStr};
 use bevy::prelude::*;
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
 use bevy_pmetra::{prelude::*, re_exports::anyhow::Result};
+use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 
 use self::{
@@ -14,7 +15,7 @@ pub mod cube;
 pub mod cylinder;
 
 /// Basic Parametric Station Segment.
-#[derive(Debug, Reflect, Component, Clone, InspectorOptions)]
+#[derive(Debug, Reflect, Component, Clone, InspectorOptions, Serialize, Deserialize)]
 #[reflect(InspectorOptions)]
 pub struct SimpleCubeAtCylinder {
     #[inspector(min = 0.1)]
@@ -84,20 +85,19 @@ impl PmetraModelling for SimpleCubeAtCylinder {
                     cylinder_mesh_builder(self, CadShellName(CadShellIds::Cylinder.to_string()))?,
                 )?;
 
-        let cubes_count = (self.cylinder_radius * 4.).floor() as i32;
-        for i in 0..cubes_count {
-            cad_meshes_lazy_builders_by_cad_shell.add_mesh_builder_with_outlines(
+        let cubes_count = (self.cylinder_radius * 4.).floor().max(0.) as usize;
+        // Cubes around the cylinder share the same mesh...
+        cad_meshes_lazy_builders_by_cad_shell.add_mesh_builder_pattern_with_outlines(
+            CadShellName(CadShellIds::Cube.to_string()),
+            CadMeshIds::Cube.to_string(),
+            cube_mesh_builder(
+                self,
                 CadShellName(CadShellIds::Cube.to_string()),
-                CadMeshIds::Cube.to_string() + &i.to_string(),
-                cube_mesh_builder(
-                    self,
-                    CadShellName(CadShellIds::Cube.to_string()),
-                    shells_by_name,
-                    -(i as f32 * std::f32::consts::TAU / cubes_count as f32
-                        + std::f32::consts::FRAC_PI_8),
-                )?,
-            )?;
-        }
+                shells_by_name,
+                -std::f32::consts::FRAC_PI_8,
+            )?,
+            &CadMeshPattern::circular(Vec3::ZERO, Vec3::Y, -std::f32::consts::TAU, cubes_count),
+        )?;
 
         Ok(cad_meshes_lazy_builders_by_cad_shell)
     }
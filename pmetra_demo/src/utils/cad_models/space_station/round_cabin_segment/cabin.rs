@@ -1,6 +1,10 @@
 use std::f64::consts::FRAC_PI_2;
 
-use bevy::{color::palettes::css, math::DVec3, prelude::*};
+use bevy::{
+    color::palettes::css,
+    math::{DVec2, DVec3},
+    prelude::*,
+};
 use bevy_pmetra::{
     math::get_rotation_from_normals,
     pmetra_core::extensions::face::FaceCadExtension,
@@ -14,12 +18,53 @@ use bevy_pmetra::{
     },
 };
 
-use crate::utils::cad_models::space_station::common::{
-    get_corner_arcs_for_corner_vertices, get_profile_from_corner_arcs, ref_edge_direction_for_wire,
-};
+use crate::utils::cad_models::space_station::common::ref_edge_direction_for_wire;
 
 use super::{CadShellIds, RoundCabinSegment};
 
+/// Validate the params, so that the inner (hole) profile of the walls can be built.
+pub fn validate_cabin_params(params: &RoundCabinSegment) -> Result<(), Vec<ParamIssue>> {
+    let RoundCabinSegment {
+        profile_width,
+        profile_height,
+        profile_corner_radius,
+        profile_thickness,
+        profile_extrude_length,
+        ..
+    } = params;
+    let mut issues = vec![];
+    // Inner profile keeps the corner radius (arcs moved in by the thickness),
+    // so its straight edges need room between the corners...
+    let min_side = 2. * (profile_corner_radius + profile_thickness);
+    if *profile_width <= min_side {
+        issues.push(ParamIssue::new(
+            "profile_width",
+            "should be more than twice the sum of the corner radius and thickness",
+        ));
+    }
+    if *profile_height <= min_side {
+        issues.push(ParamIssue::new(
+            "profile_height",
+            "should be more than twice the sum of the corner radius and thickness",
+        ));
+    }
+    if *profile_thickness <= 0. {
+        issues.push(ParamIssue::new("profile_thickness", "should be positive"));
+    }
+    if *profile_extrude_length <= 0. {
+        issues.push(ParamIssue::new(
+            "profile_extrude_length",
+            "should be positive",
+        ));
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
 pub fn build_cabin_shell(params: &RoundCabinSegment) -> Result<CadShell> {
     let RoundCabinSegment {
         profile_width,
@@ -34,56 +79,26 @@ pub fn build_cabin_shell(params: &RoundCabinSegment) -> Result<CadShell> {
 
     let mut tagged_elements = CadTaggedElements::default();
 
-    // alignment vertices...
-    let a0 = DVec3::ZERO;
-    let a1 = a0 + DVec3::X * profile_width;
-    let a2 = a1 + DVec3::Y * profile_height;
-    let a3 = a2 - DVec3::X * profile_width;
-
-    let (arc0, arc1, arc2, arc3) =
-        get_corner_arcs_for_corner_vertices(a0, a1, a2, a3, profile_corner_radius);
-    tagged_elements.insert(CadElementTag::new("Arc3"), CadElement::Edge(arc3.clone()));
-    // connect all arcs with intermediate wires and create a profile...
-    let profile = get_profile_from_corner_arcs(&arc0, &arc1, &arc2, &arc3)?;
+    // Profile with walls of the thickness (inner corners keep the corner radius)...
+    let profile_sketch = Sketch::new("Profile", SketchPlane::xy())
+        .add_rect(DVec2::ZERO, DVec2::new(profile_width, profile_height))?
+        .fillet_all(profile_corner_radius)?
+        .thicken_with(profile_thickness, SketchOffsetArcs::KeepRadius)?
+        .build()?;
+    let [profile, profile_inner] = profile_sketch.wires.as_slice() else {
+        return Err(anyhow!("Profile should have an outer and an inner wire!"));
+    };
+    // Inner profile in the same orientation as the outer (inverted later for the hole)...
+    let (profile, profile_inner) = (profile.clone(), profile_inner.inverse());
+    // Corner arc at the top left (edges start from the bottom left corner arc)...
+    let arc3 = profile_sketch
+        .tagged_elements
+        .get(&CadElementTag::new("ProfileEdge6"))
+        .ok_or_else(|| anyhow!("Could not find top left corner arc!"))?;
+    tagged_elements.insert(CadElementTag::new("Arc3"), arc3.clone());
     // calc centroid
     let profile_centroid = profile.get_centroid();
 
-    // get inner arcs from outer arcs...
-    let arc0_in = builder::translated(
-        &arc0,
-        DVec3::new(profile_thickness, profile_thickness, 0.)
-            .to_array()
-            .into(),
-    );
-    let arc1_in = builder::translated(
-        &arc1,
-        DVec3::new(-profile_thickness, profile_thickness, 0.)
-            .to_array()
-            .into(),
-    );
-    let arc2_in = builder::translated(
-        &arc2,
-        DVec3::new(-profile_thickness, -profile_thickness, 0.)
-            .to_array()
-            .into(),
-    );
-    let arc3_in = builder::translated(
-        &arc3,
-        DVec3::new(profile_thickness, -profile_thickness, 0.)
-            .to_array()
-            .into(),
-    );
-    // Create inner profile/hole profile from main profile...
-    let profile_inner = get_profile_from_corner_arcs(&arc0_in, &arc1_in, &arc2_in, &arc3_in)?;
-    // calc centroid
-    let profile_inner_centroid = profile_inner.get_centroid();
-    let profile_inner = builder::translated(
-        &profile_inner,
-        (profile_centroid - profile_inner_centroid)
-            .to_array()
-            .into(),
-    );
-
     // Create face from profile with hole profile...
     let mut profile_face =
         builder::try_attach_plane(&[profile.clone()]).with_context(|| "Could not attach plane!")?;
@@ -294,6 +309,13 @@ pub fn build_cabin_mesh(
     // Init cad mesh from mesh stuff...
     let mesh_builder = CadMeshBuilder::new(params.clone(), shell_name)? // builder
         .set_base_material(Color::from(css::RED).into())?
+        // Roof (top face) gets its own material...
+        .set_face_material(
+            CadElementTag::new("TopFace"),
+            Color::from(css::DARK_GRAY).into(),
+        )?
+        // Coarser meshes when far from the camera...
+        .set_lods([(10., 1e-2), (30., 5e-2)])?
         .set_transform(main_mesh_transform)?;
 
     Ok(mesh_builder)
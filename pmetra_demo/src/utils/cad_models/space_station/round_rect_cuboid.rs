@@ -1,17 +1,18 @@
-use bevy::{math::DVec3, prelude::*};
+use std::hash::Hasher;
+
+use bevy::{math::DVec2, prelude::*};
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
 use bevy_pmetra::{
-    pmetra_core::extensions::shell::ShellCadExtension,
+    prelude::{ParamsKey, Sketch, SketchPlane},
     re_exports::{
-        anyhow::{Context, Error, Result},
-        truck_modeling::{builder, Shell, Vector3},
+        anyhow::{Error, Result},
+        truck_modeling::Shell,
     },
 };
-
-use super::common::{get_corner_arcs_for_corner_vertices, get_profile_from_corner_arcs};
+use serde::{Deserialize, Serialize};
 
 /// Rounded Rectangle Cuboid.
-#[derive(Debug, Reflect, Component, Clone, InspectorOptions)]
+#[derive(Debug, Reflect, Component, Clone, InspectorOptions, Serialize, Deserialize)]
 #[reflect(InspectorOptions)]
 pub struct RoundRectCuboid {
     #[inspector(min = 0.2, speed = 0.1)]
@@ -31,6 +32,15 @@ impl RoundRectCuboid {
     }
 }
 
+impl ParamsKey for RoundRectCuboid {
+    fn hash_key<H: Hasher>(&self, state: &mut H) {
+        self.profile_width.hash_key(state);
+        self.profile_height.hash_key(state);
+        self.profile_corner_radius.hash_key(state);
+        self.profile_extrude_length.hash_key(state);
+    }
+}
+
 impl Default for RoundRectCuboid {
     fn default() -> Self {
         Self {
@@ -53,24 +63,13 @@ impl TryFrom<RoundRectCuboid> for Shell {
             profile_extrude_length,
         } = value;
 
-        // Corner alignment vertices...
-        let c0 = DVec3::ZERO;
-        let c1 = c0 + DVec3::X * profile_width;
-        let c2 = c1 + DVec3::Y * profile_height;
-        let c3 = c2 - DVec3::X * profile_width;
-        let (window_arc0, window_arc1, window_arc2, window_arc3) =
-            get_corner_arcs_for_corner_vertices(c0, c1, c2, c3, profile_corner_radius);
-        // Create profile...
-        let profile =
-            get_profile_from_corner_arcs(&window_arc0, &window_arc1, &window_arc2, &window_arc3)?;
-
-        // Create face from profile with hole profile...
-        let profile_face =
-            builder::try_attach_plane(&[profile]).with_context(|| "Could not attach plane!")?;
-        // extrude profile into solid/cuboid structure...
-        let cuboid = builder::tsweep(&profile_face, Vector3::unit_z() * profile_extrude_length);
-
-        let shell = Shell::try_from_solid(&cuboid)?;
+        // Sketch rounded rectangle profile...
+        let profile = Sketch::new("RoundRect", SketchPlane::xy())
+            .add_rect(DVec2::ZERO, DVec2::new(profile_width, profile_height))?
+            .fillet_all(profile_corner_radius)?
+            .build()?;
+        // extrude profile into cuboid shell...
+        let shell = profile.extrude(profile_extrude_length)?.shell;
 
         Ok(shell)
     }
@@ -1,13 +1,8 @@
-use bevy::{color::palettes::css, math::DVec3, prelude::*};
+use bevy::{color::palettes::css, math::DVec2, prelude::*};
 use bevy_pmetra::{
-    pmetra_core::extensions::shell::ShellCadExtension,
     prelude::*,
-    re_exports::{
-        anyhow::{Context, Result},
-        truck_modeling::{builder, Point3, Shell, Vector3, Vertex, Wire},
-    },
+    re_exports::{anyhow::Result, truck_modeling::Shell},
 };
-use itertools::Itertools;
 
 use super::TowerExtension;
 
@@ -34,6 +29,15 @@ pub fn build_straight_beam_shell(params: &TowerExtension) -> Result<CadShell> {
     })
 }
 
+/// Key of the params the straight beam shell depends on.
+pub fn straight_beam_shell_key(params: &TowerExtension) -> [u64; 3] {
+    [
+        params.tower_length.to_bits(),
+        params.straight_beam_l_sect_side_len.to_bits(),
+        params.straight_beam_l_sect_thickness.to_bits(),
+    ]
+}
+
 /// Cross Beam Shell Builder.
 pub fn build_cross_beam_shell(params: &TowerExtension) -> Result<CadShell> {
     let TowerExtension {
@@ -47,7 +51,7 @@ pub fn build_cross_beam_shell(params: &TowerExtension) -> Result<CadShell> {
     let shell = l_beam_shell(
         cross_beam_l_sect_side_len,
         cross_beam_l_sect_thickness,
-        params.cross_beam_length(),
+        params.cross_beam_length()?,
     )?;
 
     Ok(CadShell {
@@ -56,6 +60,16 @@ pub fn build_cross_beam_shell(params: &TowerExtension) -> Result<CadShell> {
     })
 }
 
+/// Key of the params the cross beam shell depends on.
+pub fn cross_beam_shell_key(params: &TowerExtension) -> [u64; 4] {
+    [
+        params.cross_beam_l_sect_side_len.to_bits(),
+        params.cross_beam_l_sect_thickness.to_bits(),
+        params.cross_segment_length().to_bits(),
+        params.enclosure_inner_width().to_bits(),
+    ]
+}
+
 /// Get L-shaped beam shell.
 ///
 /// # Args
@@ -83,32 +97,21 @@ pub fn build_cross_beam_shell(params: &TowerExtension) -> Result<CadShell> {
 /// ```
 ///
 pub fn l_beam_shell(l_side_length: f64, thickness: f64, length: f64) -> Result<Shell> {
-    // Create points for L-shaped cross section...
-    let o = DVec3::ZERO;
-    let b = DVec3::new(l_side_length, 0., 0.);
-    let c = b + DVec3::new(0., 0., thickness);
-    let d = DVec3::new(thickness, 0., thickness);
-    let a = DVec3::new(0., 0., l_side_length);
-    let e = a + DVec3::new(thickness, 0., 0.);
-    let points = [o, b, c, d, e, a];
-    let vertices = points
-        .iter()
-        .map(|p| Vertex::new(Point3::from(p.to_array())))
-        .collect::<Vec<_>>();
-    let mut wire = Wire::new();
-    for (v0, v1) in vertices.iter().circular_tuple_windows() {
-        let edge = builder::line(v0, v1);
-        wire.push_back(edge);
-    }
-    wire.invert();
-    debug_assert!(wire.is_closed());
-    // Attach plane and extrude into shell...
-    let face =
-        builder::try_attach_plane(&[wire]).with_context(|| "Could not attach plane to wire")?;
-    let solid = builder::tsweep(&face, Vector3::from((DVec3::Y * length).to_array()));
-    let shell = Shell::try_from_solid(&solid)?;
-
-    Ok(shell)
+    // Sketch L-shaped cross section on the ZX plane (facing +Y), ie: points as (z, x)...
+    let profile = Sketch::new("LBeam", SketchPlane::zx())
+        .add_polygon(&[
+            DVec2::ZERO,                          // o
+            DVec2::new(0., l_side_length),        // b
+            DVec2::new(thickness, l_side_length), // c
+            DVec2::new(thickness, thickness),     // d
+            DVec2::new(l_side_length, thickness), // e
+            DVec2::new(l_side_length, 0.),        // a
+        ])?
+        .build()?;
+    // Extrude into shell...
+    let cad_shell = profile.extrude(length)?;
+
+    Ok(cad_shell.shell)
 }
 
 pub fn straight_beam_mesh_builder(
@@ -14,6 +14,15 @@ use itertools::Itertools;
 
 use super::{CadShellIds, TowerExtension};
 
+/// Key of the params the cuboid enclosure shell depends on.
+pub fn cuboid_enclosure_shell_key(params: &TowerExtension) -> [u64; 3] {
+    [
+        params.tower_length.to_bits(),
+        params.enclosure_profile_width.to_bits(),
+        params.enclosure_profile_depth.to_bits(),
+    ]
+}
+
 /// Straight Beam Shell Builder.
 ///
 /// The beam has a L-shaped cross section.
@@ -1,17 +1,19 @@
 use std::str::FromStr;
 
-use bevy::prelude::*;
+use bevy::{math::DVec2, prelude::*};
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
 use bevy_pmetra::{prelude::*, re_exports::anyhow::Result};
+use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 
 use self::{
     beams::{
         build_cross_beam_shell, build_straight_beam_shell, cross_beam_mesh_builder,
-        straight_beam_mesh_builder,
+        cross_beam_shell_key, straight_beam_mesh_builder, straight_beam_shell_key,
     },
     cuboid_enclosure::{
         build_cuboid_enclosure_shell, build_tower_length_slider, cuboid_enclosure_mesh_builder,
+        cuboid_enclosure_shell_key,
     },
 };
 
@@ -19,7 +21,7 @@ pub mod beams;
 pub mod cuboid_enclosure;
 
 /// Basic Parametric Station Segment.
-#[derive(Debug, Reflect, Component, Clone, InspectorOptions)]
+#[derive(Debug, Reflect, Component, Clone, InspectorOptions, Serialize, Deserialize)]
 #[reflect(InspectorOptions)]
 pub struct TowerExtension {
     #[inspector(min = 0.1)]
@@ -69,28 +71,61 @@ impl TowerExtension {
         self.enclosure_profile_width - self.straight_beam_l_sect_thickness * 2.
     }
 
-    pub fn cross_beam_hypotenuse(&self) -> f64 {
-        (self.cross_segment_length().powi(2) + self.enclosure_inner_width().powi(2)).sqrt()
-    }
+    /// Solve the cross beam layout in a cross segment via sketch constraints.
+    ///
+    /// The beam (with width as l-section side) spans the diagonal of the segment,
+    /// touching its opposite corners:
+    ///
+    /// ```ignore
+    /// y
+    /// |  q ---- c
+    /// | /      /
+    /// |/      /
+    /// o ---- /--- x
+    ///
+    /// oc : diagonal of segment (inner width x segment length)
+    /// oq : beam edge (length of beam)
+    /// qc : l-section side (perpendicular to oq)
+    /// ```
+    ///
+    /// Returns the solved constraints with the beam edge line.
+    pub fn cross_beam_sketch(&self) -> Result<(SketchConstraints, SketchLine)> {
+        let (width, length, side) = (
+            self.enclosure_inner_width(),
+            self.cross_segment_length(),
+            self.cross_beam_l_sect_side_len,
+        );
+        let mut constraints = SketchConstraints::default();
+        let o = constraints.add_point(DVec2::ZERO);
+        let c = constraints.add_point(DVec2::new(width, length));
+        // Guess `q` on the left of the diagonal...
+        let q = constraints.add_point(DVec2::new(width - side, length));
+        let beam_edge = SketchLine(o, q);
+        let constraints = constraints
+            .add_constraint(SketchConstraint::Fixed(o, DVec2::ZERO))?
+            .add_constraint(SketchConstraint::HorizontalDistance(o, c, width))?
+            .add_constraint(SketchConstraint::VerticalDistance(o, c, length))?
+            .add_constraint(SketchConstraint::Perpendicular(beam_edge, SketchLine(q, c)))?
+            .add_constraint(SketchConstraint::Distance(q, c, side))?
+            .solve()?;
 
-    pub fn cross_beam_length(&self) -> f64 {
-        (self.cross_beam_hypotenuse().powi(2) - self.cross_beam_l_sect_side_len.powi(2)).sqrt()
+        Ok((constraints, beam_edge))
     }
 
-    pub fn cross_beam_hyp_angle_z(&self) -> f64 {
-        std::f64::consts::FRAC_PI_2
-            - (self.cross_segment_length() / self.enclosure_inner_width()).atan()
+    pub fn cross_beam_length(&self) -> Result<f64> {
+        let (constraints, beam_edge) = self.cross_beam_sketch()?;
+        Ok(constraints.line_length(beam_edge))
     }
 
-    pub fn cross_beam_angle_z(&self) -> f64 {
-        self.cross_beam_hyp_angle_z()
-            - (self.cross_beam_l_sect_side_len / self.cross_beam_length()).atan()
+    /// Angle of the cross beam with the tower (y) axis.
+    pub fn cross_beam_angle_z(&self) -> Result<f64> {
+        let (constraints, beam_edge) = self.cross_beam_sketch()?;
+        let direction = constraints.line_direction(beam_edge);
+        Ok(direction.x.atan2(direction.y))
     }
 
-    pub fn cross_beam_y_offset(&self) -> f64 {
-        let cross_beam_bot_angle =
-            std::f64::consts::PI - self.cross_beam_angle_z() - std::f64::consts::FRAC_PI_2;
-        self.cross_beam_l_sect_side_len * cross_beam_bot_angle.cos()
+    pub fn cross_beam_y_offset(&self) -> Result<f64> {
+        Ok(self.cross_beam_l_sect_side_len * self.cross_beam_angle_z()?.sin())
     }
 }
 
@@ -127,6 +162,19 @@ impl PmetraCad for TowerExtension {
             .add_shell_builder(
                 CadShellName(CadShellIds::CrossBeam.to_string()),
                 build_cross_beam_shell,
+            )?
+            // Only rebuild the shells whose params changed...
+            .set_shell_key(
+                CadShellName(CadShellIds::CuboidEnclosure.to_string()),
+                cuboid_enclosure_shell_key,
+            )?
+            .set_shell_key(
+                CadShellName(CadShellIds::StraightBeam.to_string()),
+                straight_beam_shell_key,
+            )?
+            .set_shell_key(
+                CadShellName(CadShellIds::CrossBeam.to_string()),
+                cross_beam_shell_key,
             )?;
 
         Ok(builders)
@@ -202,7 +250,7 @@ impl PmetraModelling for TowerExtension {
             let org_transform = Transform::from_translation(Vec3::new(
                 -self.enclosure_profile_width as f32 / 2.
                     + self.straight_beam_l_sect_thickness as f32,
-                self.cross_beam_y_offset() as f32,
+                self.cross_beam_y_offset()? as f32,
                 self.enclosure_profile_depth as f32 / 2.
                     - self.straight_beam_l_sect_thickness as f32,
             ))
@@ -212,7 +260,7 @@ impl PmetraModelling for TowerExtension {
                 std::f32::consts::FRAC_PI_2,
                 0.,
             ));
-            let cross_beam_angle_z = self.cross_beam_angle_z();
+            let cross_beam_angle_z = self.cross_beam_angle_z()?;
             let num_of_cross_segments = self.num_of_cross_segments();
             let cross_segment_length = self.cross_segment_length();
             for jdx in 0..num_of_cross_segments {
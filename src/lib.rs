@@ -3,6 +3,152 @@
 //! The `bevy_pmetra` crate is just a container crate that makes it easier to consume the sub-crates:
 //!
 //! - `pmetra_internal`
+//! - `pmetra_macros`
 
 // Just re-export the modules of internal crate for now...
 pub use pmetra_internal::*;
+
+// Allows testing the derive macros (which refer to `::bevy_pmetra`) within this crate...
+extern crate self as bevy_pmetra;
+
+mod test {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::{
+        prelude::*,
+        re_exports::{
+            anyhow::Result,
+            bevy::{math::DVec3, prelude::*},
+            truck_modeling::{builder, Point3, Vector3},
+        },
+    };
+
+    #[test]
+    pub fn test_derive_pmetra_traits() {
+        #[derive(Debug, Clone, Default, PmetraCad, PmetraModelling, PmetraInteractions)]
+        #[pmetra(shell(name = "Cube", builder = cube_shell, key = cube_shell_key))]
+        #[pmetra(mesh(name = "Cube", shell = "Cube", builder = cube_mesh, outlines))]
+        #[pmetra(mesh(name = "CubeCopy", shell = "Cube", builder = cube_mesh))]
+        #[pmetra(slider(name = "SideLength", builder = cube_slider))]
+        #[pmetra(slider(name = "Offset", builder = cube_slider))]
+        #[pmetra(slider(name = "Custom", builder = cube_slider, on_transform = on_custom, tooltip = custom_tooltip))]
+        #[pmetra(validate = validate_cube, cache_key)]
+        pub struct DeriveCube {
+            #[pmetra(slider = "SideLength", axis = x, min = 0.5, max = 2.)]
+            pub side_length: f64,
+            #[pmetra(slider = "Offset", axis = xyz, sensitivity = 0.5)]
+            pub offset: DVec3,
+            pub custom_count: u32,
+        }
+
+        fn cube_shell(params: &DeriveCube) -> Result<CadShell> {
+            let v = builder::vertex(Point3::new(0., 0., 0.));
+            let e = builder::tsweep(&v, Vector3::unit_x() * params.side_length);
+            let f = builder::tsweep(&e, Vector3::unit_y() * params.side_length);
+            let solid = builder::tsweep(&f, Vector3::unit_z() * params.side_length);
+            Ok(CadShell {
+                shell: solid.into_boundaries().pop().unwrap(),
+                ..default()
+            })
+        }
+
+        fn cube_shell_key(params: &DeriveCube) -> u64 {
+            params.side_length.to_bits()
+        }
+
+        fn cube_mesh(
+            params: &DeriveCube,
+            shell_name: CadShellName,
+        ) -> Result<CadMeshBuilder<DeriveCube>> {
+            CadMeshBuilder::new(params.clone(), shell_name)
+        }
+
+        fn cube_slider(
+            _params: &DeriveCube,
+            _shells_by_name: &CadShellsByName,
+        ) -> Result<CadSlider> {
+            Ok(CadSlider::default())
+        }
+
+        fn on_custom(
+            params: &mut DeriveCube,
+            _prev_transform: Transform,
+            _new_transform: Transform,
+        ) {
+            params.custom_count += 1;
+        }
+
+        fn custom_tooltip(params: &DeriveCube) -> Result<Option<String>> {
+            Ok(Some(format!("custom_count: {}", params.custom_count)))
+        }
+
+        fn validate_cube(params: &DeriveCube) -> Result<(), Vec<ParamIssue>> {
+            if params.side_length <= 0. {
+                return Err(vec![ParamIssue::new("side_length", "should be positive")]);
+            }
+            Ok(())
+        }
+
+        let mut cube = DeriveCube {
+            side_length: 1.,
+            ..default()
+        };
+        // Shells (with keys)...
+        let shells_builders = cube.shells_builders().unwrap();
+        let shell_builder = &shells_builders.builders[&CadShellName("Cube".into())];
+        assert!(shell_builder.shell_key.is_some());
+        let shells_by_name = shells_builders.build_shells().unwrap();
+        assert_eq!(shells_by_name.len(), 1);
+        // Meshes...
+        let meshes_builders = cube.meshes_builders_by_shell(&shells_by_name).unwrap();
+        let meshes_builder = &meshes_builders.meshes_builders[&CadShellName("Cube".into())];
+        assert!(meshes_builder.mesh_builders[&CadMeshName("Cube".into())].build_outlines);
+        assert!(!meshes_builder.mesh_builders[&CadMeshName("CubeCopy".into())].build_outlines);
+        // Sliders...
+        assert_eq!(cube.sliders(&shells_by_name).unwrap().len(), 3);
+        let prev_transform = Transform::default();
+        cube.on_slider_transform(
+            CadSliderName("SideLength".into()),
+            prev_transform,
+            Transform::from_translation(Vec3::X * 5.),
+        );
+        assert_eq!(cube.side_length, 2.);
+        assert_eq!(
+            cube.on_slider_tooltip(CadSliderName("SideLength".into()))
+                .unwrap(),
+            Some("side_length : 2.000".into())
+        );
+        cube.on_slider_transform(
+            CadSliderName("Offset".into()),
+            prev_transform,
+            Transform::from_xyz(1., 2., 4.),
+        );
+        assert_eq!(cube.offset, DVec3::new(0.5, 1., 2.));
+        cube.on_slider_transform(
+            CadSliderName("Custom".into()),
+            prev_transform,
+            Transform::from_xyz(1., 0., 0.),
+        );
+        assert_eq!(
+            cube.on_slider_tooltip(CadSliderName("Custom".into()))
+                .unwrap(),
+            Some("custom_count: 1".into())
+        );
+        assert_eq!(
+            cube.on_slider_tooltip(CadSliderName("Unknown".into()))
+                .unwrap(),
+            None
+        );
+        // Cache key (of all the fields)...
+        let key = cube.params_key();
+        assert!(key.is_some());
+        assert_eq!(cube.clone().params_key(), key);
+        cube.custom_count += 1;
+        assert_ne!(cube.params_key(), key);
+        // Validation...
+        assert!(cube.validate().is_ok());
+        cube.side_length = 0.;
+        assert!(cube.validate().is_err());
+    }
+}